@@ -0,0 +1,482 @@
+# Deutsche Texte
+
+## Start und Beenden
+starting = RAM-Lavalampe wird gestartet...
+expected-frames = Erwartete Anzahl Frames: { $count }
+controls = Steuerung: Strg + Pfeil hoch = Größer, Strg + Pfeil runter = Kleiner, Strg + P = Nächstes Profil, Esc = Beenden
+monitoring-started = RAM-Überwachung gestartet
+shutting-down = Wird beendet...
+
+## Assets
+asset-not-found = >>> FEHLER: Asset-Datei nicht gefunden: { $file }
+asset-loading = >>> Lade: { $path }
+asset-decode-failed = FEHLER: { $path } konnte nicht dekodiert werden: { $error }
+asset-open-failed = FEHLER: { $path } kann nicht geöffnet werden: { $error }
+asset-wrong-height = FEHLER: Falsche Höhe! { $height } statt mindestens { $expected }
+asset-wrong-width = FEHLER: Breite { $width } ist nicht durch die Framegröße { $frame } teilbar
+asset-loaded = ✓ { $file } erfolgreich geladen
+asset-load-failed = ✗ { $file } konnte nicht geladen werden
+asset-fallback = Versuche Grün als Ersatz...
+
+## Lavafarben
+color-green = Grüne
+color-yellow = Gelbe
+color-orange = Orange
+color-red = Rote
+switching-lava = === Wechsel zu { $color } Lava ({ $percent }% { $metric } belegt) ===
+boiling-over = === Die Lava kocht über ({ $percent }% { $metric } belegt) ===
+falling-asleep = === Die Lampe schläft ein ({ $percent }% { $metric } belegt) ===
+
+## Änderungen zur Laufzeit
+scaling-up = Fenster wird vergrößert auf { $size }
+scaling-down = Fenster wird verkleinert auf { $size }
+scaling-to = Fenstergröße wird auf { $size } gesetzt
+docking-to = Fenster wird nach { $corner } verschoben
+switching-theme = Wechsel zum Theme { $theme }
+switching-metric = Wechsel zur Metrik { $metric }
+sampling-every = Messung alle { $ms } ms
+monitoring-paused = Überwachung pausiert
+monitoring-resumed = Überwachung fortgesetzt
+theme-classic = Klassisch
+
+## Darstellung
+resize-failed = Oberfläche konnte nicht angepasst werden: { $error }
+render-failed = pixels.render() fehlgeschlagen: { $error }
+scale-factor-changed = Anzeigeskalierung auf { $percent }% geändert
+monitors-changed = Monitore haben sich geändert ({ $count } angeschlossen)
+window-moved-to-screen = Der Bildschirm der Lampe ist weg, verschoben nach { $name }
+
+## Konfiguration
+config-loaded = >>> Konfiguration geladen: { $path }
+config-problems = >>> FEHLER: { $count } Problem(e) in der Konfiguration { $path }:
+config-position = Zeile { $line }, Spalte { $column }
+config-using-defaults = Ungültige Werte wurden durch ihre Standardwerte ersetzt
+config-unknown-key = unbekannter Schlüssel "{ $key }"
+config-interval-too-short = Messintervall { $value } ms ist zu kurz, mindestens { $min } ms verwenden
+config-max-fps-range = max_fps muss zwischen 1 und { $max } liegen, nicht { $value }
+config-theme-not-found = Theme "{ $theme }" in keinem assets/themes/-Ordner gefunden
+config-unknown-language = nicht unterstützte Sprache "{ $language }" (verfügbar: en, de)
+unknown-language = Unbekannte Sprache "{ $language }", es bleibt bei { $current }
+config-threshold-range = Schwellwert { $value } muss zwischen 0 und 100 liegen
+config-threshold-overlap = Schwellwerte überlappen: { $upper } ({ $upper_value }) muss über { $lower } ({ $lower_value }) liegen
+config-boiling-range = thresholds.boiling muss über red ({ $red }) und unter 100 liegen, nicht { $value }
+config-idle-range = thresholds.idle muss über 0 und höchstens yellow ({ $yellow }) sein, nicht { $value }
+config-speed-range = speed: fastest_ms ({ $fastest }) muss mindestens { $min } und kleiner als slowest_ms ({ $slowest }) sein
+config-trend-samples-range = speed: trend_samples muss zwischen 2 und { $max } liegen, nicht { $value }
+config-tier-speed-min = { $key } muss mindestens { $min } sein, nicht { $value }
+config-unknown-sheet = assets.sheets: unbekanntes Sheet "{ $name }" (green, yellow, orange, red, boiling oder sleeping)
+config-sheet-not-found = Sheet { $path } nicht gefunden
+config-log-path-is-dir = Logdatei { $path } ist ein Verzeichnis
+config-log-path-bad-parent = Der Ordner der Logdatei { $path } ist eine Datei
+config-log-size-zero = max_size_kb muss größer als 0 sein
+config-keys-conflict = "{ $binding }" ist mehreren Aktionen zugeordnet; es gelten die Standardtasten
+config-predict-horizon-zero = alerts.predict_horizon_min muss größer als 0 sein
+config-predict-window-short = alerts.predict_window_s muss mindestens { $min } sein, ist aber { $value }
+config-indicator-bar-corner = ein Balken braucht eine Kante als Position (top, bottom, left oder right)
+config-click-no-command = click.action = "command" braucht click.command
+keys-unknown = unbekannte Taste "{ $key }" (z. B. "ctrl+up", "escape", "f5", "page-down")
+config-written = >>> Konfiguration gespeichert unter { $path }
+config-write-failed = >>> FEHLER: Konfiguration konnte nicht gespeichert werden: { $error }
+config-no-dir = Konfigurationsverzeichnis konnte nicht ermittelt werden
+autostart-failed = >>> FEHLER: Autostart-Eintrag konnte nicht aktualisiert werden: { $error }
+
+## Ersteinrichtung
+onboarding-welcome = === Willkommen bei der RAM-Lavalampe! ===
+onboarding-intro = Keine Konfiguration gefunden, richten wir kurz das Wichtigste ein (Enter übernimmt den Standardwert).
+onboarding-no-terminal = >>> Erster Start ohne Terminal, schreibe Standardkonfiguration
+onboarding-size = Fenstergröße
+onboarding-corner = Bildschirmecke
+onboarding-metric = Was soll die Lampe anzeigen?
+onboarding-autostart = Nach dem Login automatisch starten?
+onboarding-default = (Standard)
+onboarding-enter-number = Bitte eine Zahl zwischen 1 und { $max } eingeben
+onboarding-answer-yes-no = Bitte mit j oder n antworten
+onboarding-yes-no = j/N
+onboarding-yes-no-default-yes = J/n
+corner-none = dem Fenstermanager überlassen
+corner-top-left = oben links
+corner-top-right = oben rechts
+corner-bottom-left = unten links
+corner-bottom-right = unten rechts
+
+## Tray-Menü
+tray-title = RAM-Lavalampe
+tray-started = Tray-Symbol gestartet
+tray-unavailable = >>> Tray-Symbol nicht verfügbar: { $error }
+tray-tooltip = { $metric }: { $percent }%
+tray-size = Größe
+tray-theme = Theme
+tray-metric = Metrik
+tray-sampling-interval = Messintervall
+tray-interval-custom = Eigenes ({ $interval })
+tray-pause = Überwachung pausieren
+tray-show = Lampe zeigen
+tray-hide = Lampe verstecken
+tray-quit = Beenden
+
+## Logdatei
+log-file = >>> Protokolliere nach { $path }
+log-open-failed = >>> FEHLER: Logdatei { $path } kann nicht geöffnet werden: { $error }
+log-rotate-failed = >>> FEHLER: Logdatei { $path } kann nicht rotiert werden: { $error }
+
+## Absturzberichte
+crash-report-written = >>> Die Lampe ist abgestürzt. Ein Absturzbericht wurde unter { $path } gespeichert
+crash-report-failed = >>> Die Lampe ist abgestürzt, der Absturzbericht konnte nicht geschrieben werden: { $error }
+crash-notification-title = RAM-Lavalampe abgestürzt
+crash-notification-body = Ein Absturzbericht wurde unter { $path } gespeichert
+
+## Umgebungsvariablen
+config-env-override = >>> Konfiguration überschrieben: { $name }={ $value }
+config-env-problems = >>> FEHLER: { $count } Problem(e) in LAVALAMPE_*-Umgebungsvariablen:
+
+## Profile
+profile-default = Standard
+profile-none-defined = In der Konfiguration sind keine Profile definiert
+profile-not-found = Profil "{ $profile }" ist nicht definiert
+profile-invalid = Profil "{ $profile }" ist ungültig: { $error }
+switching-profile = Wechsel zum Profil { $profile }
+tray-profile = Profil
+
+## Live-Neuladen
+config-reloaded = >>> Konfiguration neu geladen: { $path }
+config-read-failed = >>> FEHLER: Konfiguration { $path } konnte nicht gelesen werden: { $error }
+config-read-defaults = Start mit den Standardeinstellungen, die Datei bleibt unverändert
+config-reload-read-failed = >>> FEHLER: Konfiguration { $path } konnte nicht neu gelesen werden: { $error }
+config-reload-kept = Die aktuellen Einstellungen bleiben aktiv
+sighup-received = >>> SIGHUP empfangen, Konfiguration wird neu geladen
+sighup-failed = >>> FEHLER: SIGHUP-Handler konnte nicht installiert werden: { $error }
+
+## Skripte
+config-script-not-found = Skript { $path } nicht gefunden
+script-loaded = >>> Skript geladen: { $path }
+script-load-failed = >>> FEHLER: Skript { $path } konnte nicht geladen werden: { $error }
+script-failed = >>> FEHLER: Skript { $path } fehlgeschlagen, Standardzuordnung wird verwendet: { $error }
+script-no-function = fn sample(percent, metric, hour) fehlt
+script-bad-result = sample() muss (), eine Zahl, einen Stufennamen oder eine Map mit tier/percent/frame_ms zurückgeben
+script-unknown-tier = unbekannte Stufe "{ $tier }" (green, yellow, orange oder red verwenden)
+
+## Messquellen und Plugins
+metric-ram = RAM
+metric-swap = Swap
+metric-cpu = CPU
+metric-plugin = Plugin
+metric-stdin = stdin
+metric-audio = Audio
+metric-ping = Ping
+metric-vram = VRAM
+metric-zfs-arc = ZFS-ARC
+metric-load = Systemlast
+metric-smart = SMART
+metric-cpu-freq = CPU-Takt
+metric-throttling = Drosselung
+metric-fan = Lüfter
+metric-fds = Dateideskriptoren
+metric-hugepages = Hugepages
+metric-disk = Datenträger-E/A
+metric-unavailable = >>> FEHLER: { $metric } liefert keinen Wert: { $error }
+plugin-loaded = >>> Plugin "{ $name }" geladen aus { $path }
+plugin-load-failed = >>> FEHLER: Plugin { $path } konnte nicht geladen werden: { $error }
+plugin-duplicate = >>> FEHLER: Plugin "{ $name }" in { $path } ist bereits geladen, wird übersprungen
+plugin-no-info = das Plugin hat keine Beschreibung geliefert
+plugin-wrong-abi = Plugin-ABI-Version { $found } wird nicht unterstützt (erwartet { $expected })
+plugin-not-set = Messgröße "plugin" braucht metrics.plugin = "<Name>"
+plugin-not-found = kein Plugin namens "{ $name }" geladen
+plugin-no-value = Plugin "{ $name }" hat keinen Wert geliefert
+config-stdin-max = stdin_max muss größer als 0 sein, ist { $value }
+stdin-waiting = warte auf den ersten Wert auf stdin
+stdin-closed = stdin wurde geschlossen, bevor ein Wert ankam
+stdin-bad-line = >>> FEHLER: stdin-Zeile ist keine Zahl und wird ignoriert: { $line }
+stdin-eof = >>> stdin geschlossen, der letzte Wert bleibt stehen
+
+## Kommandozeile
+cli-usage = Aufruf: ram-lavalampe [--metric <Name>] [--watch-process <Name|PID>] [--assets-dir <Verz.>] [--widget] [--output <Format>] | ram-lavalampe bench [--frames <n>] | ram-lavalampe doctor | ram-lavalampe status [--format tmux] | ram-lavalampe pack <Verz.> [-o <Datei>] [--size <px>] | ram-lavalampe --extract-assets <Verz.>
+cli-options = Optionen:
+cli-help-metric = --metric <Name>   Anzuzeigende Messgröße, überschreibt die Konfiguration ({ $metrics })
+cli-help-extract-assets = --extract-assets <Verz.>   Eingebaute Sprite-Sheets nach <Verz.> schreiben, als Ausgangspunkt für eigene Themes
+cli-help-output = --output <Format>   Jedes Sample für eine Statusleiste ausgeben, statt die Lampe zu zeigen ({ $formats })
+cli-help-watch-process = --watch-process <Name|PID>   Zeigt den RAM dieses Prozesses statt des ganzen Systems
+cli-help-widget = --widget          Desktop-Widget: ohne Rahmen, immer im Vordergrund, nicht in der Taskleiste, auf jeder Arbeitsfläche, transparent, in einer Ecke angedockt, überall greifbar zum Verschieben
+cli-help-assets-dir = --assets-dir <Verz.>   Sprite-Sheets und Themes zuerst in <Verz.> suchen (mehrfach möglich)
+cli-help-help = -h, --help        Diese Hilfe anzeigen
+cli-help-version = -V, --version     Version anzeigen
+cli-commands = Befehle:
+cli-help-doctor = doctor   Zeigt, wo Config, Sprite-Sheets und Themes gesucht werden
+cli-help-pack = pack <Verz.> [-o <Datei>] [--size <px>]   Die nummerierten PNGs in <Verz.> zu einem Sprite-Sheet (Standard { $output }) mit <px>×<px> großen Frames (Standard 128) zusammenfügen
+cli-help-status = status [--format <Format>]   Den aktuellen Wert einmal ausgeben und beenden, z. B. für tmux ({ $formats })
+cli-help-bench = bench [--frames <n>]   <n> Frames (Standard { $frames }) pro Fenstergröße ohne Fenster rendern und Zeiten ausgeben
+cli-bad-number = { $flag } braucht eine positive Zahl, nicht "{ $value }"
+cli-example = Beispiel: my-exporter | ram-lavalampe --metric stdin
+cli-error = Fehler: { $error }
+cli-missing-value = { $flag } braucht einen Wert
+cli-unknown-metric = unbekannte Messgröße "{ $metric }" (verfügbar: { $available })
+cli-unknown-output = unbekanntes Ausgabeformat "{ $format }" (verfügbar: { $available })
+cli-unknown-argument = unbekanntes Argument "{ $argument }"
+
+## Audio
+config-audio-floor = audio_floor_db muss unter 0 liegen, ist { $value }
+audio-not-built = dieser Build hat keine Audio-Unterstützung (mit --features audio neu bauen)
+audio-no-device = kein Audio-Eingabegerät gefunden
+audio-device-not-found = Audiogerät "{ $device }" nicht gefunden
+audio-unsupported-format = nicht unterstütztes Sample-Format { $format }
+audio-opened = >>> Höre auf { $device } ({ $rate } Hz)
+audio-stream-error = >>> FEHLER: Audioeingang fehlgeschlagen: { $error }
+
+## Ping
+config-ping-max = ping_max_ms muss größer als 0 sein, ist { $value }
+config-ping-host = "{ $host }" ist kein gültiger Host
+ping-waiting = warte auf die erste Antwort von { $host }
+ping-failed = ping fehlgeschlagen: { $error }
+
+## Datenträger-I/O
+config-disk-max = disk_max_mb_s muss größer als 0 sein, ist { $value }
+config-process-budget = process_budget_mb muss größer als 0 sein, ist { $value }
+disk-stats-failed = /proc/diskstats konnte nicht gelesen werden (nur Linux): { $error }
+disk-not-found = kein Blockgerät "{ $device }" in /proc/diskstats
+disk-none = keine Datenträger in /proc/diskstats gefunden
+
+## VRAM
+vram-no-gpu = keine GPU { $gpu } mit VRAM-Informationen gefunden (NVIDIA-Treiber oder AMD amdgpu nötig)
+vram-read-failed = VRAM-Belegung konnte nicht gelesen werden: { $error }
+
+## ZFS
+zfs-not-loaded = ZFS ist nicht geladen (kein /proc/spl/kstat/zfs/arcstats)
+
+## Hugepages
+hugepages-read-failed = Hugepage-Zähler konnten nicht gelesen werden: { $error }
+hugepages-none = keine Hugepages eingerichtet (vm.nr_hugepages ist 0)
+
+## NUMA
+config-numa-node = NUMA-Knoten { $node } existiert nicht (verfügbar: { $available })
+numa-node-not-found = NUMA-Knoten { $node } nicht gefunden
+
+## Lastdurchschnitt
+config-load-minutes = load_minutes muss 1, 5 oder 15 sein, ist { $value }
+
+## Dateideskriptoren
+fds-read-failed = Anzahl der Dateideskriptoren konnte nicht gelesen werden: { $error }
+fds-no-permission = keine Berechtigung, die Dateideskriptoren von { $process } zu lesen
+process-not-found = kein Prozess "{ $process }" läuft
+
+## Lüfter
+config-fan-range = fan_max_rpm ({ $max }) muss über fan_min_rpm ({ $min }) liegen
+fans-none = keine Lüftersensoren in /sys/class/hwmon gefunden
+fans-not-found = kein Lüfter "{ $fan }" (verfügbar: { $available })
+
+## SMART
+smart-waiting = warte auf die erste SMART-Prüfung
+smart-failed = smartctl fehlgeschlagen: { $error }
+smart-no-devices = smartctl hat keine Datenträger gefunden
+smart-problem = >>> WARNUNG: { $problem }
+smart-failing = { $device }: SMART-Gesamtzustand FEHLGESCHLAGEN
+smart-too-hot = { $device }: Temperatur { $temp } °C liegt über { $max } °C
+smart-bad-sectors = { $device }: { $count } defekte Sektoren/Medienfehler (toleriert: { $max })
+
+## CPU-Takt
+cpufreq-unavailable = keine CPU-Taktinformationen (cpufreq) verfügbar
+throttle-unavailable = keine Zähler für thermische Drosselung verfügbar (nur Intel-CPUs)
+
+## Mehrere Lampen
+layout-single = Eine Lampe
+layout-per-core = Eine Lampe pro CPU-Kern
+layout-per-numa-node = Eine Lampe pro NUMA-Knoten
+tray-layout = Anordnung
+switching-layout = Wechsel der Anordnung zu { $layout } ({ $lamps } Lampen)
+config-layout-no-numa = keine NUMA-Knoten in /sys/devices/system/node gefunden
+
+## Alarme
+oom-alert = >>> WARNUNG: { $message }
+oom-killed = Der OOM-Killer hat { $process } beendet
+oom-killed-unknown = Der OOM-Killer hat einen Prozess beendet
+oom-notification-title = Arbeitsspeicher voll
+oom-kmsg-unavailable = Kernel-Log nicht lesbar ({ $error }), OOM-Kills werden stattdessen über /proc/vmstat erkannt
+forecast-full = { $metric } voll in ~{ $minutes } Min
+forecast-notification-title = Speicher wird knapp
+alert-quiet-hours = Ruhezeit, keine Benachrichtigung
+alert-cooldown = Alarm in der Sperrzeit, keine Benachrichtigung
+quiet-hours-invalid = ungültige Ruhezeit "{ $value }" (z. B. "22:00-07:00")
+
+## Bildschirmaufnahme
+capture-excluded = Fenster wird bei Bildschirmaufnahmen ausgeblendet
+capture-included = Fenster ist bei Bildschirmaufnahmen wieder sichtbar
+capture-exclude-failed = >>> WARNUNG: Fenster konnte nicht vor Bildschirmaufnahmen verborgen werden: { $error }
+capture-unsupported = auf dieser Plattform nicht unterstützt (nur Windows und macOS)
+
+## Sprite-Speicherung
+asset-mmap-failed = >>> WARNUNG: Rohkopie von { $path } kann nicht eingeblendet werden ({ $error }), wird stattdessen dekodiert
+asset-raw-written = Rohe Frames nach { $path } geschrieben
+asset-on-demand-failed = >>> WARNUNG: { $path } kann nicht Frame für Frame dekodiert werden ({ $error }), wird stattdessen komplett dekodiert
+asset-frame-decode-failed = >>> FEHLER: Frame { $frame } konnte nicht dekodiert werden: { $error }
+asset-interlaced = PNGs mit Zeilensprung (interlaced) können nicht Frame für Frame dekodiert werden
+asset-truncated = das Bild endet vorzeitig
+asset-downscaling = Verkleinere { $from }×{ $from }-Frames auf { $to }×{ $to }
+asset-needs-downscaling = { $height }-px-Frames müssen erst verkleinert werden
+asset-evicted = { $file } aus dem Sprite-Cache entfernt (Budget überschritten)
+
+## Benchmark
+bench-header = Rendere { $frames } Frames pro Fenstergröße (Sprite-Speicherung: { $storage })
+bench-load = Laden der vier Sprite-Sheets: { $ms } ms
+bench-no-assets = >>> FEHLER: Sprite-Sheets nicht gefunden, nichts zu messen
+bench-col-size = Größe
+bench-col-fps = FPS
+bench-col-decode = Dekod. ms
+bench-col-blend = Blend. ms
+bench-col-scale = Skal. ms
+
+## Asset-Suchpfad
+assets-source-config = Config / LAVALAMPE_ASSETS_DIRS
+assets-source-working-dir = Arbeitsverzeichnis
+assets-source-data-dir = Datenverzeichnis
+assets-source-executable = neben dem Programm
+
+## Doctor
+doctor-config = Config-Datei: { $path }
+doctor-config-missing = Config-Datei: { $path } (nicht gefunden, Standardwerte)
+doctor-files = Weitere Dateien:
+doctor-log = Logdatei
+doctor-plugins = Plugins
+doctor-crashes = Absturzberichte
+doctor-sprite-cache = Sprite-Cache
+doctor-asset-dirs = Asset-Verzeichnisse in Suchreihenfolge (✓ = vorhanden):
+doctor-sheets = Sprite-Sheets für Theme "{ $theme }":
+doctor-not-found = in keinem Asset-Verzeichnis gefunden
+doctor-themes = Gefundene Themes: { $themes }
+doctor-no-themes = Keine Themes gefunden
+
+## Eingebaute Assets
+asset-built-in = eingebaut
+extract-written = { $path } geschrieben
+extract-skipped = { $path } existiert bereits, übersprungen
+extract-failed = >>> FEHLER: Konnte Assets nicht nach { $dir } schreiben: { $error }
+extract-hint = Für ein eigenes Theme die Dateien bearbeiten und nach { $themes }/<Name>/ legen.
+
+## Prüfung der Sprite-Sheets
+sheet-problems = >>> FEHLER: { $count } Problem(e) mit den Sprite-Sheets von Theme "{ $theme }":
+sheet-missing = in keinem Asset-Verzeichnis gefunden
+sheet-too-small = Frames sind { $height } px hoch, mindestens { $expected } nötig
+sheet-not-divisible = Breite { $width } ist kein Vielfaches der Framegröße { $frame } (Frames müssen quadratisch sein)
+sheet-too-many-frames = { $frames } Frames, nur die ersten { $max } werden verwendet
+sheet-frame-size-differs = { $size } px große Frames, { $other } hat aber { $other_size } px
+sheet-frame-count-differs = { $frames } Frames, { $other } hat aber { $other_frames }
+doctor-sheets-ok = Alle vier Sheets sehen gut aus
+doctor-sheet-problems = Probleme:
+
+## SVG-Sheets
+svg-unclosed-placeholder = "{{" ohne schließendes "}}"
+svg-bad-placeholder = unbekannter Platzhalter "{{{ $placeholder }}}" (t, frame, sin oder cos, optional etwa sin*40+64)
+
+## Lottie-Sheets
+lottie-invalid = keine Lottie-Animation, die rlottie lesen kann
+lottie-not-built = Lottie-Sheets brauchen einen Build mit --features lottie
+
+## Aseprite-Sheets
+aseprite-no-frames = die Datei hat keine Frames
+
+## pack
+pack-written = { $path } und { $sidecar } geschrieben
+pack-failed = >>> FEHLER: Frames konnten nicht zusammengefügt werden: { $error }
+pack-no-frames = keine PNG-Frames in { $dir }
+pack-skipped = { $path } hat keine Frame-Nummer im Namen, übersprungen
+pack-too-many-frames = { $frames } Frames, höchstens { $max } werden gezeigt
+pack-size-differs = Frame ist { $size }, der erste { $first }
+pack-size-too-small = Frames müssen mindestens { $min } Pixel groß sein, nicht { $size }
+pack-sidecar-comment = Von ram-lavalampe pack aus { $dir } geschrieben; die Lampe liest diese Datei nicht.
+
+## Palette
+palette-bad-color = "{ $color }" ist keine Farbe (#rrggbb oder #rgb verwenden)
+
+## Effekte
+config-bloom-range = bloom muss zwischen 0 und 2 liegen, nicht { $value }
+config-bloom-threshold-range = bloom_threshold muss mindestens 0 und kleiner als 1 sein, nicht { $value }
+config-bloom-radius-range = bloom_radius muss zwischen 1 und 32 liegen, nicht { $value }
+config-crt-range = { $key } muss zwischen 0 und { $max } liegen, nicht { $value }
+crt-on = CRT-Filter an
+crt-off = CRT-Filter aus
+tray-crt = CRT-Filter
+tray-borderless = Rahmenlos
+tray-baseline = Mit Ausgangswert vergleichen
+borderless-on = Fensterrahmen aus
+borderless-off = Fensterrahmen an
+tray-widget = Widget-Modus
+widget-on = Widget-Modus an (beenden mit Strg+W oder über das Tray)
+widget-off = Widget-Modus aus
+tray-click-through = Klickdurchlässig
+click-through-on = Klicks gehen durch die Lampe (Strg+T oder das Tray fängt sie wieder ab)
+click-through-off = Klicks erreichen die Lampe wieder
+config-particle-count-range = particle_count muss zwischen 1 und 200 liegen, nicht { $value }
+
+## Hintergrund
+config-level-opacity-range = level_opacity muss zwischen 0 und 1 liegen, nicht { $value }
+breakdown-read-failed = Aufteilung des Speichers nicht lesbar: { $error }
+background-load-failed = >>> FEHLER: Hintergrundbild { $path } konnte nicht geladen werden: { $error }
+
+## Sitzungszusammenfassung
+session-header = === Zusammenfassung der Sitzung ===
+session-duration = Dauer: { $duration }
+session-percent = { $metric }: Durchschnitt { $average }%, Maximum { $max }%
+session-tiers = Zeit pro Farbe: { $tiers }
+session-red-excursions = So oft rot geworden: { $count }
+session-written = Zusammenfassung nach { $path } geschrieben
+session-write-failed = >>> FEHLER: Zusammenfassung { $path } konnte nicht geschrieben werden: { $error }
+
+## Zustand (SIGUSR1)
+state-header = === Aktueller Zustand ===
+state-metric = Messwert: { $metric } ({ $layout })
+state-lamp = Lampe { $slot }: { $percent }%, { $color }, Sheet { $sheet }, Frame { $frame } ({ $ms } ms pro Frame)
+state-settings = Theme: { $theme }, Profil: { $profile }, Größe: { $size }, Messung alle { $interval } ms
+state-paused = Pausiert: { $paused }
+state-yes = ja
+state-no = nein
+state-config = Konfiguration: { $path }
+state-log = Logdatei: { $path }
+state-script = Skript: { $path }
+
+## Gamepad
+gamepad-connected = Gamepad verbunden: { $name }
+gamepad-failed = >>> FEHLER: Gamepads sind nicht verfügbar: { $error }
+gamepad-not-built = Gamepad-Unterstützung nicht eingebaut (cargo build --features gamepad)
+
+## Tempo (Mausrad)
+speed-factor = Animationstempo ×{ $factor }
+
+## Widget
+widget-skip-taskbar-failed = >>> WARNUNG: Lampe konnte nicht aus der Taskleiste genommen werden: { $error }
+widget-sticky-failed = >>> WARNUNG: Lampe konnte nicht auf jeder Arbeitsfläche angezeigt werden: { $error }
+widget-click-through-unsupported = Klickdurchlässigkeit wird auf dieser Plattform (X11) nicht unterstützt, Klicks erreichen weiter die Lampe
+widget-click-through-failed = >>> WARNUNG: Klicks konnten nicht durch die Lampe durchgereicht werden: { $error }
+widget-unsupported = auf dieser Plattform nicht unterstützt
+widget-wmctrl-missing = wmctrl ist nicht installiert ({ $error })
+widget-wmctrl-failed = wmctrl wurde mit { $status } beendet
+
+## XEmbed-Tray
+xembed-docked = Tray-Symbol im System-Tray angedockt (XEmbed)
+xembed-waiting = Kein System-Tray aktiv, das Symbol erscheint, sobald einer startet
+xembed-failed = >>> XEmbed-Tray-Symbol nicht verfügbar: { $error }
+xembed-unsupported-visual = nicht unterstützte Farbtiefe { $depth }
+
+## Ausgangswert
+baseline-taken = Ausgangswert festgehalten: { $percent }% { $metric }
+baseline-cleared = Ausgangswert verworfen ({ $delta } seitdem)
+baseline-dropped = Ausgangswert verworfen, die Messgröße hat sich geändert
+
+## Klick-Aktion
+click-running = Starte { $command }
+click-failed = >>> FEHLER: { $command } konnte nicht gestartet werden: { $error }
+click-no-system-monitor = >>> FEHLER: Keine Aufgabenverwaltung gefunden, trage deine in click.command ein
+
+## Schließen
+close-confirm = Zum Beenden erneut schließen
+hidden-to-tray = Fenster versteckt, die Lampe misst weiter (Klick aufs Tray-Symbol holt es zurück)
+hide-no-tray = >>> WARNUNG: Kein Tray-Symbol, das das Fenster zurückholen könnte, die Lampe wird beendet
+
+## Render-Watchdog
+renderer-software-fallback = >>> WARNUNG: Kein funktionierender GPU-Renderer ({ $error }), Software-Rendering wird verwendet
+renderer-restarting = >>> WARNUNG: Frames schlagen wiederholt fehl, der Renderer wird neu gestartet
+renderer-restarting-software = >>> WARNUNG: Frames schlagen wiederholt fehl, der Renderer wird in Software neu gestartet
+renderer-restarted = Renderer neu gestartet: { $backend }
+renderer-restart-failed = >>> FEHLER: Der Renderer konnte nicht neu gestartet werden: { $error }
+renderer-gave-up = >>> FEHLER: Der Renderer fällt immer wieder aus, es wird nicht mehr gezeichnet; Messung, Tray und Alarme laufen weiter
+
+## Prozess beobachten
+process-watching = Beobachte den RAM von "{ $process }"
+process-watch-fallback = >>> WARNUNG: Kein Prozess "{ $process }" läuft, bis er startet, wird der RAM des ganzen Systems gezeigt
+process-watch-exited = >>> WARNUNG: "{ $process }" wurde beendet, bis er zurück ist, wird der RAM des ganzen Systems gezeigt
@@ -0,0 +1,482 @@
+# English messages (reference catalog, every key must exist here)
+
+## Startup and shutdown
+starting = Starting RAM Lava Lamp...
+expected-frames = Expected frame count: { $count }
+controls = Controls: Ctrl + Up Arrow = Scale Up, Ctrl + Down Arrow = Scale Down, Ctrl + P = Next Profile, Esc = Exit
+monitoring-started = RAM monitoring started
+shutting-down = Shutting down...
+
+## Assets
+asset-not-found = >>> ERROR: Could not find asset file: { $file }
+asset-loading = >>> Attempting to load: { $path }
+asset-decode-failed = ERROR: Failed to decode { $path }: { $error }
+asset-open-failed = ERROR: Can't open { $path }: { $error }
+asset-wrong-height = ERROR: Wrong height! Got { $height }, expected { $expected } or more
+asset-wrong-width = ERROR: Width { $width } is not divisible by frame size { $frame }
+asset-loaded = ✓ Successfully loaded { $file }
+asset-load-failed = ✗ Failed to load { $file }
+asset-fallback = Trying green as fallback...
+
+## Lava colors
+color-green = Green
+color-yellow = Yellow
+color-orange = Orange
+color-red = Red
+switching-lava = === Switching to { $color } lava ({ $percent }% { $metric } used) ===
+boiling-over = === The lava is boiling over ({ $percent }% { $metric } used) ===
+falling-asleep = === The lamp falls asleep ({ $percent }% { $metric } used) ===
+
+## Runtime changes
+scaling-up = Scaling window up to { $size }
+scaling-down = Scaling window down to { $size }
+scaling-to = Scaling window to { $size }
+docking-to = Moving window to { $corner }
+switching-theme = Switching theme to { $theme }
+switching-metric = Switching metric to { $metric }
+sampling-every = Sampling every { $ms } ms
+monitoring-paused = Monitoring paused
+monitoring-resumed = Monitoring resumed
+theme-classic = Classic
+
+## Rendering
+resize-failed = Failed to resize surface: { $error }
+render-failed = pixels.render() failed: { $error }
+scale-factor-changed = Display scale changed to { $percent }%
+monitors-changed = Monitor layout changed ({ $count } connected)
+window-moved-to-screen = The lamp's screen is gone, moved to { $name }
+
+## Config
+config-loaded = >>> Loaded config: { $path }
+config-problems = >>> ERROR: { $count } problem(s) in config { $path }:
+config-position = line { $line }, column { $column }
+config-using-defaults = Invalid values were replaced by their defaults
+config-unknown-key = unknown key "{ $key }"
+config-interval-too-short = sampling interval { $value } ms is too short, use at least { $min } ms
+config-max-fps-range = max_fps must be between 1 and { $max }, got { $value }
+config-theme-not-found = theme "{ $theme }" not found in any assets/themes/ folder
+config-unknown-language = unsupported language "{ $language }" (available: en, de)
+unknown-language = Unknown language "{ $language }", keeping { $current }
+config-threshold-range = threshold { $value } must be between 0 and 100
+config-threshold-overlap = thresholds overlap: { $upper } ({ $upper_value }) must be above { $lower } ({ $lower_value })
+config-boiling-range = thresholds.boiling must be above red ({ $red }) and below 100, got { $value }
+config-idle-range = thresholds.idle must be above 0 and at most yellow ({ $yellow }), got { $value }
+config-speed-range = speed: fastest_ms ({ $fastest }) must be at least { $min } and below slowest_ms ({ $slowest })
+config-trend-samples-range = speed: trend_samples must be between 2 and { $max }, got { $value }
+config-tier-speed-min = { $key } must be at least { $min }, got { $value }
+config-unknown-sheet = assets.sheets: unknown sheet "{ $name }" (green, yellow, orange, red, boiling or sleeping)
+config-sheet-not-found = sheet { $path } not found
+config-log-path-is-dir = log file { $path } is a directory
+config-log-path-bad-parent = the folder of log file { $path } is a file
+config-log-size-zero = max_size_kb must be greater than 0
+config-keys-conflict = "{ $binding }" is bound to more than one action; using the default keys
+config-predict-horizon-zero = alerts.predict_horizon_min must be greater than 0
+config-predict-window-short = alerts.predict_window_s must be at least { $min }, got { $value }
+config-indicator-bar-corner = a bar needs an edge as position (top, bottom, left or right)
+config-click-no-command = click.action = "command" needs click.command
+keys-unknown = unknown key "{ $key }" (e.g. "ctrl+up", "escape", "f5", "page-down")
+config-written = >>> Config written to { $path }
+config-write-failed = >>> ERROR: Could not write config: { $error }
+config-no-dir = could not determine the config directory
+autostart-failed = >>> ERROR: Could not update autostart entry: { $error }
+
+## First-run setup
+onboarding-welcome = === Welcome to RAM Lava Lamp! ===
+onboarding-intro = No config file found, let's set up the basics (press Enter to keep the default).
+onboarding-no-terminal = >>> First start without a terminal, writing default config
+onboarding-size = Window size
+onboarding-corner = Screen corner
+onboarding-metric = What should the lamp show?
+onboarding-autostart = Start automatically after login?
+onboarding-default = (default)
+onboarding-enter-number = Please enter a number between 1 and { $max }
+onboarding-answer-yes-no = Please answer y or n
+onboarding-yes-no = y/N
+onboarding-yes-no-default-yes = Y/n
+corner-none = let the window manager decide
+corner-top-left = top left
+corner-top-right = top right
+corner-bottom-left = bottom left
+corner-bottom-right = bottom right
+
+## Tray menu
+tray-title = RAM Lava Lamp
+tray-started = Tray icon started
+tray-unavailable = >>> Tray icon not available: { $error }
+tray-tooltip = { $metric }: { $percent }%
+tray-size = Size
+tray-theme = Theme
+tray-metric = Metric
+tray-sampling-interval = Sampling interval
+tray-interval-custom = Custom ({ $interval })
+tray-pause = Pause monitoring
+tray-show = Show lamp
+tray-hide = Hide lamp
+tray-quit = Quit
+
+## Log file
+log-file = >>> Logging to { $path }
+log-open-failed = >>> ERROR: Could not open log file { $path }: { $error }
+log-rotate-failed = >>> ERROR: Could not rotate log file { $path }: { $error }
+
+## Crash reports
+crash-report-written = >>> The lamp crashed. A crash report was written to { $path }
+crash-report-failed = >>> The lamp crashed and the crash report could not be written: { $error }
+crash-notification-title = RAM Lava Lamp crashed
+crash-notification-body = A crash report was saved to { $path }
+
+## Environment overrides
+config-env-override = >>> Config override: { $name }={ $value }
+config-env-problems = >>> ERROR: { $count } problem(s) in LAVALAMPE_* environment variables:
+
+## Profiles
+profile-default = Default
+profile-none-defined = No profiles defined in the config
+profile-not-found = profile "{ $profile }" is not defined
+profile-invalid = profile "{ $profile }" is invalid: { $error }
+switching-profile = Switching to profile { $profile }
+tray-profile = Profile
+
+## Live reload
+config-reloaded = >>> Config reloaded: { $path }
+config-read-failed = >>> ERROR: Could not read config { $path }: { $error }
+config-read-defaults = Starting with the default settings, the file is left as it is
+config-reload-read-failed = >>> ERROR: Could not re-read config { $path }: { $error }
+config-reload-kept = The current settings stay in effect
+sighup-received = >>> SIGHUP received, reloading config
+sighup-failed = >>> ERROR: Could not install SIGHUP handler: { $error }
+
+## Scripting
+config-script-not-found = script { $path } not found
+script-loaded = >>> Loaded script: { $path }
+script-load-failed = >>> ERROR: Could not load script { $path }: { $error }
+script-failed = >>> ERROR: Script { $path } failed, using the default mapping: { $error }
+script-no-function = missing fn sample(percent, metric, hour)
+script-bad-result = sample() must return (), a number, a tier name or a map with tier/percent/frame_ms
+script-unknown-tier = unknown tier "{ $tier }" (use green, yellow, orange or red)
+
+## Metric sources and plugins
+metric-ram = RAM
+metric-swap = Swap
+metric-cpu = CPU
+metric-plugin = Plugin
+metric-stdin = stdin
+metric-audio = Audio
+metric-ping = Ping
+metric-vram = VRAM
+metric-zfs-arc = ZFS ARC
+metric-load = Load
+metric-smart = SMART
+metric-cpu-freq = CPU clock
+metric-throttling = Throttling
+metric-fan = Fan
+metric-fds = File descriptors
+metric-hugepages = Hugepages
+metric-disk = Disk I/O
+metric-unavailable = >>> ERROR: { $metric } has no value: { $error }
+plugin-loaded = >>> Loaded plugin "{ $name }" from { $path }
+plugin-load-failed = >>> ERROR: Could not load plugin { $path }: { $error }
+plugin-duplicate = >>> ERROR: Plugin "{ $name }" in { $path } is already loaded, skipping
+plugin-no-info = the plugin returned no description
+plugin-wrong-abi = plugin ABI version { $found } is not supported (expected { $expected })
+plugin-not-set = metric "plugin" needs metrics.plugin = "<name>"
+plugin-not-found = no plugin named "{ $name }" is loaded
+plugin-no-value = plugin "{ $name }" reported no value
+config-stdin-max = stdin_max must be greater than 0, got { $value }
+stdin-waiting = waiting for the first value on stdin
+stdin-closed = stdin was closed before any value arrived
+stdin-bad-line = >>> ERROR: Ignoring stdin line that is not a number: { $line }
+stdin-eof = >>> stdin closed, keeping the last value
+
+## Command line
+cli-usage = Usage: ram-lavalampe [--metric <name>] [--watch-process <name|pid>] [--assets-dir <dir>] [--widget] [--output <format>] | ram-lavalampe bench [--frames <n>] | ram-lavalampe doctor | ram-lavalampe status [--format tmux] | ram-lavalampe pack <dir> [-o <file>] [--size <px>] | ram-lavalampe --extract-assets <dir>
+cli-options = Options:
+cli-help-metric = --metric <name>   Metric to show, overrides the config ({ $metrics })
+cli-help-extract-assets = --extract-assets <dir>   Write the built-in sprite sheets to <dir> as a starting point for a theme
+cli-help-output = --output <format>   Print each sample for a status bar instead of showing the lamp ({ $formats })
+cli-help-watch-process = --watch-process <name|pid>   Show the RAM of this process instead of the whole system
+cli-help-widget = --widget          Desktop widget: borderless, always on top, not in the taskbar, on every workspace, transparent, docked in a corner, moved by dragging anywhere
+cli-help-assets-dir = --assets-dir <dir>   Search <dir> for sprite sheets and themes first (repeatable)
+cli-help-help = -h, --help        Show this help
+cli-help-version = -V, --version     Show the version
+cli-commands = Commands:
+cli-help-doctor = doctor   Show where config, sprite sheets and themes are looked up
+cli-help-pack = pack <dir> [-o <file>] [--size <px>]   Assemble the numbered PNGs in <dir> into a sprite sheet (default { $output }) with <px>×<px> frames (default 128)
+cli-help-status = status [--format <format>]   Print the current value once and exit, e.g. for tmux ({ $formats })
+cli-help-bench = bench [--frames <n>]   Render <n> frames (default { $frames }) offscreen per window size and print timings
+cli-bad-number = { $flag } needs a positive number, got "{ $value }"
+cli-example = Example: my-exporter | ram-lavalampe --metric stdin
+cli-error = error: { $error }
+cli-missing-value = { $flag } needs a value
+cli-unknown-metric = unknown metric "{ $metric }" (available: { $available })
+cli-unknown-output = unknown output format "{ $format }" (available: { $available })
+cli-unknown-argument = unknown argument "{ $argument }"
+
+## Audio
+config-audio-floor = audio_floor_db must be below 0, got { $value }
+audio-not-built = this build has no audio support (rebuild with --features audio)
+audio-no-device = no audio input device found
+audio-device-not-found = audio device "{ $device }" not found
+audio-unsupported-format = unsupported sample format { $format }
+audio-opened = >>> Listening on { $device } ({ $rate } Hz)
+audio-stream-error = >>> ERROR: Audio input failed: { $error }
+
+## Ping
+config-ping-max = ping_max_ms must be greater than 0, got { $value }
+config-ping-host = "{ $host }" is not a valid host
+ping-waiting = waiting for the first reply from { $host }
+ping-failed = ping failed: { $error }
+
+## Disk I/O
+config-disk-max = disk_max_mb_s must be greater than 0, got { $value }
+config-process-budget = process_budget_mb must be greater than 0, got { $value }
+disk-stats-failed = could not read /proc/diskstats (Linux only): { $error }
+disk-not-found = no block device "{ $device }" in /proc/diskstats
+disk-none = no disks found in /proc/diskstats
+
+## VRAM
+vram-no-gpu = no GPU { $gpu } with VRAM information found (NVIDIA driver or AMD amdgpu needed)
+vram-read-failed = could not read VRAM usage: { $error }
+
+## ZFS
+zfs-not-loaded = ZFS is not loaded (no /proc/spl/kstat/zfs/arcstats)
+
+## Hugepages
+hugepages-read-failed = could not read hugepage counters: { $error }
+hugepages-none = no hugepages configured (vm.nr_hugepages is 0)
+
+## NUMA
+config-numa-node = NUMA node { $node } does not exist (available: { $available })
+numa-node-not-found = NUMA node { $node } not found
+
+## Load average
+config-load-minutes = load_minutes must be 1, 5 or 15, got { $value }
+
+## File descriptors
+fds-read-failed = could not read file descriptor counts: { $error }
+fds-no-permission = no permission to read the file descriptors of { $process }
+process-not-found = no process "{ $process }" running
+
+## Fans
+config-fan-range = fan_max_rpm ({ $max }) must be above fan_min_rpm ({ $min })
+fans-none = no fan sensors found in /sys/class/hwmon
+fans-not-found = no fan "{ $fan }" (available: { $available })
+
+## SMART
+smart-waiting = waiting for the first SMART check
+smart-failed = smartctl failed: { $error }
+smart-no-devices = smartctl found no disks
+smart-problem = >>> WARNING: { $problem }
+smart-failing = { $device }: SMART overall health check FAILED
+smart-too-hot = { $device }: temperature { $temp } °C is above { $max } °C
+smart-bad-sectors = { $device }: { $count } bad sectors/media errors (tolerated: { $max })
+
+## CPU clock
+cpufreq-unavailable = no CPU frequency information (cpufreq) available
+throttle-unavailable = no thermal throttling counters available (Intel CPUs only)
+
+## Multi-lamp layouts
+layout-single = Single lamp
+layout-per-core = One lamp per CPU core
+layout-per-numa-node = One lamp per NUMA node
+tray-layout = Layout
+switching-layout = Switching layout to { $layout } ({ $lamps } lamps)
+config-layout-no-numa = no NUMA nodes found in /sys/devices/system/node
+
+## Alerts
+oom-alert = >>> WARNING: { $message }
+oom-killed = The OOM killer ended { $process }
+oom-killed-unknown = The OOM killer ended a process
+oom-notification-title = Out of memory
+oom-kmsg-unavailable = Kernel log not readable ({ $error }), watching /proc/vmstat for OOM kills instead
+forecast-full = { $metric } full in ~{ $minutes } min
+forecast-notification-title = Running out of memory
+alert-quiet-hours = Quiet hours, no notification
+alert-cooldown = Alert is cooling down, no notification
+quiet-hours-invalid = invalid quiet hours "{ $value }" (e.g. "22:00-07:00")
+
+## Screen capture
+capture-excluded = Window hidden from screen capture
+capture-included = Window visible in screen capture again
+capture-exclude-failed = >>> WARNING: Could not hide the window from screen capture: { $error }
+capture-unsupported = not supported on this platform (only Windows and macOS)
+
+## Sprite storage
+asset-mmap-failed = >>> WARNING: Can't memory-map a raw copy of { $path } ({ $error }), decoding it instead
+asset-raw-written = Wrote raw frames to { $path }
+asset-on-demand-failed = >>> WARNING: Can't decode { $path } frame by frame ({ $error }), decoding it completely instead
+asset-frame-decode-failed = >>> ERROR: Failed to decode frame { $frame }: { $error }
+asset-interlaced = interlaced PNGs can't be decoded frame by frame
+asset-truncated = the image ends early
+asset-downscaling = Scaling { $from }×{ $from } frames down to { $to }×{ $to }
+asset-needs-downscaling = { $height } px frames have to be scaled down first
+asset-evicted = Dropped { $file } from the sprite cache (over budget)
+
+## Benchmark
+bench-header = Rendering { $frames } frames per window size (sprite storage: { $storage })
+bench-load = Loading the four sprite sheets took { $ms } ms
+bench-no-assets = >>> ERROR: Sprite sheets not found, nothing to benchmark
+bench-col-size = size
+bench-col-fps = fps
+bench-col-decode = decode ms
+bench-col-blend = blend ms
+bench-col-scale = scale ms
+
+## Asset search path
+assets-source-config = config / LAVALAMPE_ASSETS_DIRS
+assets-source-working-dir = working directory
+assets-source-data-dir = data directory
+assets-source-executable = next to the executable
+
+## Doctor
+doctor-config = Config file: { $path }
+doctor-config-missing = Config file: { $path } (not found, using defaults)
+doctor-files = Other files:
+doctor-log = log file
+doctor-plugins = plugins
+doctor-crashes = crash reports
+doctor-sprite-cache = sprite cache
+doctor-asset-dirs = Asset directories, in search order (✓ = exists):
+doctor-sheets = Sprite sheets for theme "{ $theme }":
+doctor-not-found = not found in any asset directory
+doctor-themes = Themes found: { $themes }
+doctor-no-themes = No themes found
+
+## Built-in assets
+asset-built-in = built in
+extract-written = Wrote { $path }
+extract-skipped = { $path } already exists, skipped
+extract-failed = >>> ERROR: Could not write assets to { $dir }: { $error }
+extract-hint = To make a theme, edit the files and put them into { $themes }/<name>/.
+
+## Sprite sheet check
+sheet-problems = >>> ERROR: { $count } problem(s) with the sprite sheets of theme "{ $theme }":
+sheet-missing = not found in any asset directory
+sheet-too-small = frames are { $height } px high, need at least { $expected }
+sheet-not-divisible = width { $width } is not a multiple of the frame size { $frame } (frames must be square)
+sheet-too-many-frames = { $frames } frames, only the first { $max } are used
+sheet-frame-size-differs = { $size } px frames, but { $other } has { $other_size } px
+sheet-frame-count-differs = { $frames } frames, but { $other } has { $other_frames }
+doctor-sheets-ok = All four sheets look fine
+doctor-sheet-problems = Problems:
+
+## SVG sheets
+svg-unclosed-placeholder = "{{" without a closing "}}"
+svg-bad-placeholder = unknown placeholder "{{{ $placeholder }}}" (use t, frame, sin or cos, optionally like sin*40+64)
+
+## Lottie sheets
+lottie-invalid = not a Lottie animation rlottie can read
+lottie-not-built = Lottie sheets need a build with --features lottie
+
+## Aseprite sheets
+aseprite-no-frames = the file has no frames
+
+## pack
+pack-written = Wrote { $path } and { $sidecar }
+pack-failed = >>> ERROR: Could not pack the frames: { $error }
+pack-no-frames = no PNG frames in { $dir }
+pack-skipped = { $path } has no frame number in its name, skipped
+pack-too-many-frames = { $frames } frames, at most { $max } are shown
+pack-size-differs = frame is { $size }, the first one { $first }
+pack-size-too-small = frames must be at least { $min } pixels, got { $size }
+pack-sidecar-comment = Written by ram-lavalampe pack from { $dir }; the lamp doesn't read this file.
+
+## Palette
+palette-bad-color = "{ $color }" is not a color (use #rrggbb or #rgb)
+
+## Effects
+config-bloom-range = bloom must be between 0 and 2, got { $value }
+config-bloom-threshold-range = bloom_threshold must be at least 0 and below 1, got { $value }
+config-bloom-radius-range = bloom_radius must be between 1 and 32, got { $value }
+config-crt-range = { $key } must be between 0 and { $max }, got { $value }
+crt-on = CRT filter on
+crt-off = CRT filter off
+tray-crt = CRT filter
+tray-borderless = Borderless
+tray-baseline = Compare with baseline
+borderless-on = Window border off
+borderless-off = Window border on
+tray-widget = Widget mode
+widget-on = Widget mode on (leave with Ctrl+W or the tray)
+widget-off = Widget mode off
+tray-click-through = Click-through
+click-through-on = Clicks go through the lamp (Ctrl+T or the tray catches them again)
+click-through-off = Clicks reach the lamp again
+config-particle-count-range = particle_count must be between 1 and 200, got { $value }
+
+## Background
+config-level-opacity-range = level_opacity must be between 0 and 1, got { $value }
+breakdown-read-failed = Could not read the memory breakdown: { $error }
+background-load-failed = >>> ERROR: Could not load background image { $path }: { $error }
+
+## Session summary
+session-header = === Session summary ===
+session-duration = Duration: { $duration }
+session-percent = { $metric }: average { $average }%, max { $max }%
+session-tiers = Time per color: { $tiers }
+session-red-excursions = Times it went red: { $count }
+session-written = Summary written to { $path }
+session-write-failed = >>> ERROR: Could not write summary { $path }: { $error }
+
+## State dump (SIGUSR1)
+state-header = === Current state ===
+state-metric = Metric: { $metric } ({ $layout })
+state-lamp = Lamp { $slot }: { $percent }%, { $color }, sheet { $sheet }, frame { $frame } ({ $ms } ms per frame)
+state-settings = Theme: { $theme }, profile: { $profile }, size: { $size }, sampling every { $interval } ms
+state-paused = Paused: { $paused }
+state-yes = yes
+state-no = no
+state-config = Config: { $path }
+state-log = Log file: { $path }
+state-script = Script: { $path }
+
+## Gamepad
+gamepad-connected = Gamepad connected: { $name }
+gamepad-failed = >>> ERROR: Gamepads are not available: { $error }
+gamepad-not-built = Gamepad support not built in (cargo build --features gamepad)
+
+## Speed (mouse wheel)
+speed-factor = Animation speed ×{ $factor }
+
+## Widget
+widget-skip-taskbar-failed = >>> WARNING: Could not hide the lamp from the taskbar: { $error }
+widget-sticky-failed = >>> WARNING: Could not show the lamp on every workspace: { $error }
+widget-click-through-unsupported = Click-through isn't supported on this platform (X11), clicks still reach the lamp
+widget-click-through-failed = >>> WARNING: Could not make the lamp click-through: { $error }
+widget-unsupported = not supported on this platform
+widget-wmctrl-missing = wmctrl is not installed ({ $error })
+widget-wmctrl-failed = wmctrl exited with { $status }
+
+## XEmbed tray
+xembed-docked = Tray icon docked into the system tray (XEmbed)
+xembed-waiting = No system tray running, the icon appears once one starts
+xembed-failed = >>> XEmbed tray icon not available: { $error }
+xembed-unsupported-visual = unsupported color depth { $depth }
+
+## Baseline
+baseline-taken = Baseline taken at { $percent }% { $metric }
+baseline-cleared = Baseline cleared ({ $delta } since it was taken)
+baseline-dropped = Baseline dropped, the metric changed
+
+## Click action
+click-running = Starting { $command }
+click-failed = >>> ERROR: Could not start { $command }: { $error }
+click-no-system-monitor = >>> ERROR: No task manager found, set click.command to the one you use
+
+## Closing
+close-confirm = Close again to quit
+hidden-to-tray = Window hidden, the lamp keeps monitoring (click the tray icon to bring it back)
+hide-no-tray = >>> WARNING: No tray icon to bring the window back, quitting instead
+
+## Render watchdog
+renderer-software-fallback = >>> WARNING: No working GPU renderer ({ $error }), rendering in software
+renderer-restarting = >>> WARNING: Frames keep failing, restarting the renderer
+renderer-restarting-software = >>> WARNING: Frames keep failing, restarting the renderer in software
+renderer-restarted = Renderer restarted: { $backend }
+renderer-restart-failed = >>> ERROR: Could not restart the renderer: { $error }
+renderer-gave-up = >>> ERROR: The renderer keeps failing, drawing stops; monitoring, the tray and alerts go on
+
+## Process watching
+process-watching = Watching the RAM of "{ $process }"
+process-watch-fallback = >>> WARNING: No process "{ $process }" running, showing the RAM of the whole system until it starts
+process-watch-exited = >>> WARNING: "{ $process }" exited, showing the RAM of the whole system until it's back
@@ -0,0 +1,328 @@
+//! Alerts for events that are worse than a high value: the lamp flashes red
+//! and a desktop notification says what happened.
+//!
+//! OOM kills are read from the kernel log (`/dev/kmsg`), which names the killed
+//! process. Where the kernel log is restricted (`kernel.dmesg_restrict = 1`),
+//! the `oom_kill` counter in `/proc/vmstat` is polled instead; it counts kills
+//! by the global and the cgroup OOM killer alike, but without a name.
+//!
+//! Before it gets that far, [`Forecast`] extrapolates the recent slope and
+//! warns when the value would reach 100% within the configured horizon.
+//!
+//! Notifications go through [`Throttle`]: each kind of alert has its own
+//! cooldown, and none are sent during quiet hours. The lamp flashes anyway.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+use winit::event_loop::EventLoopProxy;
+
+use crate::clock;
+use crate::i18n::tr;
+use crate::AppEvent;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertConfig {
+    /// Flash the lamp when the OOM killer ends a process.
+    pub oom_kill: bool,
+    /// Also show a desktop notification (via `notify-send`).
+    pub notify: bool,
+    /// How long the lamp flashes.
+    pub duration_s: u64,
+    /// Warn when the trend reaches 100% within `predict_horizon_min`.
+    pub predict: bool,
+    pub predict_horizon_min: u64,
+    /// How far back the trend looks.
+    pub predict_window_s: u64,
+    /// Minimum time between two notifications about OOM kills.
+    pub oom_kill_cooldown_min: u64,
+    /// Minimum time between two notifications about the forecast.
+    pub predict_cooldown_min: u64,
+    /// No notifications during this time of day, e.g. `"22:00-07:00"`.
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        AlertConfig {
+            oom_kill: true,
+            notify: true,
+            duration_s: 10,
+            predict: true,
+            predict_horizon_min: 10,
+            predict_window_s: 120,
+            oom_kill_cooldown_min: 1,
+            predict_cooldown_min: 30,
+            quiet_hours: None,
+        }
+    }
+}
+
+impl AlertConfig {
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.duration_s)
+    }
+
+    pub fn predict_horizon(&self) -> Duration {
+        Duration::from_secs(self.predict_horizon_min * 60)
+    }
+
+    pub fn predict_window(&self) -> Duration {
+        Duration::from_secs(self.predict_window_s)
+    }
+
+    fn cooldown(&self, kind: Kind) -> Duration {
+        let minutes = match kind {
+            Kind::OomKill => self.oom_kill_cooldown_min,
+            Kind::Forecast => self.predict_cooldown_min,
+        };
+        Duration::from_secs(minutes * 60)
+    }
+}
+
+/// A daily time span, written as `"22:00-07:00"` in the config. It may
+/// wrap around midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct QuietHours {
+    /// Minutes since midnight.
+    start: u32,
+    end: u32,
+}
+
+impl QuietHours {
+    /// Whether `minutes` since midnight fall into the span.
+    pub fn contains(&self, minutes: u32) -> bool {
+        if self.start <= self.end {
+            (self.start..self.end).contains(&minutes)
+        } else {
+            minutes >= self.start || minutes < self.end
+        }
+    }
+}
+
+impl TryFrom<String> for QuietHours {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, String> {
+        let invalid = || tr!("quiet-hours-invalid", value = text.clone());
+        let time = |part: &str| -> Option<u32> {
+            let (hours, minutes) = part.trim().split_once(':')?;
+            let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+            (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+        };
+        let (start, end) = text.split_once('-').ok_or_else(invalid)?;
+        let (start, end) = (time(start).ok_or_else(invalid)?, time(end).ok_or_else(invalid)?);
+        if start == end {
+            return Err(invalid());
+        }
+        Ok(QuietHours { start, end })
+    }
+}
+
+impl From<QuietHours> for String {
+    fn from(quiet: QuietHours) -> String {
+        let time = |minutes: u32| format!("{:02}:{:02}", minutes / 60, minutes % 60);
+        format!("{}-{}", time(quiet.start), time(quiet.end))
+    }
+}
+
+/// What an alert is about; each has its own cooldown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    OomKill,
+    Forecast,
+}
+
+/// Decides whether an alert may send a notification.
+#[derive(Default)]
+pub struct Throttle {
+    last: HashMap<Kind, Instant>,
+}
+
+impl Throttle {
+    /// `true` if a notification of `kind` is due now, and counts it as sent.
+    pub fn allow(&mut self, kind: Kind, config: &AlertConfig) -> bool {
+        if config.quiet_hours.is_some_and(|quiet| quiet.contains(clock::local_minutes())) {
+            log::debug!("{}", tr!("alert-quiet-hours"));
+            return false;
+        }
+        let now = Instant::now();
+        if self.last.get(&kind).is_some_and(|last| now.duration_since(*last) < config.cooldown(kind)) {
+            log::debug!("{}", tr!("alert-cooldown"));
+            return false;
+        }
+        self.last.insert(kind, now);
+        true
+    }
+}
+
+/// Samples needed before the slope means anything.
+const FORECAST_MIN_SAMPLES: usize = 5;
+
+/// The recent samples of one metric, for extrapolating when it hits 100%.
+#[derive(Default)]
+pub struct Forecast {
+    samples: VecDeque<(Instant, f64)>,
+    /// Warned already; again only after the projection moved well away.
+    warned: bool,
+}
+
+impl Forecast {
+    /// Takes a sample. Returns the time left until 100% when that's a new
+    /// warning, i.e. within the horizon for the first time.
+    pub fn record(&mut self, now: Instant, percent: f64, config: &AlertConfig) -> Option<Duration> {
+        self.samples.push_back((now, percent));
+        while self.samples.front().is_some_and(|(time, _)| now.duration_since(*time) > config.predict_window()) {
+            self.samples.pop_front();
+        }
+
+        let horizon = config.predict_horizon();
+        let left = self.time_to_full();
+        // Erst wieder warnen, wenn sich die Lage deutlich entspannt hat, sonst
+        // meldet ein wackliger Trend an der Grenze jede Minute aufs Neue
+        if left.is_none_or(|left| left > horizon * 2) {
+            self.warned = false;
+        }
+        match left {
+            Some(left) if left <= horizon && !self.warned => {
+                self.warned = true;
+                Some(left)
+            }
+            _ => None,
+        }
+    }
+
+    /// Least-squares slope over the kept samples, extrapolated to 100%.
+    /// `None` while flat or falling, or when it's full already.
+    fn time_to_full(&self) -> Option<Duration> {
+        if self.samples.len() < FORECAST_MIN_SAMPLES {
+            return None;
+        }
+        let (start, _) = self.samples[0];
+        let points: Vec<(f64, f64)> =
+            self.samples.iter().map(|(time, percent)| (time.duration_since(start).as_secs_f64(), *percent)).collect();
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let (mut covariance, mut variance) = (0.0, 0.0);
+        for (x, y) in &points {
+            covariance += (x - mean_x) * (y - mean_y);
+            variance += (x - mean_x) * (x - mean_x);
+        }
+        // Prozentpunkte pro Sekunde
+        let slope = covariance / variance;
+        let last = points[points.len() - 1].1;
+        if !slope.is_finite() || slope <= 0.0 || last >= 100.0 {
+            return None;
+        }
+        // Bei winziger Steigung zu groß für eine Duration, dann eben nie
+        Duration::try_from_secs_f64((100.0 - last) / slope).ok()
+    }
+}
+
+/// Best effort, like the crash notification: without `notify-send` only the lamp flashes.
+pub fn notify(title: String, body: String) {
+    // Eigener Thread, damit der Render-Loop nicht auf notify-send wartet
+    let _ = thread::Builder::new().name("notify".into()).spawn(move || {
+        let _ = Command::new("notify-send")
+            .arg("--urgency=critical")
+            .arg("--app-name=RAM Lava Lamp")
+            .arg(title)
+            .arg(body)
+            .status();
+    });
+}
+
+/// Pulses the whole frame towards red, twice per second.
+pub fn tint(frame: &mut [u8], elapsed: Duration) {
+    let phase = (elapsed.as_secs_f64() * 2.0 * std::f64::consts::TAU).sin() * 0.5 + 0.5;
+    let strength = 0.2 + phase * 0.5;
+    for pixel in frame.chunks_exact_mut(4) {
+        pixel[0] = (pixel[0] as f64 + (255.0 - pixel[0] as f64) * strength) as u8;
+        pixel[1] = (pixel[1] as f64 * (1.0 - strength)) as u8;
+        pixel[2] = (pixel[2] as f64 * (1.0 - strength)) as u8;
+    }
+}
+
+/// Starts watching for OOM kills; each one is sent as [`AppEvent::OomKill`].
+#[cfg(target_os = "linux")]
+pub fn spawn_oom_watcher(proxy: EventLoopProxy<AppEvent>) {
+    let _ = thread::Builder::new().name("oom-watch".into()).spawn(move || {
+        if let Err(e) = oom::watch_kmsg(&proxy) {
+            log::info!("{}", tr!("oom-kmsg-unavailable", error = e));
+            oom::poll_vmstat(&proxy);
+        }
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn spawn_oom_watcher(_proxy: EventLoopProxy<AppEvent>) {}
+
+#[cfg(target_os = "linux")]
+mod oom {
+    use std::fs::{self, File};
+    use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+    use std::thread;
+    use std::time::Duration;
+    use winit::event_loop::EventLoopProxy;
+
+    use crate::AppEvent;
+
+    const VMSTAT_INTERVAL: Duration = Duration::from_secs(2);
+
+    /// Follows the kernel log from now on. Returns an error if it can't be
+    /// opened; otherwise only once the event loop is gone.
+    pub fn watch_kmsg(proxy: &EventLoopProxy<AppEvent>) -> io::Result<()> {
+        let mut kmsg = File::open("/dev/kmsg")?;
+        // Alte Meldungen überspringen, sonst meldet jeder Start vergangene Kills
+        kmsg.seek(SeekFrom::End(0))?;
+
+        for record in BufReader::new(kmsg).lines() {
+            let record = match record {
+                Ok(record) => record,
+                // EPIPE: Meldungen wurden überschrieben, bevor wir sie lesen konnten
+                Err(e) if e.raw_os_error() == Some(libc::EPIPE) => continue,
+                Err(e) => return Err(e),
+            };
+            if let Some(process) = parse_killed_process(&record) {
+                if proxy.send_event(AppEvent::OomKill(Some(process))).is_err() {
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fallback without access to the kernel log.
+    pub fn poll_vmstat(proxy: &EventLoopProxy<AppEvent>) {
+        let Some(mut last) = read_oom_kill_count() else { return };
+        loop {
+            thread::sleep(VMSTAT_INTERVAL);
+            let Some(count) = read_oom_kill_count() else { return };
+            for _ in last..count {
+                if proxy.send_event(AppEvent::OomKill(None)).is_err() {
+                    return;
+                }
+            }
+            last = count;
+        }
+    }
+
+    fn read_oom_kill_count() -> Option<u64> {
+        let vmstat = fs::read_to_string("/proc/vmstat").ok()?;
+        vmstat.lines().find_map(|line| line.strip_prefix("oom_kill ")?.trim().parse().ok())
+    }
+
+    /// "6,1234,5678,-;Out of memory: Killed process 4321 (firefox) total-vm:..." → "firefox (4321)"
+    fn parse_killed_process(record: &str) -> Option<String> {
+        let message = record.split_once(';')?.1;
+        let rest = message.split_once("Killed process ")?.1;
+        let (pid, rest) = rest.split_once(' ')?;
+        let name = rest.strip_prefix('(')?.split_once(')')?.0;
+        Some(format!("{} ({})", name, pid))
+    }
+}
@@ -0,0 +1,1241 @@
+use ksni::blocking::Handle;
+use pixels::Pixels;
+use std::time::{Duration, Instant};
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::{ElementState, Event, KeyboardInput, MouseButton, MouseScrollDelta, VirtualKeyCode, WindowEvent},
+    event_loop::ControlFlow,
+    window::Window,
+};
+
+use crate::alerts::{self, Forecast, Kind, Throttle};
+use crate::background::Background;
+use crate::badge::Badge;
+use crate::cli::Args;
+use crate::config::{self, CloseBehavior, Config, Corner, Layout};
+use crate::effects::PostProcess;
+use crate::metrics::breakdown::Breakdown;
+use crate::metrics::{Metric, Sampler};
+use crate::overlay::{self, Toast};
+use crate::i18n::{self, tr};
+use crate::indicators::Indicators;
+use crate::keys::Action;
+use crate::lamp::{self, Lamp, SpriteCache};
+use crate::launch::{self, ClickAction, ClickButton};
+use crate::menu::{ContextMenu, Entry};
+use crate::particles::{self, Particles};
+use crate::script::{Mapping, Script};
+use crate::session::Session;
+use crate::tier::{Extreme, Tier};
+use crate::touch::{Gesture, Gestures};
+use crate::tray::{self, LampTray};
+use crate::watchdog::{Verdict, Watchdog};
+use crate::widget;
+use crate::xembed::XembedTray;
+use crate::{
+    apply_size_mode, assets, capture, crash, dock_on, dock_to_corner, logging, sprites, window_size, AppEvent, WindowSizeMode,
+    WINDOW_SIZE,
+};
+
+/// How often the monitor layout is checked for screens that came or went.
+const MONITOR_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Speed-ups the mouse wheel steps through, slowest first.
+const SPEED_FACTORS: [f64; 10] = [0.25, 0.33, 0.5, 0.75, 1.0, 1.25, 1.5, 2.0, 3.0, 4.0];
+/// Index of 1.0 in [`SPEED_FACTORS`].
+const NORMAL_SPEED: usize = 4;
+/// Touchpads scroll in pixels; this many make one wheel notch.
+const PIXELS_PER_NOTCH: f64 = 50.0;
+/// Longest time between the two clicks of a double click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+/// How long a second close quits with `on_close = "confirm"`.
+const CONFIRM_CLOSE: Duration = Duration::from_secs(3);
+
+/// Everything the event loop works on.
+pub struct App {
+    pub window: Window,
+    pub pixels: Pixels,
+    pub sampler: Sampler,
+    pub tray: Option<Handle<LampTray>>,
+    /// XEmbed icon, when there's no StatusNotifierItem host.
+    pub xembed: Option<XembedTray>,
+    /// Command line options, re-applied on every config reload.
+    pub args: Args,
+
+    /// Config from file and environment, without a profile applied.
+    pub base_config: Config,
+    /// Settings in effect: base config + active profile + changes made via hotkeys/tray.
+    pub config: Config,
+    pub profile: Option<String>,
+    pub script: Option<Script>,
+
+    pub ctrl_pressed: bool,
+    pub paused: bool,
+    /// Start of the current alert, while the lamp flashes.
+    pub alert: Option<Instant>,
+    pub forecast: Forecast,
+    /// Metric and average usage when the baseline was taken.
+    pub baseline: Option<(Metric, f64)>,
+    /// Cooldowns and quiet hours of the alert notifications.
+    pub throttle: Throttle,
+
+    /// One entry per lamp in the window, left to right.
+    pub lamps: Vec<Lamp>,
+    pub sprites: SpriteCache,
+    /// Whether the current theme has SVG or Lottie sheets, which are rendered at the window size.
+    pub vector_theme: bool,
+    /// Extremes the current theme has its own sheets for.
+    pub extreme_sheets: Vec<Extreme>,
+    /// Lamps in the frame buffer and the side of each, in pixels.
+    pub buffer: (usize, usize),
+    /// Scale factor of the monitor the window is on (1.5 at 150 %).
+    pub scale_factor: f64,
+    pub last_sample: Instant,
+    pub last_frame: Instant,
+    /// When a frame was last presented, for `max_fps`.
+    pub last_present: Instant,
+    /// Sprite, animation frame and "still loading" of every lamp as last presented.
+    pub presented: Vec<(&'static str, usize, bool)>,
+    /// Forces the next redraw to composite and present even if no lamp changed.
+    pub dirty: bool,
+    pub post: PostProcess,
+    pub particles: Particles,
+    pub background: Background,
+    /// RAM split into used and cache for the stacked fill, if it's on and available.
+    pub breakdown: Option<Breakdown>,
+    pub indicators: Indicators,
+    pub session: Session,
+    pub badge: Badge,
+    pub gestures: Gestures,
+    /// Index into [`SPEED_FACTORS`], changed with the mouse wheel.
+    pub speed_step: usize,
+    /// Scrolling not yet turned into whole notches.
+    pub wheel: f64,
+    /// Message shown over the lamps for a moment.
+    pub toast: Option<Toast>,
+    /// Context menu opened by a long press, until an entry is picked.
+    pub menu: Option<ContextMenu>,
+    /// Where the mouse is over the window, to click entries of the menu.
+    pub cursor: Option<PhysicalPosition<f64>>,
+    /// Last left click that could start a double click.
+    pub last_click: Option<Instant>,
+    /// First close with `on_close = "confirm"`, waiting for the second.
+    pub close_requested: Option<Instant>,
+    /// Hidden to the tray; sampling and alerts go on.
+    pub hidden: bool,
+    /// Rebuilds the renderer when frames keep failing.
+    pub watchdog: Watchdog,
+    /// Whether the window was created transparent. Only then does
+    /// `background.transparent` show the desktop; it can't change later.
+    pub transparent_window: bool,
+    /// Config before widget mode was switched on at runtime, to go back to.
+    pub before_widget: Option<Config>,
+    /// The window manager is moving the window after a click (`drag_anywhere`).
+    pub dragging: bool,
+    /// Position and size of every monitor at the last check.
+    pub monitors: Vec<(PhysicalPosition<i32>, PhysicalSize<u32>)>,
+    pub last_monitor_check: Instant,
+}
+
+impl App {
+    pub fn new(window: Window, pixels: Pixels, sampler: Sampler, base_config: Config) -> Self {
+        let profile = base_config.profile.clone();
+        let config = base_config.with_profile(profile.as_deref());
+        let script = load_script(&config);
+
+        let scale_factor = window.scale_factor();
+        let transparent_window = config.background.transparent;
+        let mut app = App {
+            window,
+            pixels,
+            sampler,
+            tray: None,
+            xembed: None,
+            args: Args::default(),
+            base_config,
+            config,
+            profile,
+            script,
+            ctrl_pressed: false,
+            paused: false,
+            alert: None,
+            forecast: Forecast::default(),
+            baseline: None,
+            throttle: Throttle::default(),
+            lamps: Vec::new(),
+            sprites: SpriteCache::default(),
+            vector_theme: false,
+            extreme_sheets: Vec::new(),
+            buffer: (1, WINDOW_SIZE),
+            scale_factor,
+            last_sample: Instant::now(),
+            last_frame: Instant::now(),
+            last_present: Instant::now(),
+            presented: Vec::new(),
+            dirty: true,
+            post: PostProcess::default(),
+            particles: Particles::default(),
+            background: Background::default(),
+            breakdown: None,
+            indicators: Indicators::default(),
+            session: Session::default(),
+            badge: Badge::default(),
+            gestures: Gestures::default(),
+            speed_step: NORMAL_SPEED,
+            wheel: 0.0,
+            toast: None,
+            menu: None,
+            cursor: None,
+            last_click: None,
+            close_requested: None,
+            hidden: false,
+            watchdog: Watchdog::default(),
+            transparent_window,
+            before_widget: None,
+            dragging: false,
+            monitors: Vec::new(),
+            last_monitor_check: Instant::now(),
+        };
+        app.monitors = monitor_layout(&app.window);
+        app.background.load(app.config.background_image_path().as_deref());
+        app.check_sprites();
+        app.sample();
+        app
+    }
+
+    pub fn handle_event(&mut self, event: Event<AppEvent>, control_flow: &mut ControlFlow) {
+        match event {
+            Event::WindowEvent {
+                event: WindowEvent::CloseRequested,
+                ..
+            } => self.close(control_flow),
+            Event::WindowEvent {
+                event: WindowEvent::KeyboardInput { input, .. },
+                ..
+            } => self.handle_key(input, control_flow),
+            Event::UserEvent(app_event) => self.handle_app_event(app_event, control_flow),
+            Event::WindowEvent {
+                event: WindowEvent::Resized(physical_size),
+                ..
+            } => {
+                // Nur loggen, wenn sich wirklich was ändert, um Spam zu vermeiden
+                // println!("Window resized to: {}x{}", physical_size.width, physical_size.height);
+                if let Err(e) = self.pixels.resize_surface(physical_size.width, physical_size.height) {
+                    log::error!("{}", tr!("resize-failed", error = e));
+                }
+                self.dirty = true;
+            }
+            Event::WindowEvent {
+                event: WindowEvent::ScaleFactorChanged { scale_factor, new_inner_size },
+                ..
+            } => {
+                // Die logische Größe bleibt, sonst würde die Lampe auf dem anderen Monitor
+                // größer oder kleiner als die gewählte Stufe
+                let size = window_size(self.config.size, self.lamps.len().max(1)).to_physical::<u32>(scale_factor);
+                *new_inner_size = size;
+                log::info!("{}", tr!("scale-factor-changed", percent = (scale_factor * 100.0).round()));
+                self.scale_factor = scale_factor;
+                if let Err(e) = self.pixels.resize_surface(size.width, size.height) {
+                    log::error!("{}", tr!("resize-failed", error = e));
+                }
+                self.resize_buffer();
+                self.dirty = true;
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Touch(touch),
+                ..
+            } => {
+                if let Some(gesture) = self.gestures.touch(touch) {
+                    self.run_gesture(gesture, control_flow);
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::CursorMoved { position, .. },
+                ..
+            } => self.cursor = Some(position),
+            Event::WindowEvent {
+                event: WindowEvent::CursorLeft { .. },
+                ..
+            } => self.cursor = None,
+            Event::WindowEvent {
+                event: WindowEvent::MouseWheel { delta, .. },
+                ..
+            } => self.scroll(delta),
+            Event::WindowEvent {
+                event: WindowEvent::MouseInput { state: ElementState::Pressed, button, .. },
+                ..
+            } => self.click(button, control_flow),
+            Event::WindowEvent {
+                event: WindowEvent::MouseInput { state: ElementState::Released, .. },
+                ..
+            } => self.dragging = false,
+            Event::WindowEvent {
+                event: WindowEvent::Moved(_),
+                ..
+            } if self.dragging => {
+                // Wie beim Ziehen mit dem Finger: weggezogen heißt nicht mehr angedockt
+                self.dragging = false;
+                if self.config.corner != Corner::None {
+                    self.set_corner(Corner::None);
+                }
+            }
+            Event::RedrawRequested(_) => self.redraw(control_flow),
+            Event::LoopDestroyed => self.session.finish(&self.config.session, self.config.session_summary_path().as_deref()),
+            Event::MainEventsCleared if self.hidden || self.watchdog.gave_up() => self.sample_hidden(control_flow),
+            Event::MainEventsCleared => {
+                self.window.request_redraw();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_key(&mut self, input: KeyboardInput, control_flow: &mut ControlFlow) {
+        if let Some(VirtualKeyCode::LControl | VirtualKeyCode::RControl) = input.virtual_keycode {
+            self.ctrl_pressed = input.state == ElementState::Pressed;
+            return;
+        }
+        if input.state != ElementState::Pressed {
+            return;
+        }
+        let Some(action) = self.config.keys.action(&input, self.ctrl_pressed) else { return };
+        self.run_action(action, control_flow);
+    }
+
+    /// Does what a shortcut or gamepad button is bound to.
+    fn run_action(&mut self, action: Action, control_flow: &mut ControlFlow) {
+        match action {
+            // Esc schließt erst das Menü
+            Action::Quit if self.menu.is_some() => self.close_menu(),
+            Action::Quit => self.close(control_flow),
+            Action::ScaleUp => {
+                let size_mode = self.config.size.scale_up();
+                if size_mode != self.config.size {
+                    log::info!("{}", tr!("scaling-up", size = size_mode.description()));
+                    self.set_size_mode(size_mode);
+                }
+            }
+            Action::ScaleDown => {
+                let size_mode = self.config.size.scale_down();
+                if size_mode != self.config.size {
+                    log::info!("{}", tr!("scaling-down", size = size_mode.description()));
+                    self.set_size_mode(size_mode);
+                }
+            }
+            Action::NextProfile => self.cycle_profile(),
+            Action::ToggleCrt => self.toggle_crt(),
+            Action::ToggleBorderless => self.toggle_borderless(),
+            Action::ToggleWidget => self.toggle_widget(),
+            Action::ToggleClickThrough => self.toggle_click_through(),
+            Action::ToggleBaseline => self.toggle_baseline(),
+            Action::NextCorner => self.set_corner(self.config.corner.clockwise()),
+            Action::PreviousCorner => self.set_corner(self.config.corner.counterclockwise()),
+        }
+    }
+
+    fn handle_app_event(&mut self, event: AppEvent, control_flow: &mut ControlFlow) {
+        match event {
+            AppEvent::SetSizeMode(size_mode) => {
+                if size_mode != self.config.size {
+                    log::info!("{}", tr!("scaling-to", size = size_mode.description()));
+                    self.set_size_mode(size_mode);
+                }
+            }
+            AppEvent::SetTheme(theme) => {
+                let mut config = self.config.clone();
+                config.set_active_theme(theme);
+                self.apply_config(config);
+            }
+            AppEvent::SetMetric(metric) => {
+                let mut config = self.config.clone();
+                config.metric = metric;
+                self.apply_config(config);
+            }
+            AppEvent::SetLayout(layout) => {
+                let mut config = self.config.clone();
+                config.layout = layout;
+                self.apply_config(config);
+            }
+            AppEvent::SetPluginMetric(name) => {
+                let mut config = self.config.clone();
+                config.metric = Metric::Plugin;
+                config.metrics.plugin = Some(name);
+                self.apply_config(config);
+            }
+            AppEvent::SetSamplingInterval(interval) => {
+                let mut config = self.config.clone();
+                config.sampling_interval_ms = interval.as_millis() as u64;
+                self.apply_config(config);
+            }
+            AppEvent::SetProfile(profile) => self.set_profile(profile),
+            AppEvent::OomKill(process) => self.oom_kill(process),
+            AppEvent::SpriteLoaded => self.window.request_redraw(),
+            AppEvent::ReloadConfig => self.reload_config(),
+            AppEvent::ToggleCrt => self.toggle_crt(),
+            AppEvent::ToggleBorderless => self.toggle_borderless(),
+            AppEvent::ToggleWidget => self.toggle_widget(),
+            AppEvent::ToggleClickThrough => self.toggle_click_through(),
+            AppEvent::ToggleBaseline => self.toggle_baseline(),
+            AppEvent::DumpState => self.dump_state(),
+            AppEvent::Shortcut(action) => self.run_action(action, control_flow),
+            AppEvent::TogglePause => self.toggle_pause(),
+            AppEvent::TrayClicked if self.hidden => self.show_window(),
+            AppEvent::TrayClicked => self.toggle_pause(),
+            AppEvent::ToggleWindow if self.hidden => self.show_window(),
+            AppEvent::ToggleWindow => self.hide_window(),
+            AppEvent::Quit => {
+                log::info!("{}", tr!("shutting-down"));
+                *control_flow = ControlFlow::Exit;
+            }
+        }
+    }
+
+    /// Scrolling up speeds the animation up, scrolling down slows it down,
+    /// until the lamp is restarted.
+    fn scroll(&mut self, delta: MouseScrollDelta) {
+        self.wheel += match delta {
+            MouseScrollDelta::LineDelta(_, y) => y as f64,
+            MouseScrollDelta::PixelDelta(position) => position.y / PIXELS_PER_NOTCH,
+        };
+        let notches = self.wheel.trunc();
+        if notches == 0.0 {
+            return;
+        }
+        self.wheel -= notches;
+        let step = (self.speed_step as f64 + notches).clamp(0.0, (SPEED_FACTORS.len() - 1) as f64) as usize;
+        let (old, new) = (SPEED_FACTORS[self.speed_step], SPEED_FACTORS[step]);
+        self.speed_step = step;
+        // Laufende Frames gleich umrechnen, nicht erst beim nächsten Sample
+        for lamp in &mut self.lamps {
+            lamp.mapping.frame_duration = lamp.mapping.frame_duration.mul_f64(old / new);
+        }
+        log::info!("{}", tr!("speed-factor", factor = new));
+        self.toast = Some(Toast::new(format!("×{}", new)));
+        self.dirty = true;
+    }
+
+    fn click(&mut self, button: MouseButton, control_flow: &mut ControlFlow) {
+        // Solange das Menü offen ist, wählt jeder Klick darin aus oder schließt es
+        if self.menu.is_some() {
+            match self.cursor {
+                Some(position) if button == MouseButton::Left => self.pick_from_menu(position, control_flow),
+                _ => self.close_menu(),
+            }
+            return;
+        }
+        let action_button = match self.config.click.button {
+            ClickButton::Right => MouseButton::Right,
+            ClickButton::Middle => MouseButton::Middle,
+        };
+        if button == action_button && self.config.click.action != ClickAction::None {
+            launch::run(&self.config.click);
+            return;
+        }
+        if button == MouseButton::Middle {
+            self.toggle_pause();
+            return;
+        }
+        if button != MouseButton::Left {
+            return;
+        }
+        // Der dritte Klick beginnt einen neuen Doppelklick
+        if self.last_click.take().is_some_and(|last| last.elapsed() <= DOUBLE_CLICK) {
+            // Das Andocken nach dem Größenwechsel ist kein Wegziehen
+            self.dragging = false;
+            let size_mode = self.config.size.cycle();
+            log::info!("{}", tr!("scaling-to", size = size_mode.description()));
+            self.set_size_mode(size_mode);
+        } else {
+            self.last_click = Some(Instant::now());
+            // Ohne Titelleiste kann man das Fenster sonst nirgends greifen
+            if self.config.drag_anywhere {
+                self.dragging = self.window.drag_window().is_ok();
+            }
+        }
+    }
+
+    /// The close button or Esc, depending on `on_close`. Quitting from the
+    /// tray menu or with a signal doesn't come here.
+    fn close(&mut self, control_flow: &mut ControlFlow) {
+        match self.config.on_close {
+            CloseBehavior::Confirm if self.close_requested.is_none_or(|at| at.elapsed() > CONFIRM_CLOSE) => {
+                self.close_requested = Some(Instant::now());
+                self.toast = Some(Toast::lasting(tr!("close-confirm"), CONFIRM_CLOSE));
+                self.dirty = true;
+            }
+            // Ohne Tray-Icon käme das Fenster nie wieder zurück
+            CloseBehavior::Tray if self.tray.is_some() || self.xembed.is_some() => self.hide_window(),
+            behavior => {
+                if behavior == CloseBehavior::Tray {
+                    log::warn!("{}", tr!("hide-no-tray"));
+                }
+                log::info!("{}", tr!("shutting-down"));
+                *control_flow = ControlFlow::Exit;
+            }
+        }
+    }
+
+    /// Hides the window; sampling and alerts go on, the tray brings it back.
+    fn hide_window(&mut self) {
+        log::info!("{}", tr!("hidden-to-tray"));
+        self.window.set_visible(false);
+        self.hidden = true;
+        tray::update(&self.tray, |t| t.hidden = true);
+    }
+
+    fn show_window(&mut self) {
+        if !self.hidden {
+            return;
+        }
+        self.hidden = false;
+        tray::update(&self.tray, |t| t.hidden = false);
+        self.window.set_visible(true);
+        self.window.focus_window();
+        self.dirty = true;
+        self.window.request_redraw();
+    }
+
+    /// While hidden nothing is drawn, but sampling goes on for the tray icon,
+    /// the log and alerts.
+    fn sample_hidden(&mut self, control_flow: &mut ControlFlow) {
+        if self.paused {
+            *control_flow = ControlFlow::Wait;
+            return;
+        }
+        if self.last_sample.elapsed() >= self.config.sampling_interval() {
+            self.sample();
+        }
+        *control_flow = ControlFlow::WaitUntil(self.last_sample + self.config.sampling_interval());
+    }
+
+    fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        if self.paused {
+            self.session.interrupt();
+        }
+        log::info!("{}", if self.paused { tr!("monitoring-paused") } else { tr!("monitoring-resumed") });
+        // Mittelklick, Gamepad & Co.: der Haken im Tray soll stimmen
+        let paused = self.paused;
+        tray::update(&self.tray, |t| t.paused = paused);
+    }
+
+    fn run_gesture(&mut self, gesture: Gesture, control_flow: &mut ControlFlow) {
+        match gesture {
+            Gesture::Drag(offset) => {
+                let Ok(position) = self.window.outer_position() else { return };
+                let x = position.x + offset.x.round() as i32;
+                let y = position.y + offset.y.round() as i32;
+                self.window.set_outer_position(PhysicalPosition::new(x, y));
+            }
+            // Wer die Lampe wegzieht, will sie nicht beim nächsten Größenwechsel wieder in der Ecke
+            Gesture::DragEnded if self.config.corner != Corner::None => self.set_corner(Corner::None),
+            Gesture::DragEnded => {}
+            Gesture::Pinch(true) => self.run_action(Action::ScaleUp, control_flow),
+            Gesture::Pinch(false) => self.run_action(Action::ScaleDown, control_flow),
+            Gesture::LongPress => self.open_menu(),
+            Gesture::Tap(position) => self.pick_from_menu(position, control_flow),
+        }
+    }
+
+    /// Opens the context menu with the toggles of the tray menu.
+    fn open_menu(&mut self) {
+        let config = &self.config;
+        let toggle = |key: &str, checked: bool, event: AppEvent| Entry { label: tr!(key), checked: Some(checked), event };
+        let mut entries = vec![
+            toggle("tray-pause", self.paused, AppEvent::TogglePause),
+            toggle("tray-crt", config.effects.crt, AppEvent::ToggleCrt),
+            toggle("tray-borderless", config.borderless, AppEvent::ToggleBorderless),
+            toggle("tray-widget", config.widget, AppEvent::ToggleWidget),
+            toggle("tray-baseline", self.baseline.is_some(), AppEvent::ToggleBaseline),
+        ];
+        // Verstecken nur, wenn ein Tray-Symbol die Lampe zurückholen kann
+        if self.tray.is_some() || self.xembed.is_some() {
+            entries.push(Entry { label: tr!("tray-hide"), checked: None, event: AppEvent::ToggleWindow });
+        }
+        entries.push(Entry { label: tr!("tray-quit"), checked: None, event: AppEvent::Quit });
+        self.menu = Some(ContextMenu::new(entries));
+        self.dirty = true;
+        self.window.request_redraw();
+    }
+
+    fn close_menu(&mut self) {
+        self.menu = None;
+        self.dirty = true;
+        self.window.request_redraw();
+    }
+
+    /// Runs the menu entry at `position` (in window pixels) and closes the menu;
+    /// outside the entries it only closes. Without a menu, nothing happens.
+    fn pick_from_menu(&mut self, position: PhysicalPosition<f64>, control_flow: &mut ControlFlow) {
+        let Some(menu) = self.menu.take() else { return };
+        self.close_menu();
+        let (slots, side) = self.buffer;
+        let Ok((x, y)) = self.pixels.window_pos_to_pixel((position.x as f32, position.y as f32)) else { return };
+        if let Some(event) = menu.event_at(x, y, slots * side, side) {
+            self.handle_app_event(event, control_flow);
+        }
+    }
+
+    fn set_corner(&mut self, corner: Corner) {
+        log::info!("{}", tr!("docking-to", corner = corner.description()));
+        let mut config = self.config.clone();
+        config.corner = corner;
+        self.apply_config(config);
+    }
+
+    fn set_size_mode(&mut self, size_mode: WindowSizeMode) {
+        let mut config = self.config.clone();
+        config.size = size_mode;
+        self.apply_config(config);
+    }
+
+    /// Ctrl+R or the tray: switches the CRT filter on or off until the next
+    /// config reload or profile switch.
+    fn toggle_crt(&mut self) {
+        let mut config = self.config.clone();
+        config.effects.crt = !config.effects.crt;
+        log::info!("{}", if config.effects.crt { tr!("crt-on") } else { tr!("crt-off") });
+        self.apply_config(config);
+    }
+
+    /// Ctrl+B or the tray: removes or restores the title bar and border until
+    /// the next config reload or profile switch.
+    fn toggle_borderless(&mut self) {
+        let mut config = self.config.clone();
+        config.borderless = !config.borderless;
+        log::info!("{}", if config.borderless { tr!("borderless-on") } else { tr!("borderless-off") });
+        self.apply_config(config);
+    }
+
+    /// Ctrl+W or the tray: switches widget mode on, or back to the settings
+    /// from before. Lasts until the config is reloaded or the profile changes.
+    fn toggle_widget(&mut self) {
+        let mut config = self.config.clone();
+        if config.widget {
+            // Mit `widget = true` aus der Config gibt es kein Vorher, dann gelten die Standardwerte
+            let before = self.before_widget.take().unwrap_or_default();
+            config.leave_widget_mode(&before);
+            log::info!("{}", tr!("widget-off"));
+        } else {
+            self.before_widget = Some(self.config.clone());
+            config.apply_widget_preset();
+            log::info!("{}", tr!("widget-on"));
+        }
+        self.apply_config(config);
+    }
+
+    /// Ctrl+T or the tray: clicks go through the lamp to the windows below, or
+    /// reach it again. While they go through, the tray is the way back.
+    fn toggle_click_through(&mut self) {
+        let mut config = self.config.clone();
+        config.click_through = !config.click_through;
+        log::info!("{}", if config.click_through { tr!("click-through-on") } else { tr!("click-through-off") });
+        self.apply_config(config);
+    }
+
+    /// Ctrl+S or the tray: remembers the current usage, or forgets it again.
+    /// While a baseline is set, a banner shows how far the usage moved since.
+    fn toggle_baseline(&mut self) {
+        match self.baseline_delta() {
+            Some(delta) => {
+                log::info!("{}", tr!("baseline-cleared", delta = delta));
+                self.baseline = None;
+            }
+            None => {
+                let metric = self.config.layout.metric(self.config.metric);
+                let percent = self.average_percent();
+                log::info!("{}", tr!("baseline-taken", percent = format!("{:.1}", percent), metric = metric.description()));
+                self.baseline = Some((metric, percent));
+            }
+        }
+        let baseline = self.baseline.is_some();
+        tray::update(&self.tray, |t| t.baseline = baseline);
+        self.dirty = true;
+    }
+
+    /// Change since the baseline: in MB for memory metrics, else in percentage points.
+    fn baseline_delta(&self) -> Option<String> {
+        let (metric, start) = self.baseline?;
+        let delta = self.average_percent() - start;
+        Some(match self.sampler.capacity_mib(metric, &self.config.metrics) {
+            Some(mib) => format!("{:+.0} MB", delta / 100.0 * mib),
+            None => format!("{:+.1}%", delta),
+        })
+    }
+
+    /// Mean over all lamps; the only lamp's value in the single layout.
+    fn average_percent(&self) -> f64 {
+        self.lamps.iter().map(|l| l.percent).sum::<f64>() / self.lamps.len().max(1) as f64
+    }
+
+    /// Logs what the lamp is doing right now, for debugging instances that
+    /// have been running for days (`SIGUSR1`).
+    fn dump_state(&self) {
+        let metric = self.config.layout.metric(self.config.metric);
+        let none = || "-".to_string();
+        log::info!("{}", tr!("state-header"));
+        log::info!("  {}", tr!("state-metric", metric = metric.description(), layout = self.config.layout.description()));
+        for (slot, lamp) in self.lamps.iter().enumerate() {
+            log::info!(
+                "  {}",
+                tr!(
+                    "state-lamp",
+                    slot = slot + 1,
+                    percent = format!("{:.1}", lamp.percent),
+                    color = tr!(lamp.mapping.tier.color_key()),
+                    sheet = lamp.mapping.sprite_file(&self.extreme_sheets),
+                    frame = lamp.frame_index,
+                    ms = lamp.step().as_millis()
+                )
+            );
+        }
+        log::info!(
+            "  {}",
+            tr!(
+                "state-settings",
+                theme = self.config.active_theme().map_or_else(|| tr!("theme-classic"), String::from),
+                profile = self.profile.clone().unwrap_or_else(none),
+                size = self.config.size.description(),
+                interval = self.config.sampling_interval_ms
+            )
+        );
+        log::info!("  {}", tr!("state-paused", paused = if self.paused { tr!("state-yes") } else { tr!("state-no") }));
+        let config = config::config_path().map_or_else(none, |path| path.display().to_string());
+        let log_file = match &self.config.log {
+            log if log.enabled => log.path.clone().or_else(logging::default_log_path),
+            _ => None,
+        };
+        log::info!("  {}", tr!("state-config", path = config));
+        log::info!("  {}", tr!("state-log", path = log_file.map_or_else(none, |path| path.display().to_string())));
+        log::info!("  {}", tr!("state-script", path = self.config.script_path().map_or_else(none, |path| path.display().to_string())));
+    }
+
+    /// Ctrl+P: default → first profile → ... → last profile → default.
+    fn cycle_profile(&mut self) {
+        let names: Vec<String> = self.base_config.profiles.keys().cloned().collect();
+        if names.is_empty() {
+            log::info!("{}", tr!("profile-none-defined"));
+            return;
+        }
+
+        let next = match &self.profile {
+            None => names.first().cloned(),
+            Some(current) => names.iter().skip_while(|name| *name != current).nth(1).cloned(),
+        };
+        self.set_profile(next);
+    }
+
+    fn set_profile(&mut self, profile: Option<String>) {
+        log::info!(
+            "{}",
+            tr!("switching-profile", profile = profile.clone().unwrap_or_else(|| tr!("profile-default")))
+        );
+        let config = self.base_config.with_profile(profile.as_deref());
+        self.profile = profile;
+        let profile = self.profile.clone();
+        tray::update(&self.tray, |t| t.profile = profile);
+        self.apply_config(config);
+    }
+
+    fn oom_kill(&mut self, process: Option<String>) {
+        let body = match &process {
+            Some(process) => tr!("oom-killed", process = process),
+            None => tr!("oom-killed-unknown"),
+        };
+        log::warn!("{}", tr!("oom-alert", message = &body));
+        if !self.config.alerts.oom_kill {
+            return;
+        }
+        if self.config.alerts.notify && self.throttle.allow(Kind::OomKill, &self.config.alerts) {
+            alerts::notify(tr!("oom-notification-title"), body);
+        }
+        self.alert = Some(Instant::now());
+    }
+
+    /// The trend reaches 100% in `left`: says so over the lamp, in the log
+    /// and as a notification.
+    fn predict_full(&mut self, metric: Metric, left: Duration) {
+        let minutes = (left.as_secs_f64() / 60.0).ceil().max(1.0);
+        let message = tr!("forecast-full", metric = metric.description(), minutes = minutes);
+        log::warn!("{}", tr!("oom-alert", message = &message));
+        if self.config.alerts.notify && self.throttle.allow(Kind::Forecast, &self.config.alerts) {
+            alerts::notify(tr!("forecast-notification-title"), message.clone());
+        }
+        self.toast = Some(Toast::lasting(message, self.config.alerts.duration()));
+    }
+
+    /// Re-reads the config file (plus environment and command line overrides) and applies it live.
+    /// The active profile is kept if it still exists; hotkey/tray changes are discarded.
+    fn reload_config(&mut self) {
+        let Some(mut base_config) = Config::reload() else { return };
+        base_config.apply_env_overrides();
+        self.args.apply(&mut base_config);
+
+        let profile = match &self.profile {
+            Some(current) if base_config.profiles.contains_key(current) => Some(current.clone()),
+            _ => base_config.profile.clone(),
+        };
+        let config = base_config.with_profile(profile.as_deref());
+        self.base_config = base_config;
+        self.profile = profile.clone();
+
+        let profiles: Vec<String> = self.base_config.profiles.keys().cloned().collect();
+        // Neue Themes sollen ohne Neustart im Menü auftauchen
+        let themes = assets::list_themes(&config.assets.dirs);
+        tray::update(&self.tray, |t| {
+            t.profiles = profiles;
+            t.profile = profile;
+            t.themes = themes;
+        });
+        self.apply_config(config);
+        // Auch bei unverändertem Pfad neu laden, das Skript selbst kann sich geändert haben
+        self.script = load_script(&self.config);
+    }
+
+    /// Checks all sheets of the current theme up front and reports every problem at once.
+    fn check_sprites(&mut self) {
+        let theme = self.config.active_theme();
+        sprites::report_problems(theme, &sprites::check_theme(theme, &self.config.assets));
+        self.vector_theme = sprites::has_vector(theme, &self.config.assets);
+        self.extreme_sheets = Extreme::ALL
+            .into_iter()
+            .filter(|extreme| sprites::has_sheet(theme, extreme.sprite_file(), &self.config.assets))
+            .collect();
+    }
+
+    /// Sizes the frame buffer for the current lamps: 128×128 per lamp, or the
+    /// size they're shown at when vector sheets can be rendered sharper.
+    /// Pixel art stays at 128 px per lamp as long as the window is a whole
+    /// multiple of it, so `pixels` can scale it up sharply; at fractional
+    /// scale factors (192 px at 150 %) and for vector themes the buffer has
+    /// the window's physical size instead, so nothing is blurred or cut off.
+    fn resize_buffer(&mut self) {
+        let count = self.lamps.len().max(1);
+        let physical = window_size(self.config.size, count).to_physical::<u32>(self.scale_factor).height as usize;
+        let side = if self.vector_theme || !physical.is_multiple_of(WINDOW_SIZE) { physical } else { WINDOW_SIZE };
+        if (count, side) == self.buffer {
+            return;
+        }
+        self.buffer = (count, side);
+        if let Err(e) = self.pixels.resize_buffer((count * side) as u32, side as u32) {
+            log::error!("{}", tr!("resize-failed", error = e));
+        }
+        self.dirty = true;
+    }
+
+    /// Switches to `new`, applying only what actually changed.
+    pub fn apply_config(&mut self, new: Config) {
+        let old = std::mem::replace(&mut self.config, new);
+        let new = &self.config;
+
+        if new.language != old.language {
+            if let Some(language) = &new.language {
+                i18n::set_language(language);
+            }
+        }
+        if new.log != old.log {
+            logging::configure(&new.log);
+        }
+        if new.size != old.size {
+            apply_size_mode(&self.window, new.size, self.lamps.len());
+        }
+        if new.size != old.size || new.corner != old.corner {
+            dock_to_corner(&self.window, new.corner, new.size, self.lamps.len());
+        }
+        if new.borderless != old.borderless {
+            self.window.set_decorations(!new.borderless);
+            // Ohne Rahmen ist das Fenster kleiner, in der Ecke bleibt sonst eine Lücke
+            dock_to_corner(&self.window, new.corner, new.size, self.lamps.len());
+        }
+        if new.exclude_from_capture != old.exclude_from_capture {
+            capture::set_excluded(&self.window, new.exclude_from_capture);
+        }
+        if new.always_on_top != old.always_on_top {
+            widget::set_always_on_top(&self.window, new.always_on_top);
+        }
+        if new.skip_taskbar != old.skip_taskbar {
+            widget::set_skip_taskbar(&self.window, new.skip_taskbar);
+        }
+        if new.sticky != old.sticky {
+            widget::set_sticky(&self.window, new.sticky);
+        }
+        if new.click_through != old.click_through {
+            widget::set_click_through(&self.window, new.click_through);
+        }
+        let sheets_changed = new.assets != old.assets || new.active_theme() != old.active_theme();
+        if new.assets != old.assets {
+            self.sprites.clear();
+            self.dirty = true;
+        }
+        if new.active_theme() != old.active_theme() {
+            log::info!(
+                "{}",
+                tr!("switching-theme", theme = new.active_theme().map_or_else(|| tr!("theme-classic"), String::from))
+            );
+            // Erzwingt ein Neuladen der Sprites beim nächsten Redraw
+            self.sprites.clear();
+            self.dirty = true;
+        }
+        if new.effects != old.effects || new.background != old.background {
+            self.dirty = true;
+        }
+        if new.metric != old.metric || new.layout != old.layout {
+            // Der Trend einer anderen Messgröße sagt nichts, der Vergleichswert auch nicht
+            self.forecast = Forecast::default();
+            if self.baseline.take().is_some() {
+                log::info!("{}", tr!("baseline-dropped"));
+                tray::update(&self.tray, |t| t.baseline = false);
+            }
+        }
+        if new.metric != old.metric {
+            log::info!("{}", tr!("switching-metric", metric = new.metric.description()));
+        }
+        let resample = new.metric != old.metric
+            || new.metrics != old.metrics
+            || new.layout != old.layout
+            || new.indicators != old.indicators
+            || new.background.breakdown != old.background.breakdown;
+        if new.background_image_path() != old.background_image_path() {
+            self.background.load(new.background_image_path().as_deref());
+        }
+        if new.script_path() != old.script_path() {
+            self.script = load_script(new);
+        }
+        if new.sampling_interval_ms != old.sampling_interval_ms {
+            log::info!("{}", tr!("sampling-every", ms = new.sampling_interval_ms));
+        }
+
+        crash::set_config(&toml::to_string_pretty(new).unwrap_or_default());
+        let config = new.clone();
+        tray::update(&self.tray, |t| t.sync(&config));
+
+        if sheets_changed {
+            self.check_sprites();
+        }
+        if resample {
+            self.sample();
+        }
+        self.resize_buffer();
+    }
+
+    /// Samples every lamp. The window and frame buffer grow or shrink when the
+    /// number of lamps changes (layout switch, CPU hotplug).
+    fn sample(&mut self) {
+        let layout = self.config.layout;
+        let metric = layout.metric(self.config.metric);
+        let indicator_metrics = self.config.indicators.iter().map(|indicator| indicator.metric);
+        self.sampler.keep_open(indicator_metrics.chain([metric]).collect());
+        let mut values = self.sampler.sample_lamps(layout, self.config.metric, &self.config.metrics);
+        if values.is_empty() {
+            values.push(0.0);
+        }
+        self.last_sample = Instant::now();
+
+        // Neu hinzugekommene Lampen zählen nicht als Farbwechsel
+        let existing = self.lamps.len().min(values.len());
+        if values.len() != self.lamps.len() {
+            if !self.lamps.is_empty() {
+                log::info!("{}", tr!("switching-layout", layout = layout.description(), lamps = values.len()));
+            }
+            self.lamps.truncate(values.len());
+            self.lamps.resize_with(values.len(), || Lamp::new(0.0, Mapping::default_for(0.0, &self.config)));
+
+            let count = self.lamps.len();
+            self.resize_buffer();
+            apply_size_mode(&self.window, self.config.size, count);
+            dock_to_corner(&self.window, self.config.corner, self.config.size, count);
+        }
+
+        let speed_factor = SPEED_FACTORS[self.speed_step];
+        for (slot, (lamp, percent)) in self.lamps.iter_mut().zip(values).enumerate() {
+            let mut mapping = match &mut self.script {
+                Some(script) => script.map(percent, metric.id(), &self.config),
+                None => Mapping::default_for(percent, &self.config),
+            };
+            mapping.frame_duration = mapping.frame_duration.div_f64(speed_factor);
+            // Bei vielen Lampen wäre jeder Farbwechsel im Log zu viel
+            if layout == Layout::Single && mapping.tier != lamp.mapping.tier {
+                log::info!("{}", tr!(
+                    "switching-lava",
+                    color = tr!(mapping.tier.color_key()),
+                    percent = format!("{:.1}", percent),
+                    metric = metric.description(),
+                ));
+            }
+            if let Some(extreme) = mapping.extreme {
+                if layout == Layout::Single && mapping.extreme != lamp.mapping.extreme {
+                    let percent = format!("{:.1}", percent);
+                    log::info!("{}", tr!(extreme.message_key(), percent = percent, metric = metric.description()));
+                }
+            }
+            // Ohne eigenes Sheet ändert sich sonst nichts, was `presented` bemerken würde
+            if mapping.extreme != lamp.mapping.extreme {
+                self.dirty = true;
+            }
+            if self.config.effects.particles && slot < existing && mapping.tier != lamp.mapping.tier {
+                self.particles.burst(slot, lamp.mapping.tier, mapping.tier, self.config.effects.particle_count);
+            }
+            lamp.record(percent, &self.config.speed);
+            lamp.mapping = mapping;
+        }
+
+        self.breakdown = None;
+        let metrics = &self.config.metrics;
+        let single_ram = layout == Layout::Single
+            && metric == Metric::Ram
+            && metrics.numa_node.is_none()
+            && metrics.process.is_none();
+        if self.config.background.breakdown && single_ram {
+            match Breakdown::read(self.config.metrics.exclude_zfs_arc) {
+                Ok(breakdown) => self.breakdown = Some(breakdown),
+                Err(e) => log::debug!("{}", e),
+            }
+        }
+        // Der Füllstand ändert sich mit jedem Sample, nicht nur mit dem Frame
+        if self.config.background.level || self.breakdown.is_some() {
+            self.dirty = true;
+        }
+        if self.baseline.is_some() {
+            self.dirty = true;
+        }
+        let percents: Vec<f64> = self.lamps.iter().map(|l| l.percent).collect();
+        if self.indicators.sample(&mut self.sampler, &self.config, metric, &percents) {
+            self.dirty = true;
+        }
+
+        let peak = self.lamps.iter().map(|l| l.percent).fold(0.0, f64::max);
+        log::debug!("{}: {:.1}%", metric.description(), peak);
+        crash::record_sample(metric.id(), peak);
+        let tier = self.lamps.iter().map(|l| l.mapping.tier).max().unwrap_or(Tier::Green);
+        self.session.record(&metric.description(), peak, tier);
+        if layout == Layout::Single && self.config.alerts.predict {
+            if let Some(left) = self.forecast.record(self.last_sample, peak, &self.config.alerts) {
+                self.predict_full(metric, left);
+            }
+        }
+        self.badge.show(self.config.badge.mode, peak, tier);
+        tray::update(&self.tray, |t| t.level = Some((peak, tier)));
+        if let Some(xembed) = &self.xembed {
+            xembed.show(peak, tier);
+        }
+    }
+
+    /// When the loop has to wake up next: for the next frame or sample, for a
+    /// finger held down turning into a long press, or to clear a message.
+    fn next_wakeup(&self, now: Instant) -> ControlFlow {
+        let flow = self.next_frame_wakeup(now);
+        let due = [self.gestures.long_press_due(), self.toast.as_ref().map(Toast::until)].into_iter().flatten().min();
+        match (flow, due) {
+            (ControlFlow::WaitUntil(next), Some(due)) => ControlFlow::WaitUntil(next.min(due)),
+            (ControlFlow::Wait, Some(due)) => ControlFlow::WaitUntil(due),
+            _ => flow,
+        }
+    }
+
+    /// The first lamp due for its next animation frame, or the next sample, but
+    /// not before `max_fps` allows the next frame. While paused nothing is due at all.
+    fn next_frame_wakeup(&self, now: Instant) -> ControlFlow {
+        let earliest = self.last_present + self.config.min_frame_interval();
+        if self.alert.is_some() {
+            // Das Pulsieren läuft mit der Bildwiederholrate (vsync bremst), oder mit max_fps
+            return match self.config.max_fps {
+                Some(_) => ControlFlow::WaitUntil(earliest.max(now)),
+                None => ControlFlow::Poll,
+            };
+        }
+        if self.paused {
+            return ControlFlow::Wait;
+        }
+        if self.particles.is_active() {
+            return ControlFlow::WaitUntil((now + particles::STEP).max(earliest));
+        }
+        if self.config.effects.interpolate {
+            return ControlFlow::WaitUntil((now + lamp::CROSSFADE_STEP).max(earliest));
+        }
+        if self.lamps.iter().any(|l| l.mapping.dozing(&self.extreme_sheets)) {
+            return ControlFlow::WaitUntil((now + lamp::DOZE_STEP).max(earliest));
+        }
+        let next_sample = self.config.sampling_interval().saturating_sub(self.last_sample.elapsed());
+        let next_frame = self
+            .lamps
+            .iter()
+            .map(|l| l.step().saturating_sub(l.accumulator))
+            .min()
+            .unwrap_or(next_sample);
+        ControlFlow::WaitUntil((now + next_frame.min(next_sample)).max(earliest))
+    }
+
+    /// Winit has no event for monitors being plugged in or out, so the layout is
+    /// compared every few seconds. When it changed, a docked lamp is docked
+    /// again, and a lamp whose screen is gone is moved to the primary one.
+    fn check_monitors(&mut self) {
+        self.last_monitor_check = Instant::now();
+        let layout = monitor_layout(&self.window);
+        // Leere Liste: Plattform kennt keine Monitore (oder gerade mitten im Umschalten)
+        if layout.is_empty() || layout == self.monitors {
+            return;
+        }
+        self.monitors = layout;
+        log::info!("{}", tr!("monitors-changed", count = self.monitors.len()));
+
+        let count = self.lamps.len().max(1);
+        if self.on_screen() {
+            dock_to_corner(&self.window, self.config.corner, self.config.size, count);
+            return;
+        }
+        let Some(monitor) = self.window.primary_monitor().or_else(|| self.window.available_monitors().next()) else {
+            return;
+        };
+        log::info!("{}", tr!("window-moved-to-screen", name = monitor.name().unwrap_or_else(|| "?".to_string())));
+        dock_on(&self.window, &monitor, self.config.corner, self.config.size, count);
+    }
+
+    /// Whether the middle of the window is on one of the monitors. Where the
+    /// position is unknown (Wayland), the compositor takes care of it.
+    fn on_screen(&self) -> bool {
+        let Ok(position) = self.window.outer_position() else { return true };
+        let size = self.window.outer_size();
+        let x = position.x + size.width as i32 / 2;
+        let y = position.y + size.height as i32 / 2;
+        self.monitors.iter().any(|(origin, screen)| {
+            (origin.x..origin.x + screen.width as i32).contains(&x) && (origin.y..origin.y + screen.height as i32).contains(&y)
+        })
+    }
+
+    fn redraw(&mut self, control_flow: &mut ControlFlow) {
+        if self.hidden || self.watchdog.gave_up() {
+            return;
+        }
+        if self.last_monitor_check.elapsed() >= MONITOR_CHECK_INTERVAL {
+            self.check_monitors();
+        }
+        if let Some(gesture) = self.gestures.poll(Instant::now()) {
+            self.run_gesture(gesture, control_flow);
+        }
+        if self.toast.as_ref().is_some_and(|toast| toast.until() <= Instant::now()) {
+            self.toast = None;
+            self.dirty = true;
+        }
+        if !self.paused && self.last_sample.elapsed() >= self.config.sampling_interval() {
+            self.sample();
+        }
+
+        let now = Instant::now();
+        let elapsed = if self.paused { Duration::ZERO } else { now - self.last_frame };
+        self.last_frame = now;
+
+        self.sprites.tick(self.config.assets.cache_budget_mb);
+        lamp::tick_all(&mut self.lamps, &mut self.sprites, &self.config, &self.extreme_sheets, elapsed);
+
+        if self.particles.tick(elapsed) {
+            self.dirty = true;
+        }
+
+        if self.alert.is_some_and(|started| started.elapsed() >= self.config.alerts.duration()) {
+            self.alert = None;
+            self.dirty = true;
+        }
+
+        // Nichts geändert: alten Puffer stehen lassen und bis zum nächsten Frame/Sample schlafen
+        let shown: Vec<(&'static str, usize, bool)> = self
+            .lamps
+            .iter()
+            .map(|l| {
+                let file = l.mapping.sprite_file(&self.extreme_sheets);
+                (file, l.frame_index, self.sprites.is_loading(file))
+            })
+            .collect();
+        *control_flow = self.next_wakeup(now);
+        let always_redraw = !self.paused
+            && (self.config.effects.interpolate || self.lamps.iter().any(|l| l.mapping.dozing(&self.extreme_sheets)));
+        if !self.dirty && self.alert.is_none() && !always_redraw && shown == self.presented {
+            return;
+        }
+        // Zu früh für max_fps: die Lampen sind schon weitergezählt, gezeigt wird beim nächsten Aufwachen
+        let earliest = self.last_present + self.config.min_frame_interval();
+        if now < earliest {
+            *control_flow = ControlFlow::WaitUntil(earliest);
+            return;
+        }
+
+        let (slots, side) = self.buffer;
+        let baseline_delta = self.baseline_delta();
+        let frame = self.pixels.frame_mut();
+
+        self.background.draw(frame, slots, side, &self.lamps, self.breakdown.as_ref(), &self.config.background);
+        lamp::draw_all(frame, slots, side, &mut self.lamps, &mut self.sprites, &self.config, &self.extreme_sheets);
+        self.particles.draw(frame, slots, side);
+        self.indicators.draw(frame, slots, side, &self.config.indicators);
+        self.post.run(frame, slots * side, side, &self.config.effects);
+
+        if let Some(started) = self.alert {
+            alerts::tint(frame, started.elapsed());
+        }
+        if let Some(delta) = &baseline_delta {
+            overlay::draw_banner(frame, slots * side, side, delta);
+        }
+        if let Some(toast) = &self.toast {
+            toast.draw(frame, slots * side, side);
+        }
+        if let Some(menu) = &self.menu {
+            menu.draw(frame, slots * side, side);
+        }
+
+        self.presented = shown;
+        self.dirty = false;
+        self.last_present = now;
+
+        match self.pixels.render() {
+            Ok(()) => self.watchdog.succeeded(),
+            Err(e) => {
+                log::error!("{}", tr!("render-failed", error = e));
+                self.render_failed(control_flow);
+            }
+        }
+    }
+
+    fn render_failed(&mut self, control_flow: &mut ControlFlow) {
+        // Der nächste Versuch zeichnet alles neu
+        self.dirty = true;
+        match self.watchdog.failed(&self.config.renderer) {
+            Verdict::Retry => {}
+            Verdict::Restart { software } => self.restart_renderer(software),
+            Verdict::GiveUp => log::error!("{}", tr!("renderer-gave-up")),
+            Verdict::Quit => *control_flow = ControlFlow::Exit,
+        }
+    }
+
+    /// Replaces the renderer with a new one for the same window and buffer.
+    /// If that fails too, the old one stays and the next failed frame counts again.
+    fn restart_renderer(&mut self, software: bool) {
+        log::warn!("{}", if software { tr!("renderer-restarting-software") } else { tr!("renderer-restarting") });
+        let (slots, side) = self.buffer;
+        let transparent = self.transparent_window;
+        match crate::build_pixels(&self.window, (slots * side) as u32, side as u32, self.config.vsync, software, transparent) {
+            Ok(pixels) => {
+                self.pixels = pixels;
+                let info = crate::backend_info(&self.window, &self.pixels);
+                log::info!("{}", tr!("renderer-restarted", backend = &info));
+                crash::set_backend_info(info);
+            }
+            Err(e) => log::error!("{}", tr!("renderer-restart-failed", error = e)),
+        }
+    }
+}
+
+fn monitor_layout(window: &Window) -> Vec<(PhysicalPosition<i32>, PhysicalSize<u32>)> {
+    window.available_monitors().map(|monitor| (monitor.position(), monitor.size())).collect()
+}
+
+pub fn load_script(config: &Config) -> Option<Script> {
+    let path = config.script_path()?;
+    match Script::load(&path) {
+        Ok(script) => {
+            log::info!("{}", tr!("script-loaded", path = script.path().display()));
+            Some(script)
+        }
+        Err(e) => {
+            log::error!("{}", tr!("script-load-failed", path = path.display(), error = e));
+            None
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! Aseprite files (`.aseprite` / `.ase`) as sprite sheets, so pixel artists
+//! don't have to export strips.
+//!
+//! A theme either ships one file per color (`lavalampe_green.aseprite` etc.)
+//! or a single `lavalampe.aseprite` with a tag per color, named `green`,
+//! `yellow`, `orange` and `red`. A color uses the frames of its tag (in the
+//! tag's direction, so ping-pong loops work), or every frame if the file has
+//! no such tag. Frames keep the durations set in Aseprite, and are scaled to
+//! 128×128 with nearest neighbor so pixel art stays crisp.
+
+use asefile::{AnimationDirection, AsepriteFile};
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+use std::time::Duration;
+
+use crate::i18n::tr;
+use crate::tier::Tier;
+use crate::{ANIMATION_FRAMES, WINDOW_SIZE};
+
+/// File names of a file shared by all colors, in lookup order.
+pub const SHARED_FILES: [&str; 2] = ["lavalampe.aseprite", "lavalampe.ase"];
+
+/// The frames of one color side by side, like a PNG strip.
+pub struct Strip {
+    pub pixels: Vec<u8>,
+    pub width: usize,
+    /// How long each frame is shown.
+    pub durations: Vec<Duration>,
+}
+
+/// Reads the frames of `tier` from the Aseprite file `bytes`.
+pub fn load(bytes: &[u8], tier: Tier) -> Result<Strip, String> {
+    let file = AsepriteFile::read(bytes).map_err(|e| e.to_string())?;
+    let mut frames = frame_order(&file, tier);
+    frames.truncate(ANIMATION_FRAMES);
+    if frames.is_empty() {
+        return Err(tr!("aseprite-no-frames"));
+    }
+
+    let mut strip = RgbaImage::new((frames.len() * WINDOW_SIZE) as u32, WINDOW_SIZE as u32);
+    let mut durations = Vec::with_capacity(frames.len());
+    for (i, &index) in frames.iter().enumerate() {
+        let frame = file.frame(index);
+        let mut image = frame.image();
+        if image.dimensions() != (WINDOW_SIZE as u32, WINDOW_SIZE as u32) {
+            image = imageops::resize(&image, WINDOW_SIZE as u32, WINDOW_SIZE as u32, FilterType::Nearest);
+        }
+        imageops::replace(&mut strip, &image, (i * WINDOW_SIZE) as i64, 0);
+        // Aseprite erlaubt 0 ms, das würde die Animation einfrieren lassen
+        durations.push(Duration::from_millis(frame.duration().max(1) as u64));
+    }
+
+    let width = strip.width() as usize;
+    Ok(Strip { pixels: strip.into_raw(), width, durations })
+}
+
+/// Frame count of `tier`, for checking a theme without rendering every frame.
+pub fn frame_count(bytes: &[u8], tier: Tier) -> Result<usize, String> {
+    let file = AsepriteFile::read(bytes).map_err(|e| e.to_string())?;
+    match frame_order(&file, tier).len() {
+        0 => Err(tr!("aseprite-no-frames")),
+        frames => Ok(frames.min(ANIMATION_FRAMES)),
+    }
+}
+
+/// Indices of the frames `tier` shows, in playback order.
+fn frame_order(file: &AsepriteFile, tier: Tier) -> Vec<u32> {
+    let tag = (0..file.num_tags()).map(|i| file.tag(i)).find(|tag| tag.name().eq_ignore_ascii_case(tier.name()));
+    let Some(tag) = tag else {
+        return (0..file.num_frames()).collect();
+    };
+
+    let forward: Vec<u32> = (tag.from_frame()..=tag.to_frame()).collect();
+    match tag.animation_direction() {
+        AnimationDirection::Forward => forward,
+        AnimationDirection::Reverse => forward.into_iter().rev().collect(),
+        AnimationDirection::PingPong => {
+            // Zurück ohne die Endpunkte, sonst stehen sie doppelt so lange
+            let end = forward.len().saturating_sub(1).max(1);
+            forward.iter().chain(forward[1..end].iter().rev()).copied().collect()
+        }
+    }
+}
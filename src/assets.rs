@@ -0,0 +1,195 @@
+//! Where sprite sheets and themes are looked up.
+//!
+//! Directories are searched in this order, the first match wins:
+//!
+//! 1. `--assets-dir <dir>` (may be given several times)
+//! 2. `[assets] dirs` in the config, or `LAVALAMPE_ASSETS_DIRS`
+//! 3. `assets/` in the working directory
+//! 4. `assets/` next to the executable, its parent and grandparent
+//!    (covers `target/debug` and `target/release`)
+//! 5. `assets/` in the data directory (see [`paths`](crate::paths))
+//! 6. `ram-lavalampe/assets` in every entry of `$XDG_DATA_DIRS`
+//!    (`/usr/local/share:/usr/share` if unset; Linux and BSD only)
+//!
+//! If a classic sheet is in none of them, the copy built into the executable
+//! is used, so the binary alone is enough to run the lamp.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::i18n::tr;
+use crate::paths;
+
+/// The classic sheets, built into the executable.
+const EMBEDDED: [(&str, &[u8]); 4] = [
+    ("lavalampe_green.png", include_bytes!("../assets/lavalampe_green.png")),
+    ("lavalampe_yellow.png", include_bytes!("../assets/lavalampe_yellow.png")),
+    ("lavalampe_orange.png", include_bytes!("../assets/lavalampe_orange.png")),
+    ("lavalampe_red.png", include_bytes!("../assets/lavalampe_red.png")),
+];
+
+lazy_static::lazy_static! {
+    /// Directories from `--assets-dir`. Set before the config is loaded, so
+    /// themes found there pass validation.
+    static ref FLAG_DIRS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+}
+
+/// Why a directory is on the search path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Flag,
+    Config,
+    WorkingDir,
+    Executable,
+    DataDir,
+    DataDirs,
+}
+
+impl Source {
+    pub fn description(&self) -> String {
+        match self {
+            Source::Flag => "--assets-dir".into(),
+            Source::Config => tr!("assets-source-config"),
+            Source::WorkingDir => tr!("assets-source-working-dir"),
+            Source::Executable => tr!("assets-source-executable"),
+            Source::DataDir => tr!("assets-source-data-dir"),
+            Source::DataDirs => "XDG_DATA_DIRS".into(),
+        }
+    }
+}
+
+pub fn set_flag_dirs(dirs: Vec<PathBuf>) {
+    *FLAG_DIRS.lock().unwrap() = dirs;
+}
+
+/// All directories that may contain sprite sheets and a `themes` folder, in
+/// search order. `configured` are the `[assets] dirs` from the config.
+pub fn search_path(configured: &[PathBuf]) -> Vec<(PathBuf, Source)> {
+    let mut dirs: Vec<(PathBuf, Source)> = Vec::new();
+    let mut push = |dir: PathBuf, source: Source| {
+        if !dir.as_os_str().is_empty() && !dirs.iter().any(|(d, _)| *d == dir) {
+            dirs.push((dir, source));
+        }
+    };
+
+    for dir in FLAG_DIRS.lock().unwrap().iter() {
+        push(dir.clone(), Source::Flag);
+    }
+    for dir in configured {
+        push(dir.clone(), Source::Config);
+    }
+    push(PathBuf::from("assets"), Source::WorkingDir);
+
+    if let Ok(exe_path) = env::current_exe() {
+        for dir in exe_path.ancestors().skip(1).take(3) {
+            push(dir.join("assets"), Source::Executable);
+        }
+    }
+
+    if let Some(dir) = paths::data_dir() {
+        push(dir.join("assets"), Source::DataDir);
+    }
+
+    // Systemweite Installationen (Distributionspakete), nur unter XDG
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        let data_dirs = env::var_os("XDG_DATA_DIRS")
+            .filter(|dirs| !dirs.is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".into());
+        for dir in env::split_paths(&data_dirs) {
+            push(dir.join("ram-lavalampe/assets"), Source::DataDirs);
+        }
+    }
+
+    dirs
+}
+
+/// Looks up a sprite sheet, preferring the theme's own copy over the classic
+/// one. In each place, `filenames` are tried in order.
+pub fn find(configured: &[PathBuf], theme: Option<&str>, filenames: &[&str]) -> Option<PathBuf> {
+    let dirs = search_path(configured);
+
+    if let Some(theme) = theme {
+        for (dir, _) in &dirs {
+            for filename in filenames {
+                let path = dir.join("themes").join(theme).join(filename);
+                if path.exists() { return Some(path); }
+            }
+        }
+    }
+
+    for (dir, _) in &dirs {
+        for filename in filenames {
+            let path = dir.join(filename);
+            if path.exists() { return Some(path); }
+        }
+    }
+    None
+}
+
+/// The built-in copy of the classic sheet `filename`.
+pub fn embedded(filename: &str) -> Option<(&'static str, &'static [u8])> {
+    EMBEDDED.into_iter().find(|(name, _)| *name == filename)
+}
+
+/// Writes the built-in sheets to `dir` (`--extract-assets`). Existing files
+/// are left alone, so a half-finished theme isn't overwritten.
+pub fn extract(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for (name, bytes) in EMBEDDED {
+        let path = dir.join(name);
+        if path.exists() {
+            println!("{}", tr!("extract-skipped", path = path.display()));
+            continue;
+        }
+        fs::write(&path, bytes)?;
+        println!("{}", tr!("extract-written", path = path.display()));
+    }
+    if let Some(themes) = paths::data_dir().map(|dir| dir.join("assets").join("themes")) {
+        println!();
+        println!("{}", tr!("extract-hint", themes = themes.display()));
+    }
+    Ok(())
+}
+
+/// Names of all theme folders (`themes/<name>/`) found in any asset directory.
+pub fn list_themes(configured: &[PathBuf]) -> Vec<String> {
+    let mut themes = Vec::new();
+    for (dir, _) in search_path(configured) {
+        if let Ok(entries) = std::fs::read_dir(dir.join("themes")) {
+            for entry in entries.flatten() {
+                if entry.path().is_dir() {
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    if !themes.contains(&name) {
+                        themes.push(name);
+                    }
+                }
+            }
+        }
+    }
+    themes.sort();
+    themes
+}
+
+/// Accepts a list of paths or, for `LAVALAMPE_ASSETS_DIRS`, a single string
+/// separated like `PATH` (`:` on Unix, `;` on Windows).
+pub fn deserialize_dirs<'de, D>(deserializer: D) -> Result<Vec<PathBuf>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Dirs {
+        List(Vec<PathBuf>),
+        Joined(String),
+    }
+
+    Ok(match serde::Deserialize::deserialize(deserializer)? {
+        Dirs::List(dirs) => dirs,
+        Dirs::Joined(joined) => env::split_paths(&joined).filter(|dir| !dir.as_os_str().is_empty()).collect(),
+    })
+}
+
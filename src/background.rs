@@ -0,0 +1,180 @@
+//! What's behind the lamp: plain black, nothing at all (`transparent`, so
+//! the desktop shows around the lamp's silhouette), a picture of your choice, and
+//! optionally a fill level showing the exact percentage — a dim gradient in
+//! the tier's color rising from the bottom of each lamp, with a slightly
+//! brighter surface line. With `breakdown` (RAM on Linux) the fill is split
+//! into used memory in the tier's color and cache in blue on top of it.
+//!
+//! The picture is blended onto black with the same alpha blending as the
+//! sprites, so transparent PNGs work. It's decoded once and scaled again only
+//! when the window size changes.
+
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+use crate::lamp::Lamp;
+use crate::metrics::breakdown::Breakdown;
+use crate::{blend_alpha, WINDOW_SIZE};
+
+/// Color of the cache layer, apart from every tier color.
+const CACHE_COLOR: [u8; 3] = [60, 120, 255];
+
+/// How the background image covers the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageMode {
+    /// Scaled to fill the whole window (the aspect ratio isn't kept).
+    Stretch,
+    /// Repeated at its own size, which grows with the window size like the lamp.
+    Tile,
+}
+
+/// The `[background]` config section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackgroundConfig {
+    /// Image behind the lamp; relative to the config folder.
+    pub image: Option<PathBuf>,
+    pub image_mode: ImageMode,
+    /// Fill the background up to the current percentage.
+    pub level: bool,
+    /// Brightness of the fill at the bottom, 0 to 1.
+    pub level_opacity: f32,
+    /// Split the fill into used and cache (single RAM lamp, Linux).
+    pub breakdown: bool,
+    /// No background: the desktop shows through where the lamp is transparent.
+    /// Needs a window made transparent at startup and a compositor.
+    pub transparent: bool,
+}
+
+impl Default for BackgroundConfig {
+    fn default() -> Self {
+        BackgroundConfig {
+            image: None,
+            image_mode: ImageMode::Stretch,
+            level: false,
+            level_opacity: 0.2,
+            breakdown: false,
+            transparent: false,
+        }
+    }
+}
+
+/// The background image, decoded and scaled for the current frame buffer.
+#[derive(Default)]
+pub struct Background {
+    image: Option<RgbaImage>,
+    /// Frame buffer size and mode `layer` was made for.
+    key: Option<(usize, usize, ImageMode)>,
+    /// The image blended onto black, exactly the size of the frame buffer.
+    layer: Vec<u8>,
+}
+
+impl Background {
+    /// Loads the image at `path`, or drops the current one with `None`.
+    pub fn load(&mut self, path: Option<&Path>) {
+        self.key = None;
+        self.layer = Vec::new();
+        self.image = path.and_then(|path| match image::open(path) {
+            Ok(image) => Some(image.to_rgba8()),
+            Err(e) => {
+                log::error!("{}", tr!("background-load-failed", path = path.display(), error = e));
+                None
+            }
+        });
+    }
+
+    /// Fills a frame that is `slots` lamps wide and `side` pixels high with the background.
+    pub fn draw(
+        &mut self,
+        frame: &mut [u8],
+        slots: usize,
+        side: usize,
+        lamps: &[Lamp],
+        breakdown: Option<&Breakdown>,
+        config: &BackgroundConfig,
+    ) {
+        let (width, height) = (slots * side, side);
+        match &self.image {
+            Some(image) => {
+                let key = (width, height, config.image_mode);
+                if self.key != Some(key) {
+                    self.layer = layer(image, width, height, config.image_mode);
+                    self.key = Some(key);
+                }
+                frame.copy_from_slice(&self.layer);
+            }
+            None => {
+                let alpha = if config.transparent { 0 } else { 255 };
+                for pixel in frame.chunks_exact_mut(4) {
+                    pixel.copy_from_slice(&[0, 0, 0, alpha]);
+                }
+            }
+        }
+        match (breakdown, lamps.first()) {
+            (Some(breakdown), Some(lamp)) if slots == 1 => {
+                let [r, g, b, _] = lamp.mapping.tier.debug_color();
+                let layers = [(breakdown.used, [r, g, b]), (breakdown.cache, CACHE_COLOR)];
+                draw_layers(frame, slots, 0, side, &layers, config.level_opacity);
+            }
+            _ if config.level => draw_level(frame, slots, side, lamps, config.level_opacity),
+            _ => {}
+        }
+    }
+}
+
+/// `image` covering `width`×`height`, blended onto black.
+fn layer(image: &RgbaImage, width: usize, height: usize, mode: ImageMode) -> Vec<u8> {
+    let (width, height) = (width as u32, height as u32);
+    let covering = match mode {
+        ImageMode::Stretch => imageops::resize(image, width, height, FilterType::Triangle),
+        ImageMode::Tile => {
+            // Kacheln wachsen mit dem Fenster, wie die Lampe selbst
+            let scale = height / WINDOW_SIZE as u32;
+            let tile = match scale {
+                0 | 1 => image.clone(),
+                _ => imageops::resize(image, image.width() * scale, image.height() * scale, FilterType::Nearest),
+            };
+            let mut covering = RgbaImage::new(width, height);
+            imageops::tile(&mut covering, &tile);
+            covering
+        }
+    };
+    covering.pixels().flat_map(|&pixel| blend_alpha([0, 0, 0, 255], pixel)).collect()
+}
+
+fn draw_level(frame: &mut [u8], slots: usize, side: usize, lamps: &[Lamp], opacity: f32) {
+    for (slot, lamp) in lamps.iter().take(slots).enumerate() {
+        let [r, g, b, _] = lamp.mapping.tier.debug_color();
+        draw_layers(frame, slots, slot, side, &[(lamp.percent, [r, g, b])], opacity);
+    }
+}
+
+/// Stacks `layers` (percent and color, bottom first) from the bottom of a lamp.
+fn draw_layers(frame: &mut [u8], slots: usize, slot: usize, side: usize, layers: &[(f64, [u8; 3])], opacity: f32) {
+    let frame_width = slots * side;
+    // Die Oberfläche ist 1 px einer 128er-Lampe dick
+    let line = (side / WINDOW_SIZE).max(1) as f32;
+    let mut bottom = side as f32;
+    let mut filled = 0.0;
+    for &(percent, [r, g, b]) in layers {
+        filled = (filled + percent.max(0.0)).min(100.0);
+        let surface = side as f32 * (1.0 - filled as f32 / 100.0);
+        for y in (surface as usize)..(bottom as usize) {
+            let depth = (y as f32 + 1.0 - surface) / (side as f32 - surface).max(1.0);
+            // Unten volle Deckkraft, zur Oberfläche hin schwächer, die Oberfläche selbst hell
+            let brightness = if (y as f32) < surface + line { 1.0 } else { 0.4 + 0.6 * depth };
+            let alpha = (opacity * brightness * 255.0) as u8;
+
+            let row = (y * frame_width + slot * side) * 4;
+            for pixel in frame[row..row + side * 4].chunks_exact_mut(4) {
+                let blended = blend_alpha([pixel[0], pixel[1], pixel[2], pixel[3]], Rgba([r, g, b, alpha]));
+                pixel.copy_from_slice(&blended);
+            }
+        }
+        bottom = surface;
+    }
+}
@@ -0,0 +1,138 @@
+//! The percentage outside the window on macOS: as a badge on the Dock icon,
+//! or as a menu bar extra with a dot in the lava color. A floating lamp
+//! stays behind on one Space, the Dock and the menu bar are on all of them.
+//!
+//! Other platforms have the tray for this; there the `[badge]` section does
+//! nothing.
+
+use serde::{Deserialize, Serialize};
+
+use crate::tier::Tier;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BadgeMode {
+    Off,
+    /// Badge on the Dock icon. Dock badges are always red, so without the color.
+    Dock,
+    /// "● 42%" in the menu bar, the dot in the lava color.
+    MenuBar,
+}
+
+/// The `[badge]` config section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BadgeConfig {
+    pub mode: BadgeMode,
+}
+
+impl Default for BadgeConfig {
+    fn default() -> Self {
+        BadgeConfig { mode: BadgeMode::Dock }
+    }
+}
+
+#[derive(Default)]
+pub struct Badge {
+    /// What is shown right now, so unchanged samples don't touch the Dock.
+    shown: Option<(BadgeMode, String, Tier)>,
+    platform: platform::Badge,
+}
+
+impl Badge {
+    /// Shows `percent` in `mode`, or removes the badge with [`BadgeMode::Off`].
+    pub fn show(&mut self, mode: BadgeMode, percent: f64, tier: Tier) {
+        let wanted = (mode, format!("{:.0}%", percent), tier);
+        if self.shown.as_ref() == Some(&wanted) {
+            return;
+        }
+        self.platform.show(mode, &wanted.1, tier);
+        self.shown = Some(wanted);
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::CString;
+    use std::ptr;
+
+    use super::BadgeMode;
+    use crate::tier::Tier;
+
+    /// `NSVariableStatusItemLength`
+    const VARIABLE_LENGTH: f64 = -1.0;
+    /// Value of `NSForegroundColorAttributeName`.
+    const FOREGROUND_COLOR: &str = "NSColor";
+
+    #[repr(C)]
+    struct NSRange {
+        location: usize,
+        length: usize,
+    }
+
+    /// The menu bar extra, once there is one (retained).
+    #[derive(Default)]
+    pub struct Badge {
+        item: Option<*mut Object>,
+    }
+
+    impl Badge {
+        pub fn show(&mut self, mode: BadgeMode, text: &str, tier: Tier) {
+            unsafe {
+                let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+                let tile: *mut Object = msg_send![app, dockTile];
+                let label = if mode == BadgeMode::Dock { ns_string(text) } else { ptr::null_mut() };
+                let _: () = msg_send![tile, setBadgeLabel: label];
+
+                let bar: *mut Object = msg_send![class!(NSStatusBar), systemStatusBar];
+                if mode == BadgeMode::MenuBar {
+                    let item = *self.item.get_or_insert_with(|| {
+                        let item: *mut Object = msg_send![bar, statusItemWithLength: VARIABLE_LENGTH];
+                        msg_send![item, retain]
+                    });
+                    let button: *mut Object = msg_send![item, button];
+                    let _: () = msg_send![button, setAttributedTitle: title(text, tier)];
+                } else if let Some(item) = self.item.take() {
+                    let _: () = msg_send![bar, removeStatusItem: item];
+                    let _: () = msg_send![item, release];
+                }
+            }
+        }
+    }
+
+    unsafe fn ns_string(text: &str) -> *mut Object {
+        let text = CString::new(text).unwrap_or_default();
+        msg_send![class!(NSString), stringWithUTF8String: text.as_ptr()]
+    }
+
+    /// "● 42%" with the dot in the tier color; the text keeps the menu bar's color.
+    unsafe fn title(text: &str, tier: Tier) -> *mut Object {
+        let [r, g, b, _] = tier.debug_color();
+        let color: *mut Object = msg_send![class!(NSColor),
+            colorWithSRGBRed: r as f64 / 255.0
+            green: g as f64 / 255.0
+            blue: b as f64 / 255.0
+            alpha: 1.0f64];
+        let title: *mut Object = msg_send![class!(NSMutableAttributedString), alloc];
+        let title: *mut Object = msg_send![title, initWithString: ns_string(&format!("● {}", text))];
+        let _: *mut Object = msg_send![title, autorelease];
+        let dot = NSRange { location: 0, length: 1 };
+        let _: () = msg_send![title, addAttribute: ns_string(FOREGROUND_COLOR) value: color range: dot];
+        title
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use super::BadgeMode;
+    use crate::tier::Tier;
+
+    #[derive(Default)]
+    pub struct Badge;
+
+    impl Badge {
+        pub fn show(&mut self, _mode: BadgeMode, _text: &str, _tier: Tier) {}
+    }
+}
@@ -0,0 +1,107 @@
+//! `ram-lavalampe bench`: renders frames offscreen and reports how long each
+//! stage takes, so performance changes can be measured without a display.
+//!
+//! - decode: loading the four sprite sheets with the configured storage, plus
+//!   any decoding while rendering (`storage = "on-demand"`)
+//! - blend: compositing the lamp into the 128×128 frame buffer
+//! - scale: nearest-neighbor upscaling to the window size on the CPU — a
+//!   stand-in for the GPU pass that presents the frame, which needs a window
+
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::i18n::tr;
+use crate::lamp::{self, Lamp, SpriteCache};
+use crate::script::Mapping;
+use crate::tier::Tier;
+use crate::{WindowSizeMode, WINDOW_SIZE};
+
+/// Frames rendered before switching to the next color.
+const FRAMES_PER_TIER: usize = 50;
+
+pub fn run(config: &Config, frames: usize) {
+    let theme = config.active_theme();
+    let assets = &config.assets;
+    let mut sprites = SpriteCache::default();
+
+    println!("{}", tr!("bench-header", frames = frames, storage = assets.storage.id()));
+
+    let started = Instant::now();
+    for tier in Tier::ALL {
+        if sprites.get(theme, assets, tier.sprite_file()).is_none() {
+            eprintln!("{}", tr!("bench-no-assets"));
+            return;
+        }
+    }
+    let load_time = started.elapsed();
+    println!("{}", tr!("bench-load", ms = format!("{:.1}", ms(load_time))));
+    println!();
+    println!(
+        "{:>9}  {:>9}  {:>9}  {:>9}  {:>9}",
+        tr!("bench-col-size"),
+        tr!("bench-col-fps"),
+        tr!("bench-col-decode"),
+        tr!("bench-col-blend"),
+        tr!("bench-col-scale"),
+    );
+
+    let mut frame = vec![0u8; WINDOW_SIZE * WINDOW_SIZE * 4];
+    for size_mode in WindowSizeMode::ALL {
+        let side = size_mode.get_size();
+        let mut window = vec![0u8; side * side * 4];
+        let mut lamp = Lamp::new(0.0, Mapping { tier: Tier::Green, frame_duration: Duration::ZERO, extreme: None });
+        let (mut decode, mut blend, mut scale) = (Duration::ZERO, Duration::ZERO, Duration::ZERO);
+
+        let total = Instant::now();
+        for i in 0..frames {
+            sprites.tick(assets.cache_budget_mb);
+            lamp.mapping.tier = Tier::ALL[i / FRAMES_PER_TIER % Tier::ALL.len()];
+
+            let t = Instant::now();
+            let mut sheet = sprites.get(theme, assets, lamp.mapping.tier.sprite_file());
+            lamp.frame_index = sheet.as_ref().map_or(0, |s| i % s.frame_count());
+            if sheet.as_mut().is_some_and(|s| !s.prepare(lamp.frame_index, WINDOW_SIZE)) {
+                sheet = None;
+            }
+            decode += t.elapsed();
+
+            let t = Instant::now();
+            for pixel in frame.chunks_exact_mut(4) {
+                pixel.copy_from_slice(&[0, 0, 0, 255]);
+            }
+            lamp::draw(&mut frame, 1, 0, WINDOW_SIZE, &lamp, sheet.as_deref());
+            blend += t.elapsed();
+
+            let t = Instant::now();
+            upscale(&frame, &mut window, side);
+            scale += t.elapsed();
+        }
+        let total = total.elapsed();
+
+        let per_frame = |d: Duration| format!("{:.3}", ms(d) / frames as f64);
+        println!(
+            "{:>9}  {:>9.0}  {:>9}  {:>9}  {:>9}",
+            format!("{0}×{0}", side),
+            frames as f64 / total.as_secs_f64(),
+            per_frame(decode),
+            per_frame(blend),
+            per_frame(scale),
+        );
+    }
+}
+
+/// Nearest-neighbor upscaling of the square frame buffer to `side`×`side`.
+fn upscale(frame: &[u8], window: &mut [u8], side: usize) {
+    let factor = side / WINDOW_SIZE;
+    for (y, row) in window.chunks_exact_mut(side * 4).enumerate() {
+        let source_row = &frame[(y / factor) * WINDOW_SIZE * 4..][..WINDOW_SIZE * 4];
+        for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+            let source = (x / factor) * 4;
+            pixel.copy_from_slice(&source_row[source..source + 4]);
+        }
+    }
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -0,0 +1,68 @@
+//! Hides the window from screenshots and screen sharing.
+//!
+//! Windows and macOS let a window opt out of capture. X11 and Wayland have
+//! no such flag; there the window is simply captured like any other.
+
+use winit::window::Window;
+
+use crate::i18n::tr;
+
+/// Excludes the window from (or includes it again in) screen capture.
+pub fn set_excluded(window: &Window, excluded: bool) {
+    match platform::set_excluded(window, excluded) {
+        Ok(()) if excluded => log::info!("{}", tr!("capture-excluded")),
+        Ok(()) => log::info!("{}", tr!("capture-included")),
+        Err(error) => log::warn!("{}", tr!("capture-exclude-failed", error = error)),
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{SetWindowDisplayAffinity, WDA_EXCLUDEFROMCAPTURE, WDA_NONE};
+    use winit::platform::windows::WindowExtWindows;
+    use winit::window::Window;
+
+    pub fn set_excluded(window: &Window, excluded: bool) -> Result<(), String> {
+        let affinity = if excluded { WDA_EXCLUDEFROMCAPTURE } else { WDA_NONE };
+        // WDA_EXCLUDEFROMCAPTURE gibt es erst ab Windows 10 2004
+        if unsafe { SetWindowDisplayAffinity(window.hwnd(), affinity) } == 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc::runtime::Object;
+    use objc::{msg_send, sel, sel_impl};
+    use winit::platform::macos::WindowExtMacOS;
+    use winit::window::Window;
+
+    /// `NSWindowSharingNone` / `NSWindowSharingReadOnly`
+    const SHARING_NONE: u64 = 0;
+    const SHARING_READ_ONLY: u64 = 1;
+
+    pub fn set_excluded(window: &Window, excluded: bool) -> Result<(), String> {
+        let ns_window = window.ns_window() as *mut Object;
+        let sharing_type = if excluded { SHARING_NONE } else { SHARING_READ_ONLY };
+        unsafe {
+            let _: () = msg_send![ns_window, setSharingType: sharing_type];
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod platform {
+    use winit::window::Window;
+
+    use crate::i18n::tr;
+
+    pub fn set_excluded(_window: &Window, excluded: bool) -> Result<(), String> {
+        if excluded {
+            return Err(tr!("capture-unsupported"));
+        }
+        Ok(())
+    }
+}
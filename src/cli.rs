@@ -0,0 +1,206 @@
+//! Command line options. They override the config file and `LAVALAMPE_*` variables.
+
+use std::path::PathBuf;
+use std::process;
+
+use crate::config::Config;
+use crate::i18n::tr;
+use crate::metrics::Metric;
+use crate::output;
+use crate::pack;
+
+/// Frames rendered per window size by `bench` unless `--frames` says otherwise.
+const DEFAULT_BENCH_FRAMES: usize = 500;
+
+#[derive(Debug, Default)]
+pub struct Args {
+    pub metric: Option<Metric>,
+    /// `--watch-process`: RAM of this process (name or PID) instead of the system.
+    pub watch_process: Option<String>,
+    /// `--assets-dir`, searched before all other asset directories.
+    pub assets_dirs: Vec<PathBuf>,
+    /// `--widget`: borderless, on top, out of the taskbar, on every workspace, docked.
+    pub widget: bool,
+    /// Run this instead of the lamp.
+    pub command: Option<Command>,
+}
+
+#[derive(Debug)]
+pub enum Command {
+    /// Render frames offscreen and print timings.
+    Bench { frames: usize },
+    /// Print where config and assets are looked up.
+    Doctor,
+    /// Write the built-in sprite sheets to a directory.
+    ExtractAssets { dir: PathBuf },
+    /// Assemble numbered frames into a sprite sheet.
+    Pack { dir: PathBuf, output: PathBuf, size: Option<u32> },
+    /// Print samples for a status bar instead of showing the lamp.
+    Output { format: output::Format },
+    /// Print one sample and exit.
+    Status { format: output::StatusFormat },
+}
+
+impl Args {
+    /// Parses `std::env::args()`. Prints help/version or a usage error and exits where needed.
+    pub fn parse() -> Args {
+        match Args::parse_from(std::env::args().skip(1)) {
+            Ok(args) => args,
+            Err(error) => {
+                eprintln!("{}", tr!("cli-error", error = error));
+                eprintln!("{}", tr!("cli-usage"));
+                process::exit(2);
+            }
+        }
+    }
+
+    fn parse_from(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+        let mut parsed = Args::default();
+
+        while let Some(arg) = args.next() {
+            // Sowohl "--metric stdin" als auch "--metric=stdin"
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg.clone(), None),
+            };
+            let mut value = |name: &str| {
+                inline_value.clone().or_else(|| args.next()).ok_or_else(|| tr!("cli-missing-value", flag = name))
+            };
+
+            match flag.as_str() {
+                "--metric" => {
+                    let name = value("--metric")?;
+                    let metric = Metric::ALL
+                        .into_iter()
+                        .find(|m| m.id() == name)
+                        .ok_or_else(|| tr!("cli-unknown-metric", metric = &name, available = metric_ids()))?;
+                    parsed.metric = Some(metric);
+                }
+                "--watch-process" => parsed.watch_process = Some(value("--watch-process")?),
+                "--widget" => parsed.widget = true,
+                "--assets-dir" => parsed.assets_dirs.push(PathBuf::from(value("--assets-dir")?)),
+                "--extract-assets" if parsed.command.is_none() => {
+                    parsed.command = Some(Command::ExtractAssets { dir: PathBuf::from(value("--extract-assets")?) });
+                }
+                "bench" if parsed.command.is_none() => {
+                    parsed.command = Some(Command::Bench { frames: DEFAULT_BENCH_FRAMES });
+                }
+                "doctor" if parsed.command.is_none() => parsed.command = Some(Command::Doctor),
+                "status" if parsed.command.is_none() => {
+                    parsed.command = Some(Command::Status { format: output::StatusFormat::Plain });
+                }
+                "pack" if parsed.command.is_none() => {
+                    let dir = args.next().ok_or_else(|| tr!("cli-missing-value", flag = "pack"))?;
+                    parsed.command = Some(Command::Pack {
+                        dir: PathBuf::from(dir),
+                        output: PathBuf::from(pack::DEFAULT_OUTPUT),
+                        size: None,
+                    });
+                }
+                // Nach `pack` die Ausgabedatei, sonst das Format für Statusleisten
+                "-o" | "--output" => match &mut parsed.command {
+                    Some(Command::Pack { output, .. }) => *output = PathBuf::from(value(&flag)?),
+                    None => {
+                        let name = value(&flag)?;
+                        let format = output::Format::ALL
+                            .into_iter()
+                            .find(|f| f.id() == name)
+                            .ok_or_else(|| tr!("cli-unknown-output", format = &name, available = output_ids()))?;
+                        parsed.command = Some(Command::Output { format });
+                    }
+                    Some(_) => return Err(tr!("cli-unknown-argument", argument = arg)),
+                },
+                "--size" => {
+                    let Some(Command::Pack { size, .. }) = &mut parsed.command else {
+                        return Err(tr!("cli-unknown-argument", argument = arg));
+                    };
+                    let text = value("--size")?;
+                    *size = Some(
+                        text.parse()
+                            .ok()
+                            .filter(|n| *n > 0)
+                            .ok_or_else(|| tr!("cli-bad-number", flag = "--size", value = &text))?,
+                    );
+                }
+                "--frames" => {
+                    let Some(Command::Bench { frames }) = &mut parsed.command else {
+                        return Err(tr!("cli-unknown-argument", argument = arg));
+                    };
+                    let text = value("--frames")?;
+                    *frames = text
+                        .parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| tr!("cli-bad-number", flag = "--frames", value = &text))?;
+                }
+                "--format" => {
+                    let Some(Command::Status { format }) = &mut parsed.command else {
+                        return Err(tr!("cli-unknown-argument", argument = arg));
+                    };
+                    let name = value("--format")?;
+                    *format = output::StatusFormat::ALL
+                        .into_iter()
+                        .find(|f| f.id() == name)
+                        .ok_or_else(|| tr!("cli-unknown-output", format = &name, available = status_ids()))?;
+                }
+                "-h" | "--help" => {
+                    println!("{}", tr!("cli-usage"));
+                    println!();
+                    println!("{}", tr!("cli-options"));
+                    println!("  {}", tr!("cli-help-metric", metrics = metric_ids()));
+                    println!("  {}", tr!("cli-help-watch-process"));
+                    println!("  {}", tr!("cli-help-widget"));
+                    println!("  {}", tr!("cli-help-assets-dir"));
+                    println!("  {}", tr!("cli-help-extract-assets"));
+                    println!("  {}", tr!("cli-help-output", formats = output_ids()));
+                    println!("  {}", tr!("cli-help-help"));
+                    println!("  {}", tr!("cli-help-version"));
+                    println!();
+                    println!("{}", tr!("cli-commands"));
+                    println!("  {}", tr!("cli-help-bench", frames = DEFAULT_BENCH_FRAMES));
+                    println!("  {}", tr!("cli-help-doctor"));
+                    println!("  {}", tr!("cli-help-pack", output = pack::DEFAULT_OUTPUT));
+                    println!("  {}", tr!("cli-help-status", formats = status_ids()));
+                    println!();
+                    println!("{}", tr!("cli-example"));
+                    process::exit(0);
+                }
+                "-V" | "--version" => {
+                    println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+                    process::exit(0);
+                }
+                _ => return Err(tr!("cli-unknown-argument", argument = arg)),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(process) = &self.watch_process {
+            config.metrics.process = Some(process.clone());
+            // Ein Prozess wird nur in der RAM-Lampe gezeigt
+            if self.metric.is_none() {
+                config.metric = Metric::Ram;
+            }
+        }
+        if let Some(metric) = self.metric {
+            config.metric = metric;
+        }
+        if self.widget || config.widget {
+            config.apply_widget_preset();
+        }
+    }
+}
+
+fn metric_ids() -> String {
+    Metric::ALL.iter().map(|m| m.id()).collect::<Vec<_>>().join(", ")
+}
+
+fn output_ids() -> String {
+    output::Format::ALL.iter().map(|f| f.id()).collect::<Vec<_>>().join(", ")
+}
+
+fn status_ids() -> String {
+    output::StatusFormat::ALL.iter().map(|f| f.id()).collect::<Vec<_>>().join(", ")
+}
@@ -0,0 +1,37 @@
+//! The local time of day, for the script's `hour` and the alerts' quiet hours.
+
+/// Minutes since local midnight (0-1439).
+#[cfg(unix)]
+pub fn local_minutes() -> u32 {
+    // SAFETY: localtime_r only writes into the provided struct.
+    unsafe {
+        let now = libc::time(std::ptr::null_mut());
+        let mut tm: libc::tm = std::mem::zeroed();
+        if libc::localtime_r(&now, &mut tm).is_null() {
+            return 0;
+        }
+        (tm.tm_hour * 60 + tm.tm_min) as u32
+    }
+}
+
+#[cfg(windows)]
+pub fn local_minutes() -> u32 {
+    use windows_sys::Win32::Foundation::SYSTEMTIME;
+    use windows_sys::Win32::System::SystemInformation::GetLocalTime;
+    // SAFETY: GetLocalTime only writes into the provided struct.
+    let now = unsafe {
+        let mut now: SYSTEMTIME = std::mem::zeroed();
+        GetLocalTime(&mut now);
+        now
+    };
+    u32::from(now.wHour) * 60 + u32::from(now.wMinute)
+}
+
+/// Elsewhere there's no time zone to ask for, so this is UTC.
+#[cfg(not(any(unix, windows)))]
+pub fn local_minutes() -> u32 {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    ((secs / 60) % (24 * 60)) as u32
+}
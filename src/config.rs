@@ -0,0 +1,1084 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::i18n::{self, tr};
+use crate::alerts::AlertConfig;
+use crate::background::BackgroundConfig;
+use crate::badge::BadgeConfig;
+use crate::effects::EffectsConfig;
+use crate::logging::LogConfig;
+use crate::gamepad::GamepadConfig;
+use crate::indicators::{Anchor, IndicatorConfig, IndicatorKind};
+use crate::keys::KeysConfig;
+use crate::launch::{ClickAction, ClickConfig};
+use crate::session::SessionConfig;
+use crate::sprites::{self, AssetConfig};
+use crate::metrics::{Metric, MetricSettings};
+use crate::paths;
+use crate::tier::Tier;
+use crate::watchdog::RendererConfig;
+use crate::WindowSizeMode;
+
+const MIN_SAMPLING_INTERVAL_MS: u64 = 50;
+const MAX_FPS: u32 = 240;
+/// Fastest animation `[speed]` allows, in ms per frame.
+const MIN_FRAME_MS: u64 = 10;
+const MAX_TREND_SAMPLES: usize = 60;
+/// Shortest trend window for the OOM forecast; a few samples are too noisy.
+const MIN_PREDICT_WINDOW_S: u64 = 10;
+const ENV_PREFIX: &str = "LAVALAMPE_";
+/// Name for the built-in sheets in `[per_metric]`, where leaving `theme` out means the top-level one.
+const CLASSIC_THEME: &str = "classic";
+
+/// What closing the window (or Esc) does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CloseBehavior {
+    Quit,
+    /// Quit only when closed a second time within a few seconds.
+    Confirm,
+    /// Hide the window and keep monitoring; the tray icon brings it back.
+    Tray,
+}
+
+/// Screen corner the window is moved to on startup and after resizing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Corner {
+    None,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    pub const ALL: [Corner; 5] = [
+        Corner::None,
+        Corner::TopLeft,
+        Corner::TopRight,
+        Corner::BottomLeft,
+        Corner::BottomRight,
+    ];
+
+    pub fn description(&self) -> String {
+        match self {
+            Corner::None => tr!("corner-none"),
+            Corner::TopLeft => tr!("corner-top-left"),
+            Corner::TopRight => tr!("corner-top-right"),
+            Corner::BottomLeft => tr!("corner-bottom-left"),
+            Corner::BottomRight => tr!("corner-bottom-right"),
+        }
+    }
+
+    /// The next corner going clockwise; an undocked window starts top left.
+    pub fn clockwise(self) -> Corner {
+        match self {
+            Corner::None | Corner::BottomLeft => Corner::TopLeft,
+            Corner::TopLeft => Corner::TopRight,
+            Corner::TopRight => Corner::BottomRight,
+            Corner::BottomRight => Corner::BottomLeft,
+        }
+    }
+
+    /// The next corner going counterclockwise; an undocked window starts top left.
+    pub fn counterclockwise(self) -> Corner {
+        match self {
+            Corner::None | Corner::TopRight => Corner::TopLeft,
+            Corner::TopLeft => Corner::BottomLeft,
+            Corner::BottomLeft => Corner::BottomRight,
+            Corner::BottomRight => Corner::TopRight,
+        }
+    }
+}
+
+/// What the window shows: one lamp for `metric`, or a row of small lamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Layout {
+    Single,
+    /// One lamp per CPU core, each showing that core's load.
+    PerCore,
+    /// One lamp per NUMA node, each showing that node's RAM.
+    PerNumaNode,
+}
+
+impl Layout {
+    pub const ALL: [Layout; 3] = [Layout::Single, Layout::PerCore, Layout::PerNumaNode];
+
+    pub fn description(&self) -> String {
+        match self {
+            Layout::Single => tr!("layout-single"),
+            Layout::PerCore => tr!("layout-per-core"),
+            Layout::PerNumaNode => tr!("layout-per-numa-node"),
+        }
+    }
+
+    /// The metric every lamp of this layout shows.
+    pub fn metric(&self, configured: Metric) -> Metric {
+        match self {
+            Layout::Single => configured,
+            Layout::PerCore => Metric::Cpu,
+            Layout::PerNumaNode => Metric::Ram,
+        }
+    }
+}
+
+/// Upper bounds (in percent) of the green, yellow and orange tiers. Everything above `red` is red.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thresholds {
+    pub yellow: f64,
+    pub orange: f64,
+    pub red: f64,
+    /// Above this the red lava boils over; off if not set.
+    pub boiling: Option<f64>,
+    /// Below this the lamp falls asleep; off if not set.
+    pub idle: Option<f64>,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds { yellow: 30.0, orange: 50.0, red: 80.0, boiling: None, idle: None }
+    }
+}
+
+/// Look of one metric (`[per_metric.cpu]`), used instead of the top-level
+/// `theme` and `[thresholds]` while the lamps show that metric.
+/// `theme = "classic"` picks the built-in sheets.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricStyle {
+    pub theme: Option<String>,
+    /// A complete set; keys left out use the defaults, not the top-level values.
+    pub thresholds: Option<Thresholds>,
+}
+
+/// Shape of the curve from the slowest to the fastest animation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Easing {
+    Linear,
+    /// Calm for a long time, agitated only near the top.
+    EaseIn,
+    /// Picks up quickly, then levels off.
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps 0..=1 onto 0..=1.
+    fn apply(self, x: f64) -> f64 {
+        match self {
+            Easing::Linear => x,
+            Easing::EaseIn => x * x,
+            Easing::EaseOut => 1.0 - (1.0 - x) * (1.0 - x),
+            Easing::EaseInOut => x * x * (3.0 - 2.0 * x),
+        }
+    }
+}
+
+/// The `[speed]` section: the time per animation frame of each tier, or with
+/// `continuous`, following the exact percentage instead of jumping with the tier.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeedConfig {
+    /// Time per frame of each tier.
+    pub green_ms: u64,
+    pub yellow_ms: u64,
+    pub orange_ms: u64,
+    pub red_ms: u64,
+    pub continuous: bool,
+    pub easing: Easing,
+    /// Time per frame at 0%.
+    pub slowest_ms: u64,
+    /// Time per frame at 100%.
+    pub fastest_ms: u64,
+    /// Play the animation backwards while the value is falling.
+    pub reverse_when_falling: bool,
+    /// Samples the trend is computed over.
+    pub trend_samples: usize,
+}
+
+impl Default for SpeedConfig {
+    fn default() -> Self {
+        let ms = |tier: Tier| tier.frame_duration().as_millis() as u64;
+        SpeedConfig {
+            green_ms: ms(Tier::Green),
+            yellow_ms: ms(Tier::Yellow),
+            orange_ms: ms(Tier::Orange),
+            red_ms: ms(Tier::Red),
+            continuous: false,
+            easing: Easing::Linear,
+            slowest_ms: 200,
+            fastest_ms: 60,
+            reverse_when_falling: false,
+            trend_samples: 5,
+        }
+    }
+}
+
+impl SpeedConfig {
+    /// Time per frame of `tier`, regardless of `continuous`.
+    pub fn tier_duration(&self, tier: Tier) -> Duration {
+        let ms = match tier {
+            Tier::Green => self.green_ms,
+            Tier::Yellow => self.yellow_ms,
+            Tier::Orange => self.orange_ms,
+            Tier::Red => self.red_ms,
+        };
+        Duration::from_millis(ms)
+    }
+
+    fn tier_ms(&mut self, tier: Tier) -> &mut u64 {
+        match tier {
+            Tier::Green => &mut self.green_ms,
+            Tier::Yellow => &mut self.yellow_ms,
+            Tier::Orange => &mut self.orange_ms,
+            Tier::Red => &mut self.red_ms,
+        }
+    }
+
+    pub fn frame_duration(&self, percent: f64, tier: Tier) -> Duration {
+        if !self.continuous {
+            return self.tier_duration(tier);
+        }
+        let x = self.easing.apply((percent / 100.0).clamp(0.0, 1.0));
+        let ms = self.slowest_ms as f64 + (self.fastest_ms as f64 - self.slowest_ms as f64) * x;
+        Duration::from_secs_f64(ms / 1000.0)
+    }
+}
+
+/// Contents of `config.toml`. Every key is optional; missing keys use the defaults below.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub size: WindowSizeMode,
+    pub corner: Corner,
+    pub layout: Layout,
+    pub metric: Metric,
+    pub metrics: MetricSettings,
+    pub theme: Option<String>,
+    pub sampling_interval_ms: u64,
+    /// Upper limit of frames presented per second; unlimited if not set.
+    pub max_fps: Option<u32>,
+    /// Present frames in sync with the display refresh; read at startup.
+    pub vsync: bool,
+    pub autostart: bool,
+    /// Hide the window from screenshots and screen sharing (Windows, macOS).
+    pub exclude_from_capture: bool,
+    /// No title bar and border; toggled at runtime with Ctrl+B or the tray.
+    pub borderless: bool,
+    /// Keep the lamp above other windows.
+    pub always_on_top: bool,
+    /// Leave the lamp out of the taskbar (Windows, X11) or the Dock (macOS).
+    pub skip_taskbar: bool,
+    /// Show the lamp on every workspace (X11) or Space (macOS).
+    pub sticky: bool,
+    /// Let clicks fall through to the windows below; the lamp can't be moved then.
+    pub click_through: bool,
+    /// Move the window by dragging anywhere on it, for borderless windows.
+    pub drag_anywhere: bool,
+    /// Desktop widget mode, see [`apply_widget_preset`](Self::apply_widget_preset);
+    /// toggled at runtime with Ctrl+W or the tray.
+    pub widget: bool,
+    pub on_close: CloseBehavior,
+    /// "en" or "de"; detected from the locale when not set.
+    pub language: Option<String>,
+    pub thresholds: Thresholds,
+    /// Theme and thresholds of single metrics, e.g. a blue theme for CPU.
+    pub per_metric: BTreeMap<Metric, MetricStyle>,
+    /// Bars and dots for other metrics on top of the lamps (`[[indicators]]`).
+    pub indicators: Vec<IndicatorConfig>,
+    pub speed: SpeedConfig,
+    pub log: LogConfig,
+    pub alerts: AlertConfig,
+    pub assets: AssetConfig,
+    pub effects: EffectsConfig,
+    pub renderer: RendererConfig,
+    pub background: BackgroundConfig,
+    pub session: SessionConfig,
+    pub keys: KeysConfig,
+    /// What a right (or middle) click on the lamp opens.
+    pub click: ClickConfig,
+    pub gamepad: GamepadConfig,
+    /// Dock badge or menu bar extra (macOS).
+    pub badge: BadgeConfig,
+    /// Rhai script mapping each sample to a tier/speed; relative to the config folder.
+    pub script: Option<PathBuf>,
+    /// Profile applied at startup; switch at runtime with Ctrl+P or the tray.
+    pub profile: Option<String>,
+    /// Named sets of overrides (`[profiles.presentation]`), each may contain any key above.
+    pub profiles: BTreeMap<String, toml::Table>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            size: WindowSizeMode::Small,
+            corner: Corner::None,
+            layout: Layout::Single,
+            metric: Metric::Ram,
+            metrics: MetricSettings::default(),
+            theme: None,
+            sampling_interval_ms: 1000,
+            max_fps: None,
+            vsync: true,
+            autostart: false,
+            exclude_from_capture: false,
+            borderless: false,
+            always_on_top: false,
+            skip_taskbar: false,
+            sticky: false,
+            click_through: false,
+            drag_anywhere: false,
+            widget: false,
+            on_close: CloseBehavior::Quit,
+            language: None,
+            thresholds: Thresholds::default(),
+            per_metric: BTreeMap::new(),
+            indicators: Vec::new(),
+            speed: SpeedConfig::default(),
+            log: LogConfig::default(),
+            alerts: AlertConfig::default(),
+            assets: AssetConfig::default(),
+            effects: EffectsConfig::default(),
+            renderer: RendererConfig::default(),
+            background: BackgroundConfig::default(),
+            session: SessionConfig::default(),
+            keys: KeysConfig::default(),
+            click: ClickConfig::default(),
+            gamepad: GamepadConfig::default(),
+            badge: BadgeConfig::default(),
+            script: None,
+            profile: None,
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn sampling_interval(&self) -> Duration {
+        Duration::from_millis(self.sampling_interval_ms.max(1))
+    }
+
+    /// The theme of the metric on display: its own from `[per_metric]`, else `theme`.
+    pub fn active_theme(&self) -> Option<&str> {
+        match self.style().and_then(|style| style.theme.as_deref()) {
+            Some(CLASSIC_THEME) => None,
+            Some(theme) => Some(theme),
+            None => self.theme.as_deref(),
+        }
+    }
+
+    /// The thresholds of the metric on display: its own from `[per_metric]`, else `[thresholds]`.
+    pub fn active_thresholds(&self) -> &Thresholds {
+        self.thresholds_for(self.layout.metric(self.metric))
+    }
+
+    /// The thresholds of `metric`: its own from `[per_metric]`, else `[thresholds]`.
+    pub fn thresholds_for(&self, metric: Metric) -> &Thresholds {
+        self.per_metric.get(&metric).and_then(|style| style.thresholds.as_ref()).unwrap_or(&self.thresholds)
+    }
+
+    /// Sets the theme of the metric on display: its own if it has one, else the top-level one.
+    pub fn set_active_theme(&mut self, theme: Option<String>) {
+        let metric = self.layout.metric(self.metric);
+        match self.per_metric.get_mut(&metric).filter(|style| style.theme.is_some()) {
+            // Ohne eigenes Theme käme wieder das globale, nicht das klassische
+            Some(style) => style.theme = Some(theme.unwrap_or_else(|| CLASSIC_THEME.into())),
+            None => self.theme = theme,
+        }
+    }
+
+    fn style(&self) -> Option<&MetricStyle> {
+        self.per_metric.get(&self.layout.metric(self.metric))
+    }
+
+    /// Shortest time between two presented frames, zero without `max_fps`.
+    pub fn min_frame_interval(&self) -> Duration {
+        self.max_fps.map_or(Duration::ZERO, |fps| Duration::from_secs(1) / fps.max(1))
+    }
+
+    /// Widget mode: borderless, on top, out of the taskbar, on every
+    /// workspace, moved by dragging anywhere, with a transparent background
+    /// and docked in a corner (unless one is set). Click-through stays a
+    /// toggle of its own, the lamp couldn't be dragged anymore.
+    pub fn apply_widget_preset(&mut self) {
+        self.widget = true;
+        self.borderless = true;
+        self.always_on_top = true;
+        self.skip_taskbar = true;
+        self.sticky = true;
+        self.drag_anywhere = true;
+        self.background.transparent = true;
+        if self.corner == Corner::None {
+            self.corner = Corner::BottomRight;
+        }
+    }
+
+    /// Leaves widget mode, taking the settings it changes from `before`.
+    /// The corner stays where the lamp is docked now.
+    pub fn leave_widget_mode(&mut self, before: &Config) {
+        self.widget = false;
+        self.borderless = before.borderless;
+        self.always_on_top = before.always_on_top;
+        self.skip_taskbar = before.skip_taskbar;
+        self.sticky = before.sticky;
+        self.drag_anywhere = before.drag_anywhere;
+        self.background.transparent = before.background.transparent;
+    }
+
+    /// The script file with a relative path resolved against the config folder.
+    pub fn script_path(&self) -> Option<PathBuf> {
+        self.script.as_deref().map(relative_to_config)
+    }
+
+    /// The session summary file with a relative path resolved against the config folder.
+    pub fn session_summary_path(&self) -> Option<PathBuf> {
+        self.session.summary_file.as_deref().map(relative_to_config)
+    }
+
+    /// The background image with a relative path resolved against the config folder.
+    pub fn background_image_path(&self) -> Option<PathBuf> {
+        self.background.image.as_deref().map(relative_to_config)
+    }
+
+    /// Loads the config file. Returns `None` if there is none yet (first run).
+    /// A file that exists but can't be read is logged and returned as an error,
+    /// so it isn't mistaken for a first run and overwritten.
+    /// Problems are reported with their line; invalid values fall back to their defaults.
+    pub fn load() -> io::Result<Option<Config>> {
+        let Some(path) = config_path() else { return Ok(None) };
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                log::error!("{}", tr!("config-read-failed", path = path.display(), error = &e));
+                return Err(e);
+            }
+        };
+
+        let (config, problems) = Config::parse(&text).unwrap_or_else(|problem| (Config::default(), vec![problem]));
+        if problems.is_empty() {
+            log::info!("{}", tr!("config-loaded", path = path.display()));
+        } else {
+            report_problems(&path, &problems);
+            log::error!("    {}", tr!("config-using-defaults"));
+        }
+        Ok(Some(config))
+    }
+
+    /// Re-reads the config file while running. Unlike [`Config::load`] a file that
+    /// cannot be parsed at all (e.g. saved halfway through an edit) is rejected, so
+    /// the current settings stay in effect instead of jumping back to the defaults.
+    pub fn reload() -> Option<Config> {
+        let path = config_path()?;
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) => {
+                log::error!("{}", tr!("config-reload-read-failed", path = path.display(), error = e));
+                return None;
+            }
+        };
+
+        match Config::parse(&text) {
+            Ok((config, problems)) => {
+                if problems.is_empty() {
+                    log::info!("{}", tr!("config-reloaded", path = path.display()));
+                } else {
+                    report_problems(&path, &problems);
+                    log::error!("    {}", tr!("config-using-defaults"));
+                }
+                Some(config)
+            }
+            Err(problem) => {
+                report_problems(&path, &[problem]);
+                log::error!("    {}", tr!("config-reload-kept"));
+                None
+            }
+        }
+    }
+
+    /// Parses and validates a config file, collecting every problem instead of stopping at the first.
+    /// Fails only if the file is not valid TOML or does not match the config's shape at all.
+    pub fn parse(text: &str) -> Result<(Config, Vec<Problem>), Problem> {
+        let mut unknown_keys = Vec::new();
+        let result = toml::Deserializer::parse(text).and_then(|deserializer| {
+            serde_ignored::deserialize(deserializer, |path| unknown_keys.push(path.to_string()))
+        });
+
+        let mut config: Config = result.map_err(|e| Problem {
+            position: e.span().map(|span| Position::from_offset(text, span.start)),
+            message: e.message().to_string(),
+        })?;
+
+        let mut problems: Vec<Problem> = unknown_keys
+            .iter()
+            .map(|key| Problem::at_key(text, key, tr!("config-unknown-key", key = key)))
+            .collect();
+        problems.extend(config.validate().into_iter().map(|(key, message)| Problem::at_key(text, &key, message)));
+        problems.sort_by_key(|problem| problem.position.map_or(0, |p| p.line));
+
+        Ok((config, problems))
+    }
+
+    /// The effective settings with the overrides of profile `name` applied.
+    /// `None` (or an unknown/broken profile) gives the base settings.
+    pub fn with_profile(&self, name: Option<&str>) -> Config {
+        let Some(name) = name else { return self.clone() };
+        match self.merge_profile(name) {
+            Ok(Some(config)) => config,
+            Ok(None) => {
+                log::error!("{}", tr!("profile-not-found", profile = name));
+                self.clone()
+            }
+            Err(e) => {
+                log::error!("{}", tr!("profile-invalid", profile = name, error = e));
+                self.clone()
+            }
+        }
+    }
+
+    fn merge_profile(&self, name: &str) -> Result<Option<Config>, Box<dyn std::error::Error>> {
+        let Some(overrides) = self.profiles.get(name) else { return Ok(None) };
+
+        let mut table = toml::Table::try_from(self)?;
+        merge_tables(&mut table, overrides);
+        let mut config: Config = toml::Value::Table(table).try_into()?;
+        // Profile beschreiben nur Abweichungen, die Profilliste selbst bleibt die der Basis
+        config.profile = Some(name.to_string());
+        config.profiles = self.profiles.clone();
+        Ok(Some(config))
+    }
+
+    /// Checks values that parse fine but make no sense, including every profile.
+    /// Offending values are reset to their defaults (or dropped from the profile);
+    /// returns the dotted key and a message for each of them.
+    fn validate(&mut self) -> Vec<(String, String)> {
+        let mut problems = self.validate_values();
+
+        if let Some(profile) = &self.profile {
+            if !self.profiles.contains_key(profile) {
+                problems.push(("profile".into(), tr!("profile-not-found", profile = profile)));
+                self.profile = None;
+            }
+        }
+
+        // Jedes Profil muss zusammen mit der Basis eine gültige Konfiguration ergeben
+        let Ok(base) = toml::Table::try_from(&*self) else { return problems };
+        let names: Vec<String> = self.profiles.keys().cloned().collect();
+        for name in names {
+            let mut table = base.clone();
+            merge_tables(&mut table, &self.profiles[&name]);
+
+            let mut unknown_keys = Vec::new();
+            let result: Result<Config, _> =
+                serde_ignored::deserialize(toml::Value::Table(table), |path| unknown_keys.push(path.to_string()));
+            for key in unknown_keys {
+                let key = format!("profiles.{}.{}", name, key);
+                problems.push((key.clone(), tr!("config-unknown-key", key = key)));
+            }
+
+            match result {
+                Ok(mut merged) => {
+                    for (key, message) in merged.validate_values() {
+                        // Die Basis ist schon geprüft, der Fehler steckt also im Profil
+                        if let Some(overrides) = self.profiles.get_mut(&name) {
+                            overrides.remove(key.split('.').next().unwrap_or(&key));
+                        }
+                        problems.push((format!("profiles.{}.{}", name, key), message));
+                    }
+                }
+                Err(e) => {
+                    problems.push((format!("profiles.{}", name), tr!("profile-invalid", profile = name, error = e.message())));
+                    self.profiles.remove(&name);
+                }
+            }
+        }
+
+        problems
+    }
+
+    fn validate_values(&mut self) -> Vec<(String, String)> {
+        let defaults = Config::default();
+        let mut problems: Vec<(String, String)> = Vec::new();
+
+        if self.sampling_interval_ms < MIN_SAMPLING_INTERVAL_MS {
+            problems.push((
+                "sampling_interval_ms".into(),
+                tr!("config-interval-too-short", value = self.sampling_interval_ms, min = MIN_SAMPLING_INTERVAL_MS),
+            ));
+            self.sampling_interval_ms = defaults.sampling_interval_ms;
+        }
+        if let Some(fps) = self.max_fps.filter(|fps| !(1..=MAX_FPS).contains(fps)) {
+            problems.push(("max_fps".into(), tr!("config-max-fps-range", value = fps, max = MAX_FPS)));
+            self.max_fps = defaults.max_fps;
+        }
+
+        if self.metric == Metric::Plugin && self.metrics.plugin.is_none() {
+            problems.push(("metric".into(), tr!("plugin-not-set")));
+            self.metric = defaults.metric;
+        }
+
+        let themes = crate::assets::list_themes(&self.assets.dirs);
+        if let Some(theme) = &self.theme {
+            if !themes.contains(theme) {
+                problems.push(("theme".into(), tr!("config-theme-not-found", theme = theme)));
+                self.theme = None;
+            }
+        }
+
+        if let Some(language) = &self.language {
+            if !i18n::is_supported(language) {
+                problems.push(("language".into(), tr!("config-unknown-language", language = language)));
+                self.language = None;
+            }
+        }
+
+        if self.alerts.predict_horizon_min == 0 {
+            problems.push(("alerts.predict_horizon_min".into(), tr!("config-predict-horizon-zero")));
+            self.alerts.predict_horizon_min = defaults.alerts.predict_horizon_min;
+        }
+        if self.alerts.predict_window_s < MIN_PREDICT_WINDOW_S {
+            problems.push((
+                "alerts.predict_window_s".into(),
+                tr!("config-predict-window-short", value = self.alerts.predict_window_s, min = MIN_PREDICT_WINDOW_S),
+            ));
+            self.alerts.predict_window_s = defaults.alerts.predict_window_s;
+        }
+
+        let threshold_problems = check_thresholds("thresholds", &self.thresholds);
+        if !threshold_problems.is_empty() {
+            problems.extend(threshold_problems);
+            self.thresholds = defaults.thresholds;
+        }
+
+        for (metric, style) in &mut self.per_metric {
+            let key = format!("per_metric.{}", metric.id());
+            if let Some(theme) = style.theme.as_ref().filter(|theme| *theme != CLASSIC_THEME && !themes.contains(theme)) {
+                problems.push((format!("{}.theme", key), tr!("config-theme-not-found", theme = theme)));
+                style.theme = None;
+            }
+            if let Some(thresholds) = &style.thresholds {
+                let threshold_problems = check_thresholds(&format!("{}.thresholds", key), thresholds);
+                if !threshold_problems.is_empty() {
+                    problems.extend(threshold_problems);
+                    style.thresholds = None;
+                }
+            }
+        }
+
+        let mut sheets = std::mem::take(&mut self.assets.sheets);
+        sheets.retain(|name, path| {
+            let key = format!("assets.sheets.{}", name);
+            if !sprites::named_sheets().any(|(sheet, _)| sheet == name) {
+                problems.push((key, tr!("config-unknown-sheet", name = name)));
+                return false;
+            }
+            let path = relative_to_config(path);
+            if !path.is_file() {
+                problems.push((key, tr!("config-sheet-not-found", path = path.display())));
+                return false;
+            }
+            true
+        });
+        self.assets.sheets = sheets;
+
+        if self.click.action == ClickAction::Command && self.click.command.as_deref().is_none_or(|c| c.trim().is_empty()) {
+            problems.push(("click.command".into(), tr!("config-click-no-command")));
+            self.click = defaults.click.clone();
+        }
+
+        for (i, indicator) in self.indicators.iter_mut().enumerate() {
+            if indicator.kind == IndicatorKind::Bar && !indicator.position.is_edge() {
+                problems.push((format!("indicators.{}.position", i), tr!("config-indicator-bar-corner")));
+                indicator.position = Anchor::Bottom;
+            }
+            if indicator.metric == Metric::Plugin && self.metrics.plugin.is_none() {
+                problems.push((format!("indicators.{}.metric", i), tr!("plugin-not-set")));
+                indicator.metric = IndicatorConfig::default().metric;
+            }
+        }
+
+        let speed = &self.speed;
+        if speed.fastest_ms < MIN_FRAME_MS || speed.slowest_ms <= speed.fastest_ms {
+            let message = tr!("config-speed-range", slowest = speed.slowest_ms, fastest = speed.fastest_ms, min = MIN_FRAME_MS);
+            problems.push(("speed.fastest_ms".into(), message));
+            self.speed.slowest_ms = defaults.speed.slowest_ms;
+            self.speed.fastest_ms = defaults.speed.fastest_ms;
+        }
+        for tier in Tier::ALL {
+            let ms = self.speed.tier_ms(tier);
+            if *ms < MIN_FRAME_MS {
+                let key = format!("speed.{}_ms", tier.name());
+                problems.push((key.clone(), tr!("config-tier-speed-min", key = key, value = *ms, min = MIN_FRAME_MS)));
+                *ms = tier.frame_duration().as_millis() as u64;
+            }
+        }
+        if !(2..=MAX_TREND_SAMPLES).contains(&self.speed.trend_samples) {
+            let message = tr!("config-trend-samples-range", value = self.speed.trend_samples, max = MAX_TREND_SAMPLES);
+            problems.push(("speed.trend_samples".into(), message));
+            self.speed.trend_samples = defaults.speed.trend_samples;
+        }
+
+        if !(0.0..=2.0).contains(&self.effects.bloom) {
+            problems.push(("effects.bloom".into(), tr!("config-bloom-range", value = self.effects.bloom)));
+            self.effects.bloom = defaults.effects.bloom;
+        }
+        if !(0.0..1.0).contains(&self.effects.bloom_threshold) {
+            let message = tr!("config-bloom-threshold-range", value = self.effects.bloom_threshold);
+            problems.push(("effects.bloom_threshold".into(), message));
+            self.effects.bloom_threshold = defaults.effects.bloom_threshold;
+        }
+        if !(1..=32).contains(&self.effects.bloom_radius) {
+            problems.push(("effects.bloom_radius".into(), tr!("config-bloom-radius-range", value = self.effects.bloom_radius)));
+            self.effects.bloom_radius = defaults.effects.bloom_radius;
+        }
+        for (key, value, max) in [
+            ("crt_scanlines", self.effects.crt_scanlines, 1.0),
+            ("crt_curvature", self.effects.crt_curvature, 0.5),
+            ("crt_vignette", self.effects.crt_vignette, 1.0),
+        ] {
+            if !(0.0..=max).contains(&value) {
+                problems.push((format!("effects.{}", key), tr!("config-crt-range", key = key, value = value, max = max)));
+            }
+        }
+        if problems.iter().any(|(key, _)| key.starts_with("effects.crt_")) {
+            self.effects.crt_scanlines = defaults.effects.crt_scanlines;
+            self.effects.crt_curvature = defaults.effects.crt_curvature;
+            self.effects.crt_vignette = defaults.effects.crt_vignette;
+        }
+        if !(0.0..=1.0).contains(&self.background.level_opacity) {
+            let message = tr!("config-level-opacity-range", value = self.background.level_opacity);
+            problems.push(("background.level_opacity".into(), message));
+            self.background.level_opacity = defaults.background.level_opacity;
+        }
+        if !(1..=200).contains(&self.effects.particle_count) {
+            let message = tr!("config-particle-count-range", value = self.effects.particle_count);
+            problems.push(("effects.particle_count".into(), message));
+            self.effects.particle_count = defaults.effects.particle_count;
+        }
+
+        if let Some(path) = &self.log.path {
+            if path.is_dir() {
+                problems.push(("log.path".into(), tr!("config-log-path-is-dir", path = path.display())));
+                self.log.path = None;
+            } else if path.parent().is_some_and(|dir| dir.is_file()) {
+                problems.push(("log.path".into(), tr!("config-log-path-bad-parent", path = path.display())));
+                self.log.path = None;
+            }
+        }
+        if let Some(path) = self.script_path() {
+            if !path.is_file() {
+                problems.push(("script".into(), tr!("config-script-not-found", path = path.display())));
+                self.script = None;
+            }
+        }
+
+        if self.metrics.stdin_max <= 0.0 {
+            problems.push(("metrics.stdin_max".into(), tr!("config-stdin-max", value = self.metrics.stdin_max)));
+            self.metrics.stdin_max = defaults.metrics.stdin_max;
+        }
+
+        if self.metrics.audio_floor_db >= 0.0 {
+            problems.push(("metrics.audio_floor_db".into(), tr!("config-audio-floor", value = self.metrics.audio_floor_db)));
+            self.metrics.audio_floor_db = defaults.metrics.audio_floor_db;
+        }
+
+        if self.metrics.ping_max_ms <= 0.0 {
+            problems.push(("metrics.ping_max_ms".into(), tr!("config-ping-max", value = self.metrics.ping_max_ms)));
+            self.metrics.ping_max_ms = defaults.metrics.ping_max_ms;
+        }
+        if self.metrics.ping_host.trim().is_empty() || self.metrics.ping_host.starts_with('-') {
+            problems.push(("metrics.ping_host".into(), tr!("config-ping-host", host = &self.metrics.ping_host)));
+            self.metrics.ping_host = defaults.metrics.ping_host;
+        }
+
+        if self.metrics.disk_max_mb_s <= 0.0 {
+            problems.push(("metrics.disk_max_mb_s".into(), tr!("config-disk-max", value = self.metrics.disk_max_mb_s)));
+            self.metrics.disk_max_mb_s = defaults.metrics.disk_max_mb_s;
+        }
+
+        if self.metrics.process_budget_mb.is_some_and(|mb| mb <= 0.0) {
+            let value = self.metrics.process_budget_mb.unwrap_or_default();
+            problems.push(("metrics.process_budget_mb".into(), tr!("config-process-budget", value = value)));
+            self.metrics.process_budget_mb = defaults.metrics.process_budget_mb;
+        }
+
+        if ![1, 5, 15].contains(&self.metrics.load_minutes) {
+            problems.push(("metrics.load_minutes".into(), tr!("config-load-minutes", value = self.metrics.load_minutes)));
+            self.metrics.load_minutes = defaults.metrics.load_minutes;
+        }
+
+        if self.metrics.fan_max_rpm <= self.metrics.fan_min_rpm {
+            problems.push((
+                "metrics.fan_max_rpm".into(),
+                tr!("config-fan-range", min = self.metrics.fan_min_rpm, max = self.metrics.fan_max_rpm),
+            ));
+            self.metrics.fan_min_rpm = defaults.metrics.fan_min_rpm;
+            self.metrics.fan_max_rpm = defaults.metrics.fan_max_rpm;
+        }
+
+        if let Some(node) = self.metrics.numa_node {
+            let nodes = crate::metrics::numa::nodes();
+            if !nodes.contains(&node) {
+                let available = nodes.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ");
+                problems.push(("metrics.numa_node".into(), tr!("config-numa-node", node = node, available = available)));
+                self.metrics.numa_node = None;
+            }
+        }
+
+        if self.layout == Layout::PerNumaNode && crate::metrics::numa::nodes().is_empty() {
+            problems.push(("layout".into(), tr!("config-layout-no-numa")));
+            self.layout = defaults.layout;
+        }
+
+        if self.log.max_size_kb == 0 {
+            problems.push(("log.max_size_kb".into(), tr!("config-log-size-zero")));
+            self.log.max_size_kb = defaults.log.max_size_kb;
+        }
+
+        if let Some(binding) = self.keys.conflict() {
+            problems.push(("keys".into(), tr!("config-keys-conflict", binding = String::from(binding))));
+            self.keys = defaults.keys;
+        }
+
+        problems
+    }
+
+    /// Applies `LAVALAMPE_*` environment variables on top of the file, e.g.
+    /// `LAVALAMPE_SIZE=large` or `LAVALAMPE_THRESHOLDS_RED=90`. Nested keys are
+    /// joined with `_`; variables that don't fit the config are reported and skipped.
+    pub fn apply_env_overrides(&mut self) {
+        let mut vars: Vec<(String, String)> = env::vars().filter(|(name, _)| name.starts_with(ENV_PREFIX)).collect();
+        if vars.is_empty() {
+            return;
+        }
+        vars.sort();
+
+        let Ok(mut table) = toml::Table::try_from(&*self) else { return };
+        let mut problems = Vec::new();
+
+        for (name, value) in vars {
+            let mut candidate = table.clone();
+            set_env_key(&mut candidate, &name[ENV_PREFIX.len()..].to_lowercase(), parse_env_value(&value));
+
+            let mut unknown_keys = Vec::new();
+            let result: Result<Config, _> = serde_ignored::deserialize(toml::Value::Table(candidate.clone()), |path| {
+                unknown_keys.push(path.to_string())
+            });
+            match result {
+                Ok(_) if !unknown_keys.is_empty() => {
+                    problems.push(format!("{}: {}", name, tr!("config-unknown-key", key = unknown_keys.join(", "))));
+                }
+                Ok(_) => {
+                    log::info!("{}", tr!("config-env-override", name = name, value = value));
+                    table = candidate;
+                }
+                Err(e) => problems.push(format!("{}: {}", name, e.message())),
+            }
+        }
+
+        if let Ok(mut config) = toml::Value::Table(table).try_into::<Config>() {
+            for (key, message) in config.validate() {
+                problems.push(format!("{}{}: {}", ENV_PREFIX, key.replace('.', "_").to_uppercase(), message));
+            }
+            *self = config;
+        }
+
+        if !problems.is_empty() {
+            log::error!("{}", tr!("config-env-problems", count = problems.len()));
+            for problem in &problems {
+                log::error!("    {}", problem);
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let path = config_path().ok_or_else(|| tr!("config-no-dir"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, toml::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+}
+
+/// Problems of one set of thresholds; `prefix` is its dotted key.
+fn check_thresholds(prefix: &str, t: &Thresholds) -> Vec<(String, String)> {
+    let mut problems = Vec::new();
+    for (key, value) in [("yellow", t.yellow), ("orange", t.orange), ("red", t.red)] {
+        if !(0.0..=100.0).contains(&value) {
+            problems.push((format!("{}.{}", prefix, key), tr!("config-threshold-range", value = value)));
+        }
+    }
+    if t.orange <= t.yellow {
+        problems.push((
+            format!("{}.orange", prefix),
+            tr!("config-threshold-overlap", upper = "orange", upper_value = t.orange, lower = "yellow", lower_value = t.yellow),
+        ));
+    }
+    if t.red <= t.orange {
+        problems.push((
+            format!("{}.red", prefix),
+            tr!("config-threshold-overlap", upper = "red", upper_value = t.red, lower = "orange", lower_value = t.orange),
+        ));
+    }
+    if let Some(boiling) = t.boiling.filter(|boiling| *boiling <= t.red || *boiling >= 100.0) {
+        problems.push((format!("{}.boiling", prefix), tr!("config-boiling-range", value = boiling, red = t.red)));
+    }
+    if let Some(idle) = t.idle.filter(|idle| *idle <= 0.0 || *idle > t.yellow) {
+        problems.push((format!("{}.idle", prefix), tr!("config-idle-range", value = idle, yellow = t.yellow)));
+    }
+    problems
+}
+
+/// Recursively copies `overrides` into `table`; sub-tables are merged key by key.
+fn merge_tables(table: &mut toml::Table, overrides: &toml::Table) {
+    for (key, value) in overrides {
+        match (table.get_mut(key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(sub)) => merge_tables(existing, sub),
+            _ => {
+                table.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Sets `key` ("log_max_size_kb") in `table`, descending into sub-tables whose name is a
+/// prefix of the key ("log" + "max_size_kb"), so keys containing `_` still work.
+fn set_env_key(table: &mut toml::Table, key: &str, value: toml::Value) {
+    if !table.contains_key(key) {
+        let sub_table = table
+            .iter()
+            .filter(|(name, value)| value.is_table() && key.starts_with(&format!("{}_", name)))
+            .map(|(name, _)| name.clone())
+            .next();
+        if let Some(name) = sub_table {
+            if let Some(toml::Value::Table(sub)) = table.get_mut(&name) {
+                set_env_key(sub, &key[name.len() + 1..], value);
+                return;
+            }
+        }
+    }
+    table.insert(key.to_string(), value);
+}
+
+/// Logs every problem found in the config file at `path` as an error.
+fn report_problems(path: &Path, problems: &[Problem]) {
+    log::error!("{}", tr!("config-problems", path = path.display(), count = problems.len()));
+    for problem in problems {
+        log::error!("    {}", problem);
+    }
+}
+
+/// Environment values are plain strings; numbers and booleans are recognized so they match the config types.
+fn parse_env_value(value: &str) -> toml::Value {
+    if let Ok(b) = value.parse::<bool>() {
+        toml::Value::Boolean(b)
+    } else if let Ok(i) = value.parse::<i64>() {
+        toml::Value::Integer(i)
+    } else if let Ok(f) = value.parse::<f64>() {
+        toml::Value::Float(f)
+    } else {
+        toml::Value::String(value.to_string())
+    }
+}
+
+/// `config.toml` in the config directory, see [`paths`](crate::paths).
+pub fn config_path() -> Option<PathBuf> {
+    paths::config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// `path` as is if absolute, else relative to the folder of `config.toml`.
+pub fn relative_to_config(path: &Path) -> PathBuf {
+    match config_path() {
+        Some(config) if path.is_relative() => config.with_file_name(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Creates or removes the XDG autostart entry for the current executable.
+pub fn set_autostart(enabled: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = paths::autostart_dir().ok_or_else(|| tr!("config-no-dir"))?;
+    let path = dir.join("ram-lavalampe.desktop");
+
+    if enabled {
+        let exe = env::current_exe()?;
+        fs::create_dir_all(&dir)?;
+        fs::write(
+            &path,
+            format!(
+                "[Desktop Entry]\nType=Application\nName=RAM Lava Lamp\nExec={}\nX-GNOME-Autostart-enabled=true\n",
+                exe.display()
+            ),
+        )?;
+    } else if path.exists() {
+        fs::remove_file(&path)?;
+    }
+
+    Ok(())
+}
+
+/// 1-based line and column in the config file.
+#[derive(Debug, Clone, Copy)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    fn from_offset(text: &str, offset: usize) -> Position {
+        let before = &text[..offset.min(text.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Position {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+/// A single diagnostic about the config file.
+#[derive(Debug)]
+pub struct Problem {
+    pub position: Option<Position>,
+    pub message: String,
+}
+
+impl Problem {
+    /// Locates a dotted key like `log.path` (or `indicators.0.kind` in an
+    /// array of tables) in the file to attach its line number.
+    fn at_key(text: &str, key: &str, message: String) -> Problem {
+        let position = toml::de::DeTable::parse(text).ok().and_then(|root| {
+            let mut table = root.get_ref();
+            let mut segments = key.split('.').peekable();
+            while let Some(segment) = segments.next() {
+                let (found_key, mut value) = table.iter().find(|(k, _)| k.get_ref().as_ref() == segment)?;
+                if segments.peek().is_none() {
+                    return Some(Position::from_offset(text, found_key.span().start));
+                }
+                if let Some(array) = value.get_ref().as_array() {
+                    value = array.get(segments.next()?.parse::<usize>().ok()?)?;
+                }
+                table = value.get_ref().as_table()?;
+            }
+            None
+        });
+        Problem { position, message }
+    }
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.position {
+            Some(p) => write!(f, "{}: {}", tr!("config-position", line = p.line, column = p.column), self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
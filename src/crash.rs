@@ -0,0 +1,127 @@
+//! Panic hook that writes a crash report to `crashes/` in the data directory
+//! and tells the user about it with a desktop notification, because a widget
+//! started from a launcher has no visible stderr.
+
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::panic::{self, PanicHookInfo};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::i18n::tr;
+use crate::paths;
+use crate::logging::format_timestamp;
+
+/// How many of the most recent samples end up in the report.
+const SAMPLE_HISTORY: usize = 60;
+
+#[derive(Default)]
+struct CrashContext {
+    config: String,
+    backend: String,
+    samples: VecDeque<(SystemTime, &'static str, f64)>,
+}
+
+lazy_static::lazy_static! {
+    static ref CONTEXT: Mutex<CrashContext> = Mutex::new(CrashContext::default());
+}
+
+/// Installs the panic hook. The default hook still runs afterwards.
+pub fn install() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        match write_report(info) {
+            Ok(path) => {
+                eprintln!("{}", tr!("crash-report-written", path = path.display()));
+                notify(&path);
+            }
+            Err(e) => eprintln!("{}", tr!("crash-report-failed", error = e)),
+        }
+        default_hook(info);
+    }));
+}
+
+/// Remembers the active configuration (as TOML) for the report.
+pub fn set_config(config: &str) {
+    if let Ok(mut context) = CONTEXT.lock() {
+        context.config = config.to_string();
+    }
+}
+
+/// Remembers the window system / GPU adapter in use.
+pub fn set_backend_info(backend: String) {
+    if let Ok(mut context) = CONTEXT.lock() {
+        context.backend = backend;
+    }
+}
+
+pub fn record_sample(metric: &'static str, percent: f64) {
+    if let Ok(mut context) = CONTEXT.lock() {
+        if context.samples.len() == SAMPLE_HISTORY {
+            context.samples.pop_front();
+        }
+        context.samples.push_back((SystemTime::now(), metric, percent));
+    }
+}
+
+/// `crashes` in the data directory, see [`paths`](crate::paths).
+pub fn crash_dir() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join("crashes"))
+}
+
+fn write_report(info: &PanicHookInfo) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let now = SystemTime::now();
+    let mut report = String::new();
+
+    writeln!(report, "RAM Lava Lamp crash report")?;
+    writeln!(report, "==========================")?;
+    writeln!(report, "Time:    {} UTC", format_timestamp(now))?;
+    writeln!(report, "Version: {}", env!("CARGO_PKG_VERSION"))?;
+    writeln!(report, "OS:      {} {}", env::consts::OS, env::consts::ARCH)?;
+    writeln!(report, "Thread:  {}", std::thread::current().name().unwrap_or("<unnamed>"))?;
+    writeln!(report)?;
+    writeln!(report, "Panic: {}", info)?;
+    writeln!(report)?;
+
+    // try_lock: der Panic kann passiert sein, während der Kontext gesperrt war
+    match CONTEXT.try_lock() {
+        Ok(context) => {
+            writeln!(report, "--- Backend ---")?;
+            writeln!(report, "{}", if context.backend.is_empty() { "<not initialized>" } else { &context.backend })?;
+            writeln!(report)?;
+            writeln!(report, "--- Config ---")?;
+            writeln!(report, "{}", context.config)?;
+            writeln!(report, "--- Last samples ---")?;
+            for (time, metric, percent) in &context.samples {
+                writeln!(report, "{} {} {:.1}%", format_timestamp(*time), metric, percent)?;
+            }
+            writeln!(report)?;
+        }
+        Err(_) => writeln!(report, "<context unavailable>\n")?,
+    }
+
+    writeln!(report, "--- Backtrace ---")?;
+    writeln!(report, "{}", Backtrace::force_capture())?;
+
+    let dir = crash_dir().ok_or_else(|| tr!("config-no-dir"))?;
+    fs::create_dir_all(&dir)?;
+    let secs = now.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let path = dir.join(format!("crash-{}.txt", secs));
+    fs::write(&path, report)?;
+    Ok(path)
+}
+
+/// Best effort: there is no window left to show a dialog in, so use `notify-send`.
+fn notify(path: &std::path::Path) {
+    let _ = Command::new("notify-send")
+        .arg("--urgency=critical")
+        .arg("--app-name=RAM Lava Lamp")
+        .arg(tr!("crash-notification-title"))
+        .arg(tr!("crash-notification-body", path = path.display()))
+        .status();
+}
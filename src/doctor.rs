@@ -0,0 +1,70 @@
+//! `ram-lavalampe doctor`: prints where the config and the sprite sheets are
+//! looked up and what was actually found, for "why doesn't my theme show up?".
+
+use crate::config::{self, Config};
+use crate::i18n::tr;
+use crate::tier::{Extreme, Tier};
+use crate::{assets, crash, logging, plugins, sprites};
+
+pub fn run(config: &Config) {
+    match config::config_path() {
+        Some(path) if path.exists() => println!("{}", tr!("doctor-config", path = path.display())),
+        Some(path) => println!("{}", tr!("doctor-config-missing", path = path.display())),
+        None => println!("{}", tr!("doctor-config-missing", path = "-")),
+    }
+    println!();
+
+    println!("{}", tr!("doctor-files"));
+    let files = [
+        (tr!("doctor-log"), config.log.path.clone().or_else(logging::default_log_path)),
+        (tr!("doctor-plugins"), plugins::plugin_dir()),
+        (tr!("doctor-crashes"), crash::crash_dir()),
+        (tr!("doctor-sprite-cache"), sprites::sprite_cache_dir()),
+    ];
+    for (label, path) in files {
+        let path = path.map_or_else(|| "-".to_string(), |path| path.display().to_string());
+        println!("  {:<14} {}", label, path);
+    }
+    println!();
+
+    println!("{}", tr!("doctor-asset-dirs"));
+    for (i, (dir, source)) in assets::search_path(&config.assets.dirs).iter().enumerate() {
+        let mark = if dir.is_dir() { "✓" } else { " " };
+        println!("  {:>2}. {} {}  ({})", i + 1, mark, dir.display(), source.description());
+    }
+    println!();
+
+    let theme = config.active_theme();
+    println!("{}", tr!("doctor-sheets", theme = theme.unwrap_or("classic")));
+    for tier in Tier::ALL {
+        let file = tier.sprite_file();
+        match sprites::describe_source(theme, file, &config.assets) {
+            Some(source) => println!("  ✓ {}  {}", file, source),
+            None => println!("  ✗ {}  {}", file, tr!("doctor-not-found")),
+        }
+    }
+    for extreme in Extreme::ALL {
+        let file = extreme.sprite_file();
+        if let Some(source) = sprites::describe_source(theme, file, &config.assets) {
+            println!("  ✓ {}  {}", file, source);
+        }
+    }
+
+    let problems = sprites::check_theme(theme, &config.assets);
+    if problems.is_empty() {
+        println!("  {}", tr!("doctor-sheets-ok"));
+    } else {
+        println!("{}", tr!("doctor-sheet-problems"));
+        for problem in &problems {
+            println!("  ✗ {}", problem);
+        }
+    }
+
+    let themes = assets::list_themes(&config.assets.dirs);
+    println!();
+    if themes.is_empty() {
+        println!("{}", tr!("doctor-no-themes"));
+    } else {
+        println!("{}", tr!("doctor-themes", themes = themes.join(", ")));
+    }
+}
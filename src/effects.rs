@@ -0,0 +1,195 @@
+//! Post-processing of the frame buffer, after the lamps are drawn and before
+//! it's presented.
+//!
+//! Bloom: everything brighter than `bloom_threshold` is blurred and added back
+//! on top, so bright lava blobs glow softly into their surroundings. The blur
+//! runs at 128 px per lamp whatever the buffer size, which keeps it cheap for
+//! SVG themes rendered at 1024×1024.
+//!
+//! CRT: the window becomes an old tube screen, with dark scanlines (one every
+//! other row of a 128 px lamp), a slightly bulging picture and darker corners.
+//! Where each pixel comes from and how much it's darkened only depends on the
+//! buffer size and the settings, so that map is computed once and reused.
+
+use serde::{Deserialize, Serialize};
+
+use crate::WINDOW_SIZE;
+
+/// The `[effects]` config section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EffectsConfig {
+    /// Strength of the glow, 0 (off) to 2.
+    pub bloom: f32,
+    /// Brightness (0-1) above which pixels start to glow.
+    pub bloom_threshold: f32,
+    /// How far the glow reaches, in pixels of a 128×128 lamp.
+    pub bloom_radius: usize,
+    /// The retro tube look; toggled at runtime with Ctrl+R or the tray.
+    pub crt: bool,
+    /// How much darker the scanlines are, 0 to 1.
+    pub crt_scanlines: f32,
+    /// How much the picture bulges, 0 (flat) to 0.5.
+    pub crt_curvature: f32,
+    /// How much darker the corners get, 0 to 1.
+    pub crt_vignette: f32,
+    /// Fade between animation frames instead of switching (see [`crate::lamp::draw_crossfaded`]).
+    pub interpolate: bool,
+    /// A burst of sparks or bubbles when a lamp changes color (see [`crate::particles`]).
+    pub particles: bool,
+    /// Particles per burst.
+    pub particle_count: usize,
+}
+
+impl Default for EffectsConfig {
+    fn default() -> Self {
+        EffectsConfig {
+            bloom: 0.0,
+            bloom_threshold: 0.6,
+            bloom_radius: 6,
+            crt: false,
+            crt_scanlines: 0.35,
+            crt_curvature: 0.08,
+            crt_vignette: 0.4,
+            interpolate: false,
+            particles: false,
+            particle_count: 40,
+        }
+    }
+}
+
+/// Scratch buffers of the passes, kept between frames.
+#[derive(Default)]
+pub struct PostProcess {
+    glow: Vec<[f32; 3]>,
+    scratch: Vec<[f32; 3]>,
+    /// Per pixel: the source pixel (`None` outside the bulged picture) and
+    /// the brightness factor.
+    crt_map: Vec<(Option<u32>, f32)>,
+    /// Buffer size and settings `crt_map` was computed for.
+    crt_key: Option<(usize, usize, [u32; 3])>,
+    crt_source: Vec<u8>,
+}
+
+impl PostProcess {
+    /// Applies the enabled effects to the RGBA `frame` of `width`×`height` pixels.
+    pub fn run(&mut self, frame: &mut [u8], width: usize, height: usize, config: &EffectsConfig) {
+        if config.bloom > 0.0 {
+            self.bloom(frame, width, height, config);
+        }
+        if config.crt {
+            self.crt(frame, width, height, config);
+        }
+    }
+
+    fn crt(&mut self, frame: &mut [u8], width: usize, height: usize, config: &EffectsConfig) {
+        let settings = [config.crt_scanlines, config.crt_curvature, config.crt_vignette];
+        let key = (width, height, settings.map(f32::to_bits));
+        if self.crt_key != Some(key) {
+            self.crt_map = crt_map(width, height, config);
+            self.crt_key = Some(key);
+        }
+
+        self.crt_source.clear();
+        self.crt_source.extend_from_slice(frame);
+        for (pixel, &(source, factor)) in frame.chunks_exact_mut(4).zip(&self.crt_map) {
+            let Some(source) = source else {
+                pixel[..3].fill(0);
+                continue;
+            };
+            let source = &self.crt_source[source as usize * 4..][..3];
+            for c in 0..3 {
+                pixel[c] = (source[c] as f32 * factor) as u8;
+            }
+        }
+    }
+
+    fn bloom(&mut self, frame: &mut [u8], width: usize, height: usize, config: &EffectsConfig) {
+        // Der Glanz wird in 128er-Auflösung berechnet und beim Addieren hochskaliert
+        let step = (height / WINDOW_SIZE).max(1);
+        let (w, h) = (width.div_ceil(step), height.div_ceil(step));
+
+        // Helle Stellen herausfiltern: nur der Anteil über der Schwelle leuchtet
+        let threshold = config.bloom_threshold.clamp(0.0, 0.99);
+        self.glow.clear();
+        for y in 0..h {
+            for x in 0..w {
+                let i = ((y * step) * width + x * step) * 4;
+                let [r, g, b] = [frame[i], frame[i + 1], frame[i + 2]].map(|c| c as f32 / 255.0);
+                let brightness = r.max(g).max(b);
+                let weight = ((brightness - threshold) / (1.0 - threshold)).max(0.0);
+                self.glow.push([r * weight, g * weight, b * weight]);
+            }
+        }
+
+        // Zwei Box-Blurs hintereinander kommen einem Gauß schon nahe
+        let radius = config.bloom_radius.max(1);
+        self.scratch.resize(self.glow.len(), [0.0; 3]);
+        for _ in 0..2 {
+            box_blur(&self.glow, &mut self.scratch, w, h, radius, 1, w);
+            box_blur(&self.scratch, &mut self.glow, h, w, radius, w, 1);
+        }
+
+        for y in 0..height {
+            let row = &self.glow[(y / step) * w..];
+            for x in 0..width {
+                let glow = row[x / step];
+                let pixel = &mut frame[(y * width + x) * 4..][..3];
+                for c in 0..3 {
+                    pixel[c] = (pixel[c] as f32 + glow[c] * config.bloom * 255.0).min(255.0) as u8;
+                }
+            }
+        }
+    }
+}
+
+fn crt_map(width: usize, height: usize, config: &EffectsConfig) -> Vec<(Option<u32>, f32)> {
+    let mut map = Vec::with_capacity(width * height);
+    // Mehrere Lampen nebeneinander sind ein breiter Bildschirm, keine einzelnen
+    let aspect = width as f32 / height as f32;
+    for y in 0..height {
+        let scanline = if (y * WINDOW_SIZE / height) % 2 == 1 { 1.0 - config.crt_scanlines } else { 1.0 };
+        for x in 0..width {
+            // -1..1 in beiden Achsen, Abstand zur Mitte mit richtigem Seitenverhältnis
+            let u = (x as f32 + 0.5) / width as f32 * 2.0 - 1.0;
+            let v = (y as f32 + 0.5) / height as f32 * 2.0 - 1.0;
+            let r2 = ((u * aspect).powi(2) + v * v) / (aspect * aspect + 1.0);
+
+            let bulge = 1.0 + config.crt_curvature * r2;
+            let (su, sv) = (u * bulge, v * bulge);
+            if su.abs() > 1.0 || sv.abs() > 1.0 {
+                map.push((None, 0.0));
+                continue;
+            }
+            let sx = (((su + 1.0) / 2.0 * width as f32) as usize).min(width - 1);
+            let sy = (((sv + 1.0) / 2.0 * height as f32) as usize).min(height - 1);
+            let vignette = 1.0 - config.crt_vignette * r2 * r2;
+            map.push((Some((sy * width + sx) as u32), scanline * vignette));
+        }
+    }
+    map
+}
+
+/// Blurs `lines` lines of `len` values each along the line with a running
+/// sum. `along` is the index distance between neighbors on a line, `across`
+/// the distance between the starts of two lines, so the same function does
+/// rows and columns.
+fn box_blur(from: &[[f32; 3]], to: &mut [[f32; 3]], len: usize, lines: usize, radius: usize, along: usize, across: usize) {
+    let window = (2 * radius + 1) as f32;
+    for line in 0..lines {
+        let at = |i: usize| line * across + i * along;
+        // Ränder werden fortgesetzt, damit der Glanz am Fensterrand nicht dunkler wird
+        let sample = |i: isize| from[at(i.clamp(0, len as isize - 1) as usize)];
+
+        let mut sum = [0.0f32; 3];
+        for i in -(radius as isize)..=radius as isize {
+            let value = sample(i);
+            (0..3).for_each(|c| sum[c] += value[c]);
+        }
+        for i in 0..len {
+            to[at(i)] = sum.map(|s| s / window);
+            let (leaving, entering) = (sample(i as isize - radius as isize), sample(i as isize + radius as isize + 1));
+            (0..3).for_each(|c| sum[c] += entering[c] - leaving[c]);
+        }
+    }
+}
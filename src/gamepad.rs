@@ -0,0 +1,72 @@
+//! Gamepad input for a lamp running on a TV without a keyboard: the d-pad
+//! resizes (up/down) and moves the lamp from corner to corner (left/right),
+//! the south button (A on Xbox, × on PlayStation) pauses. Needs a build with
+//! `--features gamepad`.
+//!
+//! Gamepads are read on their own thread, which sends the buttons into the
+//! event loop as the same actions the keyboard shortcuts trigger.
+
+use serde::{Deserialize, Serialize};
+use winit::event_loop::EventLoopProxy;
+
+use crate::AppEvent;
+
+/// The `[gamepad]` config section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GamepadConfig {
+    /// Listen for gamepads; read at startup.
+    pub enabled: bool,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        GamepadConfig { enabled: true }
+    }
+}
+
+/// Starts the gamepad thread. It ends on its own once the event loop is gone.
+#[cfg(feature = "gamepad")]
+pub fn spawn(proxy: EventLoopProxy<AppEvent>) {
+    use gilrs::{Button, EventType, Gilrs};
+    use std::thread;
+
+    use crate::i18n::tr;
+    use crate::keys::Action;
+
+    let _ = thread::Builder::new().name("gamepad".into()).spawn(move || {
+        let mut gilrs = match Gilrs::new() {
+            Ok(gilrs) => gilrs,
+            Err(e) => {
+                log::error!("{}", tr!("gamepad-failed", error = e));
+                return;
+            }
+        };
+        for (_, gamepad) in gilrs.gamepads() {
+            log::info!("{}", tr!("gamepad-connected", name = gamepad.name()));
+        }
+        while let Some(event) = gilrs.next_event_blocking(None) {
+            let app_event = match event.event {
+                EventType::Connected => {
+                    log::info!("{}", tr!("gamepad-connected", name = gilrs.gamepad(event.id).name()));
+                    continue;
+                }
+                EventType::ButtonPressed(Button::DPadUp, _) => AppEvent::Shortcut(Action::ScaleUp),
+                EventType::ButtonPressed(Button::DPadDown, _) => AppEvent::Shortcut(Action::ScaleDown),
+                EventType::ButtonPressed(Button::DPadRight, _) => AppEvent::Shortcut(Action::NextCorner),
+                EventType::ButtonPressed(Button::DPadLeft, _) => AppEvent::Shortcut(Action::PreviousCorner),
+                EventType::ButtonPressed(Button::South, _) => AppEvent::TogglePause,
+                _ => continue,
+            };
+            if proxy.send_event(app_event).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+/// Without the `gamepad` feature there's nothing to listen with.
+#[cfg(not(feature = "gamepad"))]
+pub fn spawn(_proxy: EventLoopProxy<AppEvent>) {
+    log::debug!("{}", crate::i18n::tr!("gamepad-not-built"));
+}
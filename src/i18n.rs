@@ -0,0 +1,103 @@
+//! Message catalogs for all user-facing text.
+//!
+//! The catalogs live in `locales/<lang>.ftl` and use a small subset of the
+//! Fluent syntax: one `key = text` per line, `# comments`, and `{ $name }`
+//! placeholders. Keys missing in a translation fall back to English.
+
+use std::collections::HashMap;
+use std::env;
+use std::sync::RwLock;
+
+const CATALOGS: [(&str, &str); 2] = [
+    ("en", include_str!("../locales/en.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+];
+
+struct Catalog {
+    language: &'static str,
+    messages: HashMap<&'static str, &'static str>,
+    fallback: HashMap<&'static str, &'static str>,
+}
+
+lazy_static::lazy_static! {
+    static ref CATALOG: RwLock<Catalog> = RwLock::new(Catalog::new(detect_language()));
+}
+
+/// Translates `key`, replacing `{ $name }` placeholders. Prefer the [`tr!`] macro.
+pub fn translate(key: &str, args: &[(&str, String)]) -> String {
+    let catalog = CATALOG.read().unwrap();
+    let template = match catalog.messages.get(key).or_else(|| catalog.fallback.get(key)) {
+        Some(template) => *template,
+        None => return key.to_string(),
+    };
+
+    let mut text = template.to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{ ${} }}", name), value);
+    }
+    text
+}
+
+/// Switches the language, e.g. from the `language` config key. Unknown languages are ignored.
+pub fn set_language(language: &str) {
+    match find_catalog(language) {
+        Some(code) => *CATALOG.write().unwrap() = Catalog::new(code),
+        None => log::warn!("{}", tr!("unknown-language", language = language, current = current_language())),
+    }
+}
+
+pub fn is_supported(language: &str) -> bool {
+    find_catalog(language).is_some()
+}
+
+pub fn current_language() -> &'static str {
+    CATALOG.read().unwrap().language
+}
+
+impl Catalog {
+    fn new(language: &'static str) -> Self {
+        Catalog {
+            language,
+            messages: parse(CATALOGS.iter().find(|(code, _)| *code == language).map_or("", |(_, src)| src)),
+            fallback: parse(CATALOGS[0].1),
+        }
+    }
+}
+
+fn parse(source: &'static str) -> HashMap<&'static str, &'static str> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect()
+}
+
+/// Maps "de_DE.UTF-8", "de-AT" or "de" to a catalog code.
+fn find_catalog(locale: &str) -> Option<&'static str> {
+    let language = locale.split(['_', '-', '.', '@']).next().unwrap_or("").to_lowercase();
+    CATALOGS.iter().map(|(code, _)| *code).find(|code| *code == language)
+}
+
+/// Uses the first of `LC_ALL`, `LC_MESSAGES` and `LANG` that is set, like gettext does.
+fn detect_language() -> &'static str {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|locale| find_catalog(&locale))
+        .unwrap_or("en")
+}
+
+/// `tr!("key")` or `tr!("key", name = value, ...)`, where every value implements `Display`.
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::i18n::translate($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+pub(crate) use tr;
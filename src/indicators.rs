@@ -0,0 +1,192 @@
+//! Indicators: small extras drawn on top of a lamp for a second metric, so a
+//! tiny window can carry more than one value — a thin CPU bar along the
+//! bottom edge, a dot in the corner that turns red when swap fills up.
+//!
+//! Each `[[indicators]]` entry picks a metric, a kind and where it sits. The
+//! color follows the thresholds of its own metric (including `[per_metric]`).
+//! Sizes are given for a 128×128 lamp and grow with the lamp like the pixel art.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::metrics::{Metric, Sampler};
+use crate::tier::Tier;
+use crate::WINDOW_SIZE;
+
+/// Thickness of a bar on a 128×128 lamp.
+const BAR_THICKNESS: usize = 3;
+/// Diameter of a dot on a 128×128 lamp.
+const DOT_SIZE: usize = 8;
+/// Distance of a dot from the edges on a 128×128 lamp.
+const DOT_MARGIN: usize = 4;
+/// Brightness of the empty part of a bar.
+const TRACK_DIM: u8 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndicatorKind {
+    /// A thin bar along one edge, filled up to the percentage.
+    Bar,
+    /// A dot in the metric's tier color.
+    Dot,
+}
+
+/// Where on the lamp an indicator sits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Anchor {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Edges fit a bar, corners don't.
+    pub fn is_edge(&self) -> bool {
+        matches!(self, Anchor::Top | Anchor::Bottom | Anchor::Left | Anchor::Right)
+    }
+}
+
+/// One `[[indicators]]` entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndicatorConfig {
+    pub metric: Metric,
+    pub kind: IndicatorKind,
+    /// An edge for bars; any edge or corner for dots.
+    pub position: Anchor,
+    /// Index of the lamp to draw on in multi-lamp layouts (0 is the leftmost); every lamp if not set.
+    pub lamp: Option<usize>,
+}
+
+impl Default for IndicatorConfig {
+    fn default() -> Self {
+        IndicatorConfig { metric: Metric::Cpu, kind: IndicatorKind::Bar, position: Anchor::Bottom, lamp: None }
+    }
+}
+
+/// The latest value of every configured indicator.
+#[derive(Default)]
+pub struct Indicators {
+    values: Vec<(f64, Tier)>,
+}
+
+impl Indicators {
+    /// Samples every indicator. `shown` and `lamps` are the lamps' metric and
+    /// values: an indicator of the same metric takes their average instead of
+    /// reading it a second time, which would spoil rates like the CPU load.
+    /// Returns whether anything visible changed.
+    pub fn sample(&mut self, sampler: &mut Sampler, config: &Config, shown: Metric, lamps: &[f64]) -> bool {
+        let values: Vec<(f64, Tier)> = config
+            .indicators
+            .iter()
+            .map(|indicator| {
+                let percent = if indicator.metric == shown && !lamps.is_empty() {
+                    lamps.iter().sum::<f64>() / lamps.len() as f64
+                } else {
+                    sampler.sample(indicator.metric, &config.metrics)
+                };
+                (percent, Tier::from_percent(percent, config.thresholds_for(indicator.metric)))
+            })
+            .collect();
+        // Ein Balken ändert sich nur, wenn ein ganzer Pixel dazukommt; grob auf Prozent runden reicht
+        let rounded = |values: &[(f64, Tier)]| values.iter().map(|(p, t)| (p.round() as i64, *t)).collect::<Vec<_>>();
+        let changed = rounded(&values) != rounded(&self.values);
+        self.values = values;
+        changed
+    }
+
+    /// Draws the indicators over a frame that is `slots` lamps wide and `side` pixels high.
+    pub fn draw(&self, frame: &mut [u8], slots: usize, side: usize, config: &[IndicatorConfig]) {
+        let scale = side as f64 / WINDOW_SIZE as f64;
+        for (indicator, (percent, tier)) in config.iter().zip(&self.values) {
+            for slot in (0..slots).filter(|slot| indicator.lamp.is_none_or(|lamp| lamp == *slot)) {
+                let canvas = Canvas { frame, width: slots * side, left: slot * side, side };
+                match indicator.kind {
+                    IndicatorKind::Bar => draw_bar(canvas, scale, indicator.position, *percent, *tier),
+                    IndicatorKind::Dot => draw_dot(canvas, scale, indicator.position, *tier),
+                }
+            }
+        }
+    }
+}
+
+/// One lamp's square of the frame buffer.
+struct Canvas<'a> {
+    frame: &'a mut [u8],
+    width: usize,
+    left: usize,
+    side: usize,
+}
+
+impl Canvas<'_> {
+    /// Runs `paint` on every pixel of the rectangle, in lamp coordinates.
+    fn fill(&mut self, x: usize, y: usize, w: usize, h: usize, mut paint: impl FnMut(usize, usize, &mut [u8])) {
+        for py in y..(y + h).min(self.side) {
+            for px in x..(x + w).min(self.side) {
+                let i = (py * self.width + self.left + px) * 4;
+                paint(px, py, &mut self.frame[i..i + 4]);
+            }
+        }
+    }
+}
+
+fn draw_bar(mut canvas: Canvas, scale: f64, edge: Anchor, percent: f64, tier: Tier) {
+    let side = canvas.side;
+    let thickness = ((BAR_THICKNESS as f64 * scale).round() as usize).max(1);
+    let filled = (side as f64 * percent.clamp(0.0, 100.0) / 100.0).round() as usize;
+    let [r, g, b, _] = tier.debug_color();
+    // Waagerechte Balken füllen sich von links, senkrechte von unten
+    let (x, y, w, h) = match edge {
+        Anchor::Top => (0, 0, side, thickness),
+        Anchor::Left => (0, 0, thickness, side),
+        Anchor::Right => (side - thickness, 0, thickness, side),
+        _ => (0, side - thickness, side, thickness),
+    };
+    let vertical = matches!(edge, Anchor::Left | Anchor::Right);
+    canvas.fill(x, y, w, h, |px, py, pixel| {
+        let lit = if vertical { py >= side - filled } else { px < filled };
+        if lit {
+            pixel[..3].copy_from_slice(&[r, g, b]);
+        } else {
+            for channel in &mut pixel[..3] {
+                *channel /= TRACK_DIM;
+            }
+        }
+    });
+}
+
+fn draw_dot(mut canvas: Canvas, scale: f64, anchor: Anchor, tier: Tier) {
+    let side = canvas.side;
+    let size = ((DOT_SIZE as f64 * scale).round() as usize).clamp(2, side);
+    let margin = (DOT_MARGIN as f64 * scale).round() as usize;
+    let far = side.saturating_sub(size + margin);
+    let middle = (side - size) / 2;
+    let (x, y) = match anchor {
+        Anchor::Top => (middle, margin),
+        Anchor::Bottom => (middle, far),
+        Anchor::Left => (margin, middle),
+        Anchor::Right => (far, middle),
+        Anchor::TopLeft => (margin, margin),
+        Anchor::TopRight => (far, margin),
+        Anchor::BottomLeft => (margin, far),
+        Anchor::BottomRight => (far, far),
+    };
+    let [r, g, b, _] = tier.debug_color();
+    let radius = size as f64 / 2.0;
+    let (cx, cy) = (x as f64 + radius, y as f64 + radius);
+    canvas.fill(x, y, size, size, |px, py, pixel| {
+        let distance = ((px as f64 + 0.5 - cx).powi(2) + (py as f64 + 0.5 - cy).powi(2)).sqrt();
+        // Dunkler Rand, damit der Punkt auch auf Lava derselben Farbe zu sehen ist
+        if distance <= radius - scale.max(1.0) {
+            pixel[..3].copy_from_slice(&[r, g, b]);
+        } else if distance <= radius {
+            pixel[..3].copy_from_slice(&[0, 0, 0]);
+        }
+    });
+}
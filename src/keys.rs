@@ -0,0 +1,230 @@
+//! Keyboard shortcuts, configurable in the `[keys]` section.
+//!
+//! Keys are matched by their position on the keyboard (the scancode) and
+//! named after the key in that position on a US QWERTY keyboard, so Ctrl+Up,
+//! Ctrl+P and the others sit in the same place on QWERTZ, AZERTY, Dvorak or
+//! dead-key layouts, where the key might produce a different character or
+//! none at all. With `physical = false` the character the layout produces is
+//! matched instead. Ctrl is always Ctrl.
+
+use serde::{Deserialize, Serialize};
+use winit::event::{KeyboardInput, VirtualKeyCode};
+
+use crate::i18n::tr;
+
+/// What a shortcut or gamepad button does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    ScaleUp,
+    ScaleDown,
+    NextProfile,
+    ToggleCrt,
+    ToggleBorderless,
+    /// Switch desktop widget mode on or off.
+    ToggleWidget,
+    /// Let clicks through to the windows below, or catch them again.
+    ToggleClickThrough,
+    /// Dock in the next corner clockwise.
+    NextCorner,
+    /// Dock in the next corner counterclockwise.
+    PreviousCorner,
+    /// Take a baseline, or drop it again.
+    ToggleBaseline,
+    Quit,
+}
+
+/// A key, optionally with Ctrl: `"ctrl+up"`, `"escape"`, `"ctrl+p"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Binding {
+    pub ctrl: bool,
+    pub key: VirtualKeyCode,
+}
+
+impl Binding {
+    const fn ctrl(key: VirtualKeyCode) -> Self {
+        Binding { ctrl: true, key }
+    }
+}
+
+impl TryFrom<String> for Binding {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, String> {
+        let lower = text.trim().to_lowercase();
+        let (ctrl, name) = match lower.strip_prefix("ctrl+") {
+            Some(name) => (true, name),
+            None => (false, lower.as_str()),
+        };
+        let key = NAMES
+            .iter()
+            .find(|(known, _)| *known == name)
+            .map(|(_, key)| *key)
+            .ok_or_else(|| tr!("keys-unknown", key = text.clone()))?;
+        Ok(Binding { ctrl, key })
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> String {
+        let name = NAMES.iter().find(|(_, key)| *key == binding.key).map_or("?", |(name, _)| name);
+        if binding.ctrl { format!("ctrl+{}", name) } else { name.to_string() }
+    }
+}
+
+/// The `[keys]` config section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeysConfig {
+    /// Match keys by their position instead of the character they produce.
+    pub physical: bool,
+    pub scale_up: Binding,
+    pub scale_down: Binding,
+    pub next_profile: Binding,
+    pub toggle_crt: Binding,
+    pub toggle_borderless: Binding,
+    pub toggle_widget: Binding,
+    pub toggle_click_through: Binding,
+    pub next_corner: Binding,
+    pub previous_corner: Binding,
+    pub baseline: Binding,
+    pub quit: Binding,
+}
+
+impl Default for KeysConfig {
+    fn default() -> Self {
+        KeysConfig {
+            physical: true,
+            scale_up: Binding::ctrl(VirtualKeyCode::Up),
+            scale_down: Binding::ctrl(VirtualKeyCode::Down),
+            next_profile: Binding::ctrl(VirtualKeyCode::P),
+            toggle_crt: Binding::ctrl(VirtualKeyCode::R),
+            toggle_borderless: Binding::ctrl(VirtualKeyCode::B),
+            toggle_widget: Binding::ctrl(VirtualKeyCode::W),
+            toggle_click_through: Binding::ctrl(VirtualKeyCode::T),
+            next_corner: Binding::ctrl(VirtualKeyCode::Right),
+            previous_corner: Binding::ctrl(VirtualKeyCode::Left),
+            baseline: Binding::ctrl(VirtualKeyCode::S),
+            quit: Binding { ctrl: false, key: VirtualKeyCode::Escape },
+        }
+    }
+}
+
+impl KeysConfig {
+    fn bindings(&self) -> [(Action, Binding); 11] {
+        [
+            (Action::ScaleUp, self.scale_up),
+            (Action::ScaleDown, self.scale_down),
+            (Action::NextProfile, self.next_profile),
+            (Action::ToggleCrt, self.toggle_crt),
+            (Action::ToggleBorderless, self.toggle_borderless),
+            (Action::ToggleWidget, self.toggle_widget),
+            (Action::ToggleClickThrough, self.toggle_click_through),
+            (Action::NextCorner, self.next_corner),
+            (Action::PreviousCorner, self.previous_corner),
+            (Action::ToggleBaseline, self.baseline),
+            (Action::Quit, self.quit),
+        ]
+    }
+
+    /// The action bound to the pressed key in `input`, with Ctrl held or not.
+    pub fn action(&self, input: &KeyboardInput, ctrl: bool) -> Option<Action> {
+        let key = match self.physical {
+            // Unbekannte Scancodes (Sondertasten) über den Keysym
+            true => physical_key(input.scancode).or(input.virtual_keycode),
+            false => input.virtual_keycode,
+        }?;
+        self.bindings()
+            .into_iter()
+            .find(|(_, binding)| binding.key == key && binding.ctrl == ctrl)
+            .map(|(action, _)| action)
+    }
+
+    /// A binding used for more than one action, if there is one.
+    pub fn conflict(&self) -> Option<Binding> {
+        let bindings = self.bindings();
+        bindings
+            .iter()
+            .enumerate()
+            .find(|(i, (_, binding))| bindings[i + 1..].iter().any(|(_, other)| other == binding))
+            .map(|(_, (_, binding))| *binding)
+    }
+}
+
+/// Config names of the keys that can be bound.
+const NAMES: &[(&str, VirtualKeyCode)] = {
+    use VirtualKeyCode::*;
+    &[
+        ("a", A), ("b", B), ("c", C), ("d", D), ("e", E), ("f", F), ("g", G), ("h", H), ("i", I),
+        ("j", J), ("k", K), ("l", L), ("m", M), ("n", N), ("o", O), ("p", P), ("q", Q), ("r", R),
+        ("s", S), ("t", T), ("u", U), ("v", V), ("w", W), ("x", X), ("y", Y), ("z", Z),
+        ("0", Key0), ("1", Key1), ("2", Key2), ("3", Key3), ("4", Key4),
+        ("5", Key5), ("6", Key6), ("7", Key7), ("8", Key8), ("9", Key9),
+        ("f1", F1), ("f2", F2), ("f3", F3), ("f4", F4), ("f5", F5), ("f6", F6),
+        ("f7", F7), ("f8", F8), ("f9", F9), ("f10", F10), ("f11", F11), ("f12", F12),
+        ("up", Up), ("down", Down), ("left", Left), ("right", Right),
+        ("home", Home), ("end", End), ("page-up", PageUp), ("page-down", PageDown),
+        ("escape", Escape), ("space", Space), ("enter", Return), ("tab", Tab), ("backspace", Back),
+        ("minus", Minus), ("equals", Equals),
+    ]
+};
+
+/// The US QWERTY key at the position of `scancode`.
+fn physical_key(scancode: u32) -> Option<VirtualKeyCode> {
+    SCANCODES.iter().chain(NAVIGATION).find(|(code, _)| *code == scancode).map(|(_, key)| *key)
+}
+
+/// evdev codes on Linux and the BSDs; the main block matches the PC
+/// scancodes Windows reports.
+#[cfg(not(target_os = "macos"))]
+const SCANCODES: &[(u32, VirtualKeyCode)] = {
+    use VirtualKeyCode::*;
+    &[
+        (1, Escape), (2, Key1), (3, Key2), (4, Key3), (5, Key4), (6, Key5), (7, Key6), (8, Key7),
+        (9, Key8), (10, Key9), (11, Key0), (12, Minus), (13, Equals), (14, Back), (15, Tab),
+        (16, Q), (17, W), (18, E), (19, R), (20, T), (21, Y), (22, U), (23, I), (24, O), (25, P),
+        (28, Return), (30, A), (31, S), (32, D), (33, F), (34, G), (35, H), (36, J), (37, K), (38, L),
+        (44, Z), (45, X), (46, C), (47, V), (48, B), (49, N), (50, M), (57, Space),
+        (59, F1), (60, F2), (61, F3), (62, F4), (63, F5), (64, F6), (65, F7), (66, F8), (67, F9),
+        (68, F10), (87, F11), (88, F12),
+    ]
+};
+
+/// Arrows and the keys above them, which evdev numbers on its own.
+#[cfg(not(any(target_os = "macos", windows)))]
+const NAVIGATION: &[(u32, VirtualKeyCode)] = {
+    use VirtualKeyCode::*;
+    &[(102, Home), (103, Up), (104, PageUp), (105, Left), (106, Right), (107, End), (108, Down), (109, PageDown)]
+};
+
+/// Windows reports the arrows and the keys above them as "extended", with
+/// an 0xE0 prefix.
+#[cfg(windows)]
+const NAVIGATION: &[(u32, VirtualKeyCode)] = {
+    use VirtualKeyCode::*;
+    &[
+        (0xE047, Home), (0xE048, Up), (0xE049, PageUp), (0xE04B, Left),
+        (0xE04D, Right), (0xE04F, End), (0xE050, Down), (0xE051, PageDown),
+    ]
+};
+
+/// Already part of [`SCANCODES`] on macOS.
+#[cfg(target_os = "macos")]
+const NAVIGATION: &[(u32, VirtualKeyCode)] = &[];
+
+/// macOS virtual key codes (`kVK_ANSI_*`), which are positions too.
+#[cfg(target_os = "macos")]
+const SCANCODES: &[(u32, VirtualKeyCode)] = {
+    use VirtualKeyCode::*;
+    &[
+        (0x00, A), (0x01, S), (0x02, D), (0x03, F), (0x04, H), (0x05, G), (0x06, Z), (0x07, X),
+        (0x08, C), (0x09, V), (0x0b, B), (0x0c, Q), (0x0d, W), (0x0e, E), (0x0f, R), (0x10, Y),
+        (0x11, T), (0x12, Key1), (0x13, Key2), (0x14, Key3), (0x15, Key4), (0x16, Key6), (0x17, Key5),
+        (0x18, Equals), (0x19, Key9), (0x1a, Key7), (0x1b, Minus), (0x1c, Key8), (0x1d, Key0),
+        (0x1f, O), (0x20, U), (0x22, I), (0x23, P), (0x24, Return), (0x25, L), (0x26, J), (0x28, K),
+        (0x2d, N), (0x2e, M), (0x30, Tab), (0x31, Space), (0x33, Back), (0x35, Escape),
+        (0x60, F5), (0x61, F6), (0x62, F7), (0x63, F3), (0x64, F8), (0x65, F9), (0x67, F11),
+        (0x6d, F10), (0x6f, F12), (0x73, Home), (0x74, PageUp), (0x76, F4), (0x77, End), (0x78, F2),
+        (0x79, PageDown), (0x7a, F1), (0x7b, Left), (0x7c, Right), (0x7d, Down), (0x7e, Up),
+    ]
+};
@@ -0,0 +1,586 @@
+//! The lamps drawn side by side in the window (see [`Layout`](crate::config::Layout)),
+//! each with its own value, color and animation.
+
+use image::Rgba;
+use std::collections::{HashMap, VecDeque};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+use winit::event_loop::EventLoopProxy;
+
+use crate::config::{Config, SpeedConfig};
+use crate::i18n::tr;
+use crate::script::Mapping;
+use crate::sprites::{AssetConfig, SpriteSheet};
+use crate::tier::Extreme;
+use crate::{blend_alpha, AppEvent, ANIMATION_FRAMES, WINDOW_SIZE};
+
+const FALLBACK_SPRITE: &str = "lavalampe_green.png";
+
+/// After a stall (suspend, dragged window) the animation continues from where it
+/// was instead of racing through the frames it missed.
+const MAX_CATCH_UP_FRAMES: u32 = 4;
+
+/// Change in percentage points per sample below which the trend counts as
+/// flat and the animation keeps its direction.
+const TREND_DEAD_ZONE: f64 = 0.05;
+
+/// Time between redraws while frames are crossfaded.
+pub const CROSSFADE_STEP: Duration = Duration::from_millis(33);
+
+/// Time between redraws while a lamp dozes, and one slow breath of it.
+pub const DOZE_STEP: Duration = Duration::from_millis(100);
+const DOZE_BREATH: Duration = Duration::from_secs(5);
+
+/// State of one lamp.
+pub struct Lamp {
+    pub percent: f64,
+    pub mapping: Mapping,
+    pub frame_index: usize,
+    /// Time not yet turned into animation frames.
+    pub accumulator: Duration,
+    /// Duration of the current frame if the sheet sets one per frame.
+    frame_step: Option<Duration>,
+    pub crossfade: Crossfade,
+    /// The last samples, oldest first, for the trend.
+    history: VecDeque<f64>,
+    /// Whether the animation currently plays backwards.
+    pub reverse: bool,
+    /// Time spent dozing, for the pulse.
+    dozed: Duration,
+}
+
+/// The last two animation frames of a lamp at the size it's drawn, so
+/// [`draw_crossfaded`] can blend from one into the other. Stays empty unless
+/// interpolation is switched on.
+#[derive(Default)]
+pub struct Crossfade {
+    previous: Vec<u8>,
+    current: Vec<u8>,
+    /// Sprite, frame and side `current` holds.
+    shown: Option<(&'static str, usize, usize)>,
+}
+
+impl Lamp {
+    pub fn new(percent: f64, mapping: Mapping) -> Lamp {
+        Lamp {
+            percent,
+            mapping,
+            frame_index: 0,
+            accumulator: Duration::ZERO,
+            frame_step: None,
+            crossfade: Crossfade::default(),
+            history: VecDeque::new(),
+            reverse: false,
+            dozed: Duration::ZERO,
+        }
+    }
+
+    /// Asleep without a sheet for it: the animation stands still, only the
+    /// pulse goes on.
+    pub fn doze(&mut self, elapsed: Duration) {
+        self.dozed = Duration::from_secs_f64((self.dozed + elapsed).as_secs_f64() % DOZE_BREATH.as_secs_f64());
+    }
+
+    /// Takes a new sample. With `reverse_when_falling`, the animation turns
+    /// around when the trend over the last samples changes direction.
+    pub fn record(&mut self, percent: f64, speed: &SpeedConfig) {
+        self.percent = percent;
+        self.history.push_back(percent);
+        while self.history.len() > speed.trend_samples {
+            self.history.pop_front();
+        }
+        if !speed.reverse_when_falling {
+            self.reverse = false;
+            return;
+        }
+        let slope = trend(&self.history);
+        if slope < -TREND_DEAD_ZONE {
+            self.reverse = true;
+        } else if slope > TREND_DEAD_ZONE {
+            self.reverse = false;
+        }
+    }
+
+    /// Fixed timestep: advances by as many whole frames as fit into the time
+    /// since the last redraw, so the speed doesn't depend on the refresh rate.
+    /// `durations` are per-frame times from the sheet, if it has them.
+    fn advance(&mut self, elapsed: Duration, frame_count: usize, durations: Option<&[Duration]>) {
+        let Some(durations) = durations else {
+            self.frame_step = None;
+            let step = self.mapping.frame_duration;
+            self.accumulator = (self.accumulator + elapsed).min(step * MAX_CATCH_UP_FRAMES);
+
+            let steps = (self.accumulator.as_secs_f64() / step.as_secs_f64()) as u32;
+            self.accumulator -= step * steps;
+            self.frame_index = self.next_frame(steps as usize, frame_count);
+            return;
+        };
+
+        // Ein Skript oder `[speed]`, das das Tempo ändert, skaliert die Zeiten aus der Datei
+        let speed = self.mapping.frame_duration.as_secs_f64() / self.mapping.tier.frame_duration().as_secs_f64();
+        let step = |frame: usize| durations[frame].mul_f64(speed).max(Duration::from_millis(1));
+        self.accumulator = (self.accumulator + elapsed).min(step(self.frame_index) * MAX_CATCH_UP_FRAMES);
+        while self.accumulator >= step(self.frame_index) {
+            self.accumulator -= step(self.frame_index);
+            self.frame_index = self.next_frame(1, frame_count);
+        }
+        self.frame_step = Some(step(self.frame_index));
+    }
+
+    /// The frame `steps` frames on, in the current direction.
+    fn next_frame(&self, steps: usize, frame_count: usize) -> usize {
+        if self.reverse {
+            (self.frame_index + frame_count - steps % frame_count) % frame_count
+        } else {
+            (self.frame_index + steps) % frame_count
+        }
+    }
+
+    /// How long the current frame is shown.
+    pub fn step(&self) -> Duration {
+        self.frame_step.unwrap_or(self.mapping.frame_duration)
+    }
+}
+
+enum SheetState {
+    /// Being loaded on a background thread.
+    Loading,
+    /// Loaded; `None` if that failed.
+    Ready(Option<SpriteSheet>),
+}
+
+struct CacheEntry {
+    state: SheetState,
+    /// [`SpriteCache::clock`] at the last access.
+    last_used: u64,
+}
+
+impl CacheEntry {
+    fn sheet(&mut self) -> Option<&mut SpriteSheet> {
+        match &mut self.state {
+            SheetState::Ready(sheet) => sheet.as_mut(),
+            SheetState::Loading => None,
+        }
+    }
+
+    fn memory_size(&self) -> usize {
+        match &self.state {
+            SheetState::Ready(Some(sheet)) => sheet.memory_size(),
+            _ => 0,
+        }
+    }
+}
+
+/// A finished background load: generation, file and result.
+type Loaded = (u64, &'static str, Option<SpriteSheet>);
+
+/// Sprite sheets by file name, loaded on first use and shared by all lamps.
+/// Once they take more than `cache_budget_mb`, the least recently used sheets
+/// are dropped (and loaded again when needed).
+///
+/// The window's cache loads in the background, so the lamp shows up at once
+/// (with a placeholder) even when decoding takes a while on a slow disk.
+#[derive(Default)]
+pub struct SpriteCache {
+    sheets: HashMap<&'static str, CacheEntry>,
+    /// Counts redraws; sheets used in the current one are never evicted.
+    clock: u64,
+    /// Bumped by [`clear`](Self::clear), so loads started before are discarded.
+    generation: u64,
+    background: Option<Background>,
+}
+
+struct Background {
+    proxy: EventLoopProxy<AppEvent>,
+    sender: Sender<Loaded>,
+    receiver: Receiver<Loaded>,
+}
+
+impl SpriteCache {
+    /// A cache that loads on background threads and sends
+    /// [`AppEvent::SpriteLoaded`] when a sheet is ready.
+    pub fn in_background(proxy: EventLoopProxy<AppEvent>) -> SpriteCache {
+        let (sender, receiver) = mpsc::channel();
+        SpriteCache { background: Some(Background { proxy, sender, receiver }), ..SpriteCache::default() }
+    }
+
+    /// Forgets all sheets, e.g. after a theme or storage change.
+    pub fn clear(&mut self) {
+        self.sheets.clear();
+        self.generation += 1;
+    }
+
+    /// Starts a new redraw and takes over sheets finished in the background.
+    pub fn tick(&mut self, budget_mb: u64) {
+        self.clock += 1;
+
+        let Some(background) = &self.background else { return };
+        let finished: Vec<Loaded> = background.receiver.try_iter().collect();
+        for (generation, file, sheet) in finished {
+            if generation == self.generation {
+                self.insert(file, sheet, budget_mb);
+            }
+        }
+    }
+
+    /// Whether `file` (or the green sheet standing in for it) is still being
+    /// loaded in the background.
+    pub fn is_loading(&self, file: &str) -> bool {
+        match self.sheets.get(file).map(|entry| &entry.state) {
+            Some(SheetState::Loading) => true,
+            Some(SheetState::Ready(None)) if file != FALLBACK_SPRITE => self.is_loading(FALLBACK_SPRITE),
+            _ => false,
+        }
+    }
+
+    /// The sheet for `file`, falling back to the green one if it can't be loaded.
+    /// `None` while loading or if neither is available.
+    pub fn get(&mut self, theme: Option<&str>, assets: &AssetConfig, file: &'static str) -> Option<&mut SpriteSheet> {
+        if !self.sheets.contains_key(file) {
+            self.load(theme, assets, file);
+        }
+
+        let clock = self.clock;
+        let entry = self.sheets.get_mut(file)?;
+        entry.last_used = clock;
+        if matches!(entry.state, SheetState::Ready(None)) && file != FALLBACK_SPRITE {
+            if !self.sheets.contains_key(FALLBACK_SPRITE) {
+                log::info!("{}", tr!("asset-fallback"));
+                self.load(theme, assets, FALLBACK_SPRITE);
+            }
+            let entry = self.sheets.get_mut(FALLBACK_SPRITE)?;
+            entry.last_used = clock;
+            return entry.sheet();
+        }
+        self.sheets.get_mut(file)?.sheet()
+    }
+
+    fn load(&mut self, theme: Option<&str>, assets: &AssetConfig, file: &'static str) {
+        let Some(background) = &self.background else {
+            let sheet = load_sheet(theme, assets, file);
+            self.insert(file, sheet, assets.cache_budget_mb);
+            return;
+        };
+
+        let (owned_theme, owned_assets) = (theme.map(str::to_owned), assets.clone());
+        let (sender, proxy, generation) = (background.sender.clone(), background.proxy.clone(), self.generation);
+        let spawned = thread::Builder::new().name("sprite-loader".into()).spawn(move || {
+            let sheet = load_sheet(owned_theme.as_deref(), &owned_assets, file);
+            if sender.send((generation, file, sheet)).is_ok() {
+                let _ = proxy.send_event(AppEvent::SpriteLoaded);
+            }
+        });
+        match spawned {
+            Ok(_) => {
+                self.sheets.insert(file, CacheEntry { state: SheetState::Loading, last_used: self.clock });
+            }
+            // Ohne Thread eben direkt laden
+            Err(_) => {
+                let sheet = load_sheet(theme, assets, file);
+                self.insert(file, sheet, assets.cache_budget_mb);
+            }
+        }
+    }
+
+    fn insert(&mut self, file: &'static str, sheet: Option<SpriteSheet>, budget_mb: u64) {
+        let last_used = self.sheets.get(file).map_or(self.clock, |entry| entry.last_used);
+        self.sheets.insert(file, CacheEntry { state: SheetState::Ready(sheet), last_used });
+
+        let budget = budget_mb as usize * 1024 * 1024;
+        let mut total: usize = self.sheets.values().map(CacheEntry::memory_size).sum();
+        while total > budget {
+            let Some((&oldest, entry)) = self
+                .sheets
+                .iter()
+                .filter(|(_, entry)| entry.last_used < self.clock && entry.memory_size() > 0)
+                .min_by_key(|(_, entry)| entry.last_used)
+            else {
+                break;
+            };
+            total -= entry.memory_size();
+            log::debug!("{}", tr!("asset-evicted", file = oldest));
+            self.sheets.remove(oldest);
+        }
+    }
+}
+
+fn load_sheet(theme: Option<&str>, assets: &AssetConfig, file: &'static str) -> Option<SpriteSheet> {
+    let sheet = SpriteSheet::load(theme, file, assets);
+    match &sheet {
+        Some(_) => log::info!("{}", tr!("asset-loaded", file = file)),
+        None => log::error!("{}", tr!("asset-load-failed", file = file)),
+    }
+    sheet
+}
+
+/// Slope of the least-squares line through `samples`, in percentage points
+/// per sample; 0 with fewer than two.
+fn trend(samples: &VecDeque<f64>) -> f64 {
+    let n = samples.len() as f64;
+    if samples.len() < 2 {
+        return 0.0;
+    }
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = samples.iter().sum::<f64>() / n;
+    let (mut covariance, mut variance) = (0.0, 0.0);
+    for (x, y) in samples.iter().enumerate() {
+        let dx = x as f64 - mean_x;
+        covariance += dx * (y - mean_y);
+        variance += dx * dx;
+    }
+    covariance / variance
+}
+
+/// Advances the animation of `lamp` by `elapsed`.
+pub fn tick(lamp: &mut Lamp, animation: Option<&SpriteSheet>, elapsed: Duration) {
+    // Ohne Sheet läuft der Platzhalter mit der vollen Frame-Zahl
+    let actual_frame_count = animation.map_or(ANIMATION_FRAMES, SpriteSheet::frame_count);
+    // Ein anderes Sprite kann weniger Frames haben
+    lamp.frame_index %= actual_frame_count;
+    lamp.advance(elapsed, actual_frame_count, animation.and_then(SpriteSheet::frame_durations));
+}
+
+/// Advances every lamp by `elapsed`: a dozing one only pulses, the others
+/// step through their sheet. `extreme_sheets` are the theme's own extra sheets.
+pub fn tick_all(lamps: &mut [Lamp], sprites: &mut SpriteCache, config: &Config, extreme_sheets: &[Extreme], elapsed: Duration) {
+    let theme = config.active_theme();
+    for lamp in lamps {
+        if lamp.mapping.dozing(extreme_sheets) {
+            lamp.doze(elapsed);
+            continue;
+        }
+        let animation = sprites.get(theme, &config.assets, lamp.mapping.sprite_file(extreme_sheets));
+        tick(lamp, animation.as_deref(), elapsed);
+    }
+}
+
+/// Draws every lamp over the background in `frame`, side by side: the
+/// placeholder while its sheet loads, the sheet (crossfaded with
+/// `interpolate`) or the debug color without one, and the extremes on top
+/// for themes that don't have their own sheets for them.
+pub fn draw_all(
+    frame: &mut [u8],
+    slots: usize,
+    side: usize,
+    lamps: &mut [Lamp],
+    sprites: &mut SpriteCache,
+    config: &Config,
+    extreme_sheets: &[Extreme],
+) {
+    let theme = config.active_theme();
+    for (slot, lamp) in lamps.iter_mut().enumerate() {
+        // Bei On-Demand-Speicherung erst jetzt dekodieren, eine Lampe nach der anderen
+        let file = lamp.mapping.sprite_file(extreme_sheets);
+        if sprites.is_loading(file) {
+            draw_placeholder(frame, slots, slot, side, lamp);
+            continue;
+        }
+        let mut animation = sprites.get(theme, &config.assets, file);
+        if animation.as_mut().is_some_and(|sheet| !sheet.prepare(lamp.frame_index, side)) {
+            animation = None;
+        }
+        match animation {
+            Some(sheet) if config.effects.interpolate => draw_crossfaded(frame, slots, slot, side, lamp, file, sheet),
+            _ => draw(frame, slots, slot, side, lamp, animation.as_deref()),
+        }
+        match lamp.mapping.extreme {
+            Some(Extreme::Boiling) if !extreme_sheets.contains(&Extreme::Boiling) => {
+                draw_boiling(frame, slots, slot, side, lamp)
+            }
+            Some(Extreme::Sleeping) if lamp.mapping.dozing(extreme_sheets) => draw_dozing(frame, slots, slot, side, lamp),
+            _ => {}
+        }
+    }
+}
+
+/// Draws `lamp` into the lamp slot `slot` of a frame that is `slots` lamps
+/// wide and `side` pixels high. Frames of another size are scaled
+/// (nearest neighbor), e.g. PNG fallbacks in an SVG theme.
+pub fn draw(frame: &mut [u8], slots: usize, slot: usize, side: usize, lamp: &Lamp, animation: Option<&SpriteSheet>) {
+    let frame_width = slots * side;
+    let x_offset = slot * side;
+
+    // Debug pattern if no animation
+    let Some(sheet) = animation else {
+        let color = lamp.mapping.tier.debug_color();
+        for y in 0..side {
+            let row = (y * frame_width + x_offset) * 4;
+            for pixel in frame[row..row + side * 4].chunks_exact_mut(4) {
+                pixel.copy_from_slice(&color);
+            }
+        }
+        return;
+    };
+    let source_side = sheet.side();
+    for y in 0..side {
+        let source_row = sheet.row(lamp.frame_index, y * source_side / side);
+        let dest_row = (y * frame_width + x_offset) * 4;
+        let dest_row = &mut frame[dest_row..dest_row + side * 4];
+
+        for (x, dest) in dest_row.chunks_exact_mut(4).enumerate() {
+            let source = &source_row[x * source_side / side * 4..][..4];
+            let background = [dest[0], dest[1], dest[2], dest[3]];
+            let blended = blend_alpha(background, Rgba([source[0], source[1], source[2], source[3]]));
+            dest.copy_from_slice(&blended);
+        }
+    }
+}
+
+/// Like [`draw`], but fades from the previous frame into the current one over
+/// the time the current frame is shown, so slow animations don't look like
+/// a slideshow. Lags one frame behind [`draw`]. `file` is the sheet's name.
+pub fn draw_crossfaded(
+    frame: &mut [u8],
+    slots: usize,
+    slot: usize,
+    side: usize,
+    lamp: &mut Lamp,
+    file: &'static str,
+    sheet: &SpriteSheet,
+) {
+    let t = (lamp.accumulator.as_secs_f32() / lamp.step().as_secs_f32()).clamp(0.0, 1.0);
+    let key = (file, lamp.frame_index, side);
+    let fade = &mut lamp.crossfade;
+    if fade.shown != Some(key) {
+        std::mem::swap(&mut fade.previous, &mut fade.current);
+        fade.current.clear();
+        let source_side = sheet.side();
+        for y in 0..side {
+            let source_row = sheet.row(lamp.frame_index, y * source_side / side);
+            for x in 0..side {
+                fade.current.extend_from_slice(&source_row[x * source_side / side * 4..][..4]);
+            }
+        }
+        // Erster Frame oder neue Größe: nichts zum Überblenden
+        if fade.previous.len() != fade.current.len() {
+            fade.previous.clone_from(&fade.current);
+        }
+        fade.shown = Some(key);
+    }
+
+    let frame_width = slots * side;
+    for y in 0..side {
+        let dest_row = (y * frame_width + slot * side) * 4;
+        let dest_row = &mut frame[dest_row..dest_row + side * 4];
+        let from = &fade.previous[y * side * 4..(y + 1) * side * 4];
+        let to = &fade.current[y * side * 4..(y + 1) * side * 4];
+
+        for ((dest, from), to) in dest_row.chunks_exact_mut(4).zip(from.chunks_exact(4)).zip(to.chunks_exact(4)) {
+            // Mit vormultipliziertem Alpha mischen, sonst werden Kanten dunkel
+            let (from_alpha, to_alpha) = (from[3] as f32 * (1.0 - t), to[3] as f32 * t);
+            let alpha = from_alpha + to_alpha;
+            if alpha <= 0.0 {
+                continue;
+            }
+            let mix = |c: usize| ((from[c] as f32 * from_alpha + to[c] as f32 * to_alpha) / alpha) as u8;
+            let background = [dest[0], dest[1], dest[2], dest[3]];
+            let blended = blend_alpha(background, Rgba([mix(0), mix(1), mix(2), alpha as u8]));
+            dest.copy_from_slice(&blended);
+        }
+    }
+}
+
+/// "Boiling over" for themes without a boiling sheet: bubbles shooting up
+/// through the glass on top of the red lava, faster than it could ever flow.
+pub fn draw_boiling(frame: &mut [u8], slots: usize, slot: usize, side: usize, lamp: &Lamp) {
+    const BUBBLES: usize = 28;
+    /// Glass of the classic lamp in 128er coordinates: from the base up to the cap.
+    const BOTTOM: f64 = 74.0;
+    const TOP: f64 = 12.0;
+    const COLOR: [u8; 3] = [255, 230, 170];
+
+    let frame_width = slots * side;
+    let scale = side as f64 / WINDOW_SIZE as f64;
+    let time = lamp.frame_index as f64 + lamp.accumulator.as_secs_f64() / lamp.step().as_secs_f64();
+    for i in 0..BUBBLES {
+        // Feste Pseudo-Zufallswerte pro Blase, damit sie von Frame zu Frame wiedererkennbar bleibt
+        let seed = |k: u64| {
+            let mut x = (i as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ k.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            x ^= x >> 31;
+            x = x.wrapping_mul(0x94D0_49BB_1331_11EB);
+            (x >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let speed = 1.5 + 2.5 * seed(1);
+        let height = BOTTOM - TOP;
+        let y = BOTTOM - (seed(2) * height + time * speed) % height;
+        // Das Glas wird nach oben schmaler
+        let half_width = 6.0 + (y - TOP) / height * 34.0;
+        let x = 64.0 + (seed(3) * 2.0 - 1.0) * half_width * 0.8 + (time * 0.7 + seed(4) * 6.0).sin() * 2.0;
+        let radius = (1.0 + 2.0 * seed(5)) * (0.6 + 0.4 * (y - TOP) / height);
+
+        let (cx, cy, r) = (x * scale, y * scale, radius * scale);
+        let reach = r.ceil() as i64 + 1;
+        for py in (cy as i64 - reach)..=(cy as i64 + reach) {
+            for px in (cx as i64 - reach)..=(cx as i64 + reach) {
+                if px < 0 || py < 0 || px >= side as i64 || py >= side as i64 {
+                    continue;
+                }
+                let distance = ((px as f64 + 0.5 - cx).powi(2) + (py as f64 + 0.5 - cy).powi(2)).sqrt();
+                let alpha = (r + 0.5 - distance).clamp(0.0, 1.0) * 0.85;
+                if alpha <= 0.0 {
+                    continue;
+                }
+                let i = (py as usize * frame_width + slot * side + px as usize) * 4;
+                let background = [frame[i], frame[i + 1], frame[i + 2], frame[i + 3]];
+                let bubble = Rgba([COLOR[0], COLOR[1], COLOR[2], (alpha * 255.0) as u8]);
+                frame[i..i + 4].copy_from_slice(&blend_alpha(background, bubble));
+            }
+        }
+    }
+}
+
+/// Asleep for themes without a sleeping sheet: the frozen lamp slowly
+/// dims and brightens again, like a sleeping laptop's LED.
+pub fn draw_dozing(frame: &mut [u8], slots: usize, slot: usize, side: usize, lamp: &Lamp) {
+    let phase = lamp.dozed.as_secs_f32() / DOZE_BREATH.as_secs_f32() * std::f32::consts::TAU;
+    let brightness = 0.65 - 0.2 * phase.cos();
+    let frame_width = slots * side;
+    for y in 0..side {
+        let row = (y * frame_width + slot * side) * 4;
+        for pixel in frame[row..row + side * 4].chunks_exact_mut(4) {
+            for channel in &mut pixel[..3] {
+                *channel = (*channel as f32 * brightness) as u8;
+            }
+        }
+    }
+}
+
+/// Stand-in while the sprite sheet is still loading: a dark glass column with a
+/// blob of the tier's color rising and sinking in it.
+pub fn draw_placeholder(frame: &mut [u8], slots: usize, slot: usize, side: usize, lamp: &Lamp) {
+    const GLASS: [u8; 4] = [40, 40, 48, 255];
+    const HALF_WIDTH: f64 = 20.0;
+    const TOP: f64 = 16.0;
+    const BOTTOM: f64 = 112.0;
+    const BLOB_RADIUS: f64 = 16.0;
+
+    let frame_width = slots * side;
+    let x_offset = slot * side;
+    let center_x = WINDOW_SIZE as f64 / 2.0;
+    // Gezeichnet wird in 128er-Koordinaten
+    let scale = WINDOW_SIZE as f64 / side as f64;
+    let phase = lamp.frame_index as f64 / ANIMATION_FRAMES as f64 * std::f64::consts::TAU;
+    let blob_y = (TOP + BOTTOM) / 2.0 + (BOTTOM - TOP - 2.0 * BLOB_RADIUS) / 2.0 * phase.sin();
+    let [r, g, b, _] = lamp.mapping.tier.debug_color();
+
+    for y in 0..side {
+        let fy = (y as f64 + 0.5) * scale;
+        if !(TOP..BOTTOM).contains(&fy) {
+            continue;
+        }
+        for x in 0..side {
+            let fx = (x as f64 + 0.5) * scale;
+            if (fx - center_x).abs() > HALF_WIDTH {
+                continue;
+            }
+            let distance = ((fx - center_x).powi(2) + (fy - blob_y).powi(2)).sqrt();
+            // Weicher Rand: innen volle Farbe, nach außen in das Glas übergehend
+            let glow = (1.0 - (distance - BLOB_RADIUS * 0.6) / (BLOB_RADIUS * 0.4)).clamp(0.0, 1.0);
+            let mix = |glass: u8, color: u8| (glass as f64 + (color as f64 - glass as f64) * glow) as u8;
+
+            let i = (y * frame_width + x_offset + x) * 4;
+            frame[i..i + 4].copy_from_slice(&[mix(GLASS[0], r), mix(GLASS[1], g), mix(GLASS[2], b), 255]);
+        }
+    }
+}
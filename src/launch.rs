@@ -0,0 +1,120 @@
+//! Click action: a click on the lamp opens the system's task manager or a
+//! command of your choice, so the lamp that shows the problem is also the
+//! way to look into it.
+//!
+//! Programs are started in the background and not waited for; the lamp
+//! keeps running whatever they do.
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::process::Command;
+use std::thread;
+
+use crate::i18n::tr;
+
+/// Task managers to try in order; the first one that starts wins.
+#[cfg(windows)]
+const SYSTEM_MONITORS: &[&[&str]] = &[&["taskmgr"]];
+
+#[cfg(target_os = "macos")]
+const SYSTEM_MONITORS: &[&[&str]] = &[&["open", "-a", "Activity Monitor"]];
+
+// Die grafischen der gängigen Desktops, sonst htop oder top im Terminal
+#[cfg(not(any(windows, target_os = "macos")))]
+const SYSTEM_MONITORS: &[&[&str]] = &[
+    &["gnome-system-monitor"],
+    &["plasma-systemmonitor"],
+    &["ksysguard"],
+    &["xfce4-taskmanager"],
+    &["mate-system-monitor"],
+    &["lxtask"],
+    &["x-terminal-emulator", "-e", "htop"],
+    &["xterm", "-e", "top"],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClickAction {
+    None,
+    /// The platform's task manager.
+    SystemMonitor,
+    /// `click.command`.
+    Command,
+}
+
+/// The mouse button that runs the action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClickButton {
+    Right,
+    /// Instead of pausing.
+    Middle,
+}
+
+/// The `[click]` config section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClickConfig {
+    pub action: ClickAction,
+    pub button: ClickButton,
+    /// Run by the shell (`sh -c`, `cmd /C` on Windows) for `action = "command"`.
+    pub command: Option<String>,
+}
+
+impl Default for ClickConfig {
+    fn default() -> Self {
+        ClickConfig { action: ClickAction::SystemMonitor, button: ClickButton::Right, command: None }
+    }
+}
+
+/// Runs the configured action, if any.
+pub fn run(config: &ClickConfig) {
+    match (config.action, &config.command) {
+        (ClickAction::None, _) => {}
+        (ClickAction::SystemMonitor, _) => open_system_monitor(),
+        (ClickAction::Command, Some(command)) => {
+            log::info!("{}", tr!("click-running", command = command));
+            if let Err(e) = spawn(&shell_command(command)) {
+                log::error!("{}", tr!("click-failed", command = command, error = e));
+            }
+        }
+        // Wird beim Laden der Konfiguration abgefangen
+        (ClickAction::Command, None) => {}
+    }
+}
+
+fn open_system_monitor() {
+    for argv in SYSTEM_MONITORS {
+        match spawn(argv) {
+            Ok(()) => {
+                log::info!("{}", tr!("click-running", command = argv.join(" ")));
+                return;
+            }
+            // Nicht installiert: den nächsten versuchen
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                log::error!("{}", tr!("click-failed", command = argv.join(" "), error = e));
+                return;
+            }
+        }
+    }
+    log::error!("{}", tr!("click-no-system-monitor"));
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Vec<&str> {
+    vec!["cmd", "/C", command]
+}
+
+#[cfg(not(windows))]
+fn shell_command(command: &str) -> Vec<&str> {
+    vec!["sh", "-c", command]
+}
+
+/// Starts `argv` and waits for it on a thread of its own, so it doesn't linger as a zombie.
+fn spawn(argv: &[&str]) -> io::Result<()> {
+    let (program, args) = argv.split_first().ok_or(io::ErrorKind::InvalidInput)?;
+    let mut child = Command::new(program).args(args).spawn()?;
+    let _ = thread::Builder::new().name("click".into()).spawn(move || child.wait());
+    Ok(())
+}
@@ -0,0 +1,243 @@
+//! Console output plus an optional log file with size/day based rotation.
+//!
+//! Everything goes through the `log` macros: info and debug go to stdout,
+//! warnings and errors to stderr. When file logging is enabled, the same lines
+//! are appended with a timestamp to `ram-lavalampe.log`, which is rotated to
+//! `ram-lavalampe.log.1`, `.2`, ... when it grows too large or the day changes.
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::i18n::tr;
+use crate::paths;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    /// Write a log file in addition to the console output.
+    pub enabled: bool,
+    /// Log file; defaults to `ram-lavalampe.log` in the state directory.
+    pub path: Option<PathBuf>,
+    /// Rotate once the file exceeds this size.
+    pub max_size_kb: u64,
+    /// Also rotate when the date changes.
+    pub rotate_daily: bool,
+    /// Number of rotated files to keep.
+    pub keep: usize,
+    /// Also log debug messages (e.g. every sample).
+    pub debug: bool,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig {
+            enabled: false,
+            path: None,
+            max_size_kb: 1024,
+            rotate_daily: true,
+            keep: 5,
+            debug: false,
+        }
+    }
+}
+
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    day: u64,
+    max_size: u64,
+    rotate_daily: bool,
+    keep: usize,
+}
+
+impl RotatingFile {
+    fn open(config: &LogConfig, path: PathBuf) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let metadata = file.metadata()?;
+        // Tag der letzten Änderung, damit eine Datei von gestern beim Start rotiert wird
+        let day = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or_else(current_day, |d| d.as_secs() / 86400);
+
+        Ok(RotatingFile {
+            path,
+            file,
+            size: metadata.len(),
+            day,
+            max_size: config.max_size_kb.max(1) * 1024,
+            rotate_daily: config.rotate_daily,
+            keep: config.keep,
+        })
+    }
+
+    fn write_line(&mut self, line: &str) {
+        let needs_rotation = (self.size > 0 && self.size + line.len() as u64 > self.max_size)
+            || (self.rotate_daily && current_day() != self.day);
+        if needs_rotation {
+            if let Err(e) = self.rotate() {
+                eprintln!("{}", tr!("log-rotate-failed", path = self.path.display(), error = e));
+            }
+        }
+
+        if self.file.write_all(line.as_bytes()).is_ok() {
+            self.size += line.len() as u64;
+        }
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", self.path.display(), n));
+
+        if self.keep == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let _ = fs::remove_file(rotated(self.keep));
+            for n in (1..self.keep).rev() {
+                let _ = fs::rename(rotated(n), rotated(n + 1));
+            }
+            fs::rename(&self.path, rotated(1))?;
+        }
+
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.size = 0;
+        self.day = current_day();
+        Ok(())
+    }
+}
+
+struct Logger {
+    level: Mutex<LevelFilter>,
+    file: Mutex<Option<RotatingFile>>,
+    /// Info and debug go to stderr too, see [`use_stderr`].
+    stderr_only: AtomicBool,
+}
+
+static LOGGER: Logger = Logger {
+    level: Mutex::new(LevelFilter::Info),
+    file: Mutex::new(None),
+    stderr_only: AtomicBool::new(false),
+};
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        // Fremde Crates (wgpu, zbus, ...) nur bei echten Fehlern durchlassen
+        if !metadata.target().starts_with(env!("CARGO_CRATE_NAME")) {
+            return metadata.level() <= Level::Error;
+        }
+        metadata.level() <= *self.level.lock().unwrap()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        match record.level() {
+            Level::Error | Level::Warn => eprintln!("{}", record.args()),
+            _ if LOGGER.stderr_only.load(Ordering::Relaxed) => eprintln!("{}", record.args()),
+            _ => println!("{}", record.args()),
+        }
+
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            file.write_line(&format!(
+                "{} {:<5} {}\n",
+                format_timestamp(SystemTime::now()),
+                record.level(),
+                record.args()
+            ));
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+/// Installs the console logger. Call once at startup, before anything is printed.
+pub fn init() {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Debug);
+    }
+}
+
+/// Sends all console output to stderr, for modes that print data on stdout.
+pub fn use_stderr() {
+    LOGGER.stderr_only.store(true, Ordering::Relaxed);
+}
+
+/// Applies the `[log]` config section: level and (re)opening of the log file.
+pub fn configure(config: &LogConfig) {
+    *LOGGER.level.lock().unwrap() = if config.debug { LevelFilter::Debug } else { LevelFilter::Info };
+
+    let mut file = LOGGER.file.lock().unwrap();
+    *file = None;
+    if !config.enabled {
+        return;
+    }
+
+    let Some(path) = config.path.clone().or_else(default_log_path) else { return };
+    match RotatingFile::open(config, path.clone()) {
+        Ok(rotating) => {
+            *file = Some(rotating);
+            drop(file);
+            log::info!("{}", tr!("log-file", path = path.display()));
+        }
+        Err(e) => {
+            drop(file);
+            log::error!("{}", tr!("log-open-failed", path = path.display(), error = e));
+        }
+    }
+}
+
+/// `ram-lavalampe.log` in the state directory, see [`paths`](crate::paths).
+pub fn default_log_path() -> Option<PathBuf> {
+    paths::state_dir().map(|dir| dir.join("ram-lavalampe.log"))
+}
+
+fn current_day() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() / 86400)
+}
+
+/// "YYYY-MM-DD HH:MM:SS" in UTC.
+pub fn format_timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (year, month, day) = civil_from_days((secs / 86400) as i64);
+    let rem = secs % 86400;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+/// Days since 1970-01-01 to (year, month, day), after Howard Hinnant's `civil_from_days`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,116 @@
+//! Lottie (bodymovin JSON) sprite sheets, rendered with rlottie at the size
+//! the lamp is shown at. Needs a build with `--features lottie`.
+//!
+//! A theme either ships one composition per color (`lavalampe_green.json`
+//! etc.) or a single `lavalampe.json` for all of them. The shared one is
+//! recolored per tier: every fill matching the keypath `[assets]
+//! lottie_keypath` (by default everything inside a layer named `lava`) gets
+//! the tier's color.
+//!
+//! Lottie files count frames at their own frame rate (often 60 per second),
+//! while the lamp steps through at most 169 frames at its own pace, so longer
+//! animations are sampled evenly.
+
+use std::path::Path;
+
+use crate::i18n::tr;
+#[cfg(feature = "lottie")]
+use crate::ANIMATION_FRAMES;
+
+/// File name of a composition shared by all colors.
+pub const SHARED_FILE: &str = "lavalampe.json";
+
+#[cfg(feature = "lottie")]
+pub struct Animation {
+    player: Player,
+    /// Frames of the file itself.
+    total: usize,
+    /// Frames the lamp steps through.
+    frames: usize,
+    len: usize,
+}
+
+/// rlottie's animation handle.
+#[cfg(feature = "lottie")]
+struct Player(rlottie::Animation);
+
+// Sheets are loaded on the sprite loader thread and then handed over to the
+// event loop; the handle owns its data and is only ever used by one thread.
+#[cfg(feature = "lottie")]
+unsafe impl Send for Player {}
+
+#[cfg(feature = "lottie")]
+impl Animation {
+    /// Parses `json`; `resources` is where images it references are looked up.
+    pub fn parse(json: Vec<u8>, cache_key: &str, resources: &Path) -> Result<Animation, String> {
+        let len = json.len();
+        let player = rlottie::Animation::from_data(json, cache_key, resources).ok_or_else(|| tr!("lottie-invalid"))?;
+        let total = player.totalframe().max(1);
+        let size = player.size();
+        if size.width == 0 || size.height == 0 {
+            return Err(tr!("lottie-invalid"));
+        }
+        Ok(Animation { player: Player(player), total, frames: total.min(ANIMATION_FRAMES), len })
+    }
+
+    /// Overrides the color of every fill matching `keypath`.
+    pub fn tint(&mut self, keypath: &str, color: [u8; 4]) {
+        let [r, g, b, _] = color.map(|c| c as f64 / 255.0);
+        self.player.0.set_fill_color(keypath, rlottie::Rgb { r, g, b });
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames
+    }
+
+    /// Source size in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Renders `frame` into a `side`×`side` RGBA buffer (straight alpha).
+    pub fn render(&mut self, frame: usize, side: usize) -> Result<Vec<u8>, String> {
+        let mut surface = rlottie::Surface::new(rlottie::Size::new(side, side));
+        self.player.0.render(frame * self.total / self.frames, &mut surface);
+
+        // rlottie liefert vormultipliziertes BGRA
+        Ok(surface
+            .data()
+            .iter()
+            .flat_map(|pixel| {
+                let unmultiply = |c: u8| if pixel.a == 0 { 0 } else { (c as u16 * 255 / pixel.a as u16).min(255) as u8 };
+                [unmultiply(pixel.r), unmultiply(pixel.g), unmultiply(pixel.b), pixel.a]
+            })
+            .collect())
+    }
+}
+
+/// Stand-in without the `lottie` feature: can't be created, so every
+/// Lottie sheet is reported as needing the feature.
+#[cfg(not(feature = "lottie"))]
+pub struct Animation {
+    never: std::convert::Infallible,
+}
+
+#[cfg(not(feature = "lottie"))]
+impl Animation {
+    pub fn parse(_json: Vec<u8>, _cache_key: &str, _resources: &Path) -> Result<Animation, String> {
+        Err(tr!("lottie-not-built"))
+    }
+
+    pub fn tint(&mut self, _keypath: &str, _color: [u8; 4]) {
+        match self.never {}
+    }
+
+    pub fn frame_count(&self) -> usize {
+        match self.never {}
+    }
+
+    pub fn len(&self) -> usize {
+        match self.never {}
+    }
+
+    pub fn render(&mut self, _frame: usize, _side: usize) -> Result<Vec<u8>, String> {
+        match self.never {}
+    }
+}
@@ -1,23 +1,71 @@
-use pixels::{Pixels, SurfaceTexture};
+mod alerts;
+mod app;
+mod aseprite;
+mod assets;
+mod background;
+mod badge;
+mod bench;
+mod capture;
+mod cli;
+mod clock;
+mod config;
+mod crash;
+mod doctor;
+mod effects;
+mod gamepad;
+mod i18n;
+mod indicators;
+mod keys;
+mod lamp;
+mod launch;
+mod logging;
+mod lottie;
+mod menu;
+mod metrics;
+mod onboarding;
+mod overlay;
+mod output;
+mod pack;
+mod palette;
+mod particles;
+mod paths;
+mod plugins;
+mod script;
+mod session;
+mod sprites;
+mod svg;
+#[cfg(test)]
+mod tests;
+mod tier;
+mod touch;
+mod tray;
+mod watch;
+mod watchdog;
+mod widget;
+mod xembed;
+
+use pixels::{Pixels, PixelsBuilder, SurfaceTexture};
 use winit::{
-    dpi::LogicalSize,
-    event::{Event, VirtualKeyCode, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
-    window::WindowBuilder,
+    dpi::{LogicalSize, PhysicalPosition},
+    event_loop::EventLoopBuilder,
+    monitor::MonitorHandle,
+    window::{Window, WindowBuilder},
 };
-use image::{io::Reader as ImageReader, Rgba};
-use sysinfo::{System, SystemExt};
-use std::time::{Duration, Instant};
-use std::path::PathBuf;
+use image::Rgba;
+use app::App;
+use config::{Config, Corner, Layout};
+use metrics::{Metric, Sampler};
+use i18n::tr;
+use std::time::Duration;
 use std::collections::HashSet;
 use std::sync::Mutex;
-use std::env;
 
 const WINDOW_SIZE: usize = 128;
 const ANIMATION_FRAMES: usize = 169;
 
 // --- ÄNDERUNG 1: XLarge hinzugefügt ---
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 enum WindowSizeMode {
     Small,
     Medium,
@@ -26,6 +74,13 @@ enum WindowSizeMode {
 }
 
 impl WindowSizeMode {
+    const ALL: [WindowSizeMode; 4] = [
+        WindowSizeMode::Small,
+        WindowSizeMode::Medium,
+        WindowSizeMode::Large,
+        WindowSizeMode::XLarge,
+    ];
+
     fn get_size(&self) -> usize {
         match self {
             WindowSizeMode::Small => WINDOW_SIZE,
@@ -54,6 +109,14 @@ impl WindowSizeMode {
         }
     }
 
+    /// The next size up, starting over at the smallest after the largest.
+    fn cycle(&self) -> WindowSizeMode {
+        match self {
+            WindowSizeMode::XLarge => WindowSizeMode::Small,
+            size_mode => size_mode.scale_up(),
+        }
+    }
+
     fn description(&self) -> &'static str {
         match self {
             WindowSizeMode::Small => "128x128",
@@ -71,92 +134,104 @@ lazy_static::lazy_static! {
 fn print_once(msg: &str) {
     let mut cache = ALREADY_PRINTED.lock().unwrap();
     if !cache.contains(msg) {
-        println!("{}", msg);
+        log::info!("{}", msg);
         cache.insert(msg.to_string());
     }
 }
 
 
 
-fn find_asset_path(filename: &str) -> Option<PathBuf> {
-    let path = PathBuf::from("assets").join(filename);
-    if path.exists() { return Some(path); }
-    
-    if let Ok(exe_path) = env::current_exe() {
-        if let Some(exe_dir) = exe_path.parent() {
-            let path = exe_dir.join("assets").join(filename);
-            if path.exists() { return Some(path); }
-            
-            if let Some(parent_dir) = exe_dir.parent() {
-                let path = parent_dir.join("assets").join(filename);
-                if path.exists() { return Some(path); }
-                
-                if let Some(grandparent_dir) = parent_dir.parent() {
-                    let path = grandparent_dir.join("assets").join(filename);
-                    if path.exists() { return Some(path); }
-                }
-            }
-        }
-    }
-    
-    if let Some(home_dir) = env::var_os("HOME") {
-        let path = PathBuf::from(home_dir)
-            .join(".local/share/ram-lavalampe/assets")
-            .join(filename);
-        if path.exists() { return Some(path); }
-    }
-    
-    None
+/// Events sent into the winit event loop from other threads (e.g. the tray menu).
+#[derive(Debug, Clone)]
+enum AppEvent {
+    SetSizeMode(WindowSizeMode),
+    SetTheme(Option<String>),
+    SetMetric(Metric),
+    SetLayout(Layout),
+    /// Switch to `metric = "plugin"` with the given plugin.
+    SetPluginMetric(String),
+    SetSamplingInterval(Duration),
+    SetProfile(Option<String>),
+    /// The OOM killer ended a process (name and PID, if known).
+    OomKill(Option<String>),
+    /// A sprite sheet finished loading in the background.
+    SpriteLoaded,
+    /// The config file changed on disk or SIGHUP was received.
+    ReloadConfig,
+    TogglePause,
+    /// Left click on the XEmbed icon: shows a hidden window, else pauses or resumes.
+    TrayClicked,
+    /// Hide the window to the tray, or bring it back.
+    ToggleWindow,
+    /// Switch the CRT filter on or off.
+    ToggleCrt,
+    /// Switch the title bar and border on or off.
+    ToggleBorderless,
+    /// Switch desktop widget mode on or off.
+    ToggleWidget,
+    /// Let clicks through the lamp or catch them again.
+    ToggleClickThrough,
+    /// Take a baseline or drop it.
+    ToggleBaseline,
+    /// Log the current state (`SIGUSR1`).
+    DumpState,
+    /// A gamepad button mapped to a shortcut.
+    #[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+    Shortcut(keys::Action),
+    Quit,
 }
 
-fn load_lava_animation(filename: &str) -> Option<(Vec<Rgba<u8>>, usize, usize)> {
-    let file_path = match find_asset_path(filename) {
-        Some(path) => path,
-        None => {
-            eprintln!(">>> ERROR: Could not find asset file: {}", filename);
-            return None;
-        }
-    };
-    
-    println!(">>> Attempting to load: {}", file_path.display());
-    
-    let img = match ImageReader::open(&file_path) {
-        Ok(reader) => match reader.decode() {
-            Ok(image) => image.to_rgba8(),
-            Err(e) => {
-                eprintln!("    ERROR: Failed to decode {}: {}", file_path.display(), e);
-                return None;
-            }
-        },
-        Err(e) => {
-            eprintln!("    ERROR: Can't open {}: {}", file_path.display(), e);
-            return None;
-        }
-    };
+/// Logical window size for `lamps` lamps side by side. Rows of lamps use half
+/// the size per lamp so they don't cover the whole screen.
+fn window_size(size_mode: WindowSizeMode, lamps: usize) -> LogicalSize<f64> {
+    let side = if lamps > 1 { size_mode.get_size() / 2 } else { size_mode.get_size() };
+    LogicalSize::new((side * lamps) as f64, side as f64)
+}
 
-    let (width, height) = img.dimensions();
-    let width = width as usize;
-    let height = height as usize;
+fn apply_size_mode(window: &Window, size_mode: WindowSizeMode, lamps: usize) {
+    window.set_inner_size(window_size(size_mode, lamps));
+    window.request_redraw();
+}
 
-    if height != WINDOW_SIZE {
-        eprintln!("    ERROR: Wrong height! Got {}, expected {}", height, WINDOW_SIZE);
-        return None;
+/// Moves the window into a corner of the monitor it is currently on.
+fn dock_to_corner(window: &Window, corner: Corner, size_mode: WindowSizeMode, lamps: usize) {
+    if corner == Corner::None {
+        return;
     }
+    let Some(monitor) = window.current_monitor() else { return };
+    dock_on(window, &monitor, corner, size_mode, lamps);
+}
 
-    let expected_width = ANIMATION_FRAMES * WINDOW_SIZE;
-    if width != expected_width {
-        if width % WINDOW_SIZE != 0 {
-            eprintln!("    ERROR: Width {} is not divisible by frame size {}", width, WINDOW_SIZE);
-            return None;
+/// Moves the window into `corner` of `monitor`; with [`Corner::None`] it's only
+/// pushed as far as needed to be fully on that monitor.
+fn dock_on(window: &Window, monitor: &MonitorHandle, corner: Corner, size_mode: WindowSizeMode, lamps: usize) {
+    let origin = monitor.position();
+    let screen = monitor.size();
+    // Die gewünschte Größe verwenden, outer_size() hinkt nach set_inner_size() hinterher
+    let outer = window.outer_size();
+    let inner = window.inner_size();
+    let frame_w = outer.width.saturating_sub(inner.width) as i32;
+    let frame_h = outer.height.saturating_sub(inner.height) as i32;
+    let size = window_size(size_mode, lamps).to_physical::<i32>(window.scale_factor());
+    let width = size.width + frame_w;
+    let height = size.height + frame_h;
+
+    let left = origin.x;
+    let top = origin.y;
+    let right = origin.x + screen.width as i32 - width;
+    let bottom = origin.y + screen.height as i32 - height;
+
+    let (x, y) = match corner {
+        Corner::TopLeft => (left, top),
+        Corner::TopRight => (right, top),
+        Corner::BottomLeft => (left, bottom),
+        Corner::BottomRight => (right, bottom),
+        Corner::None => {
+            let current = window.outer_position().unwrap_or(PhysicalPosition::new(left, top));
+            (current.x.min(right).max(left), current.y.min(bottom).max(top))
         }
-    }
-
-    let mut pixel_data = Vec::with_capacity(width * height);
-    for pixel in img.pixels() {
-        pixel_data.push(*pixel);
-    }
-
-    Some((pixel_data, width, height))
+    };
+    window.set_outer_position(PhysicalPosition::new(x, y));
 }
 
 fn blend_alpha(background: [u8; 4], foreground: Rgba<u8>) -> [u8; 4] {
@@ -169,259 +244,182 @@ fn blend_alpha(background: [u8; 4], foreground: Rgba<u8>) -> [u8; 4] {
     if fg_a == 0 { return background; }
     if fg_a == 255 { return [fg_r, fg_g, fg_b, 255]; }
 
+    // "Über"-Operator: auf transparentem Hintergrund (Widget-Modus) bleibt
+    // die Kante der Lampe halbtransparent, statt schwarz zu werden
     let alpha = fg_a as f32 / 255.0;
-    let inv_alpha = 1.0 - alpha;
+    let bg_alpha = bg_a as f32 / 255.0 * (1.0 - alpha);
+    let out = alpha + bg_alpha;
+    if out == 0.0 { return [0, 0, 0, 0]; }
 
-    let r = (fg_r as f32 * alpha + bg_r as f32 * inv_alpha) as u8;
-    let g = (fg_g as f32 * alpha + bg_g as f32 * inv_alpha) as u8;
-    let b = (fg_b as f32 * alpha + bg_b as f32 * inv_alpha) as u8;
-    let a = ((fg_a as f32 * alpha + bg_a as f32 * inv_alpha).min(255.0)) as u8;
+    let r = ((fg_r as f32 * alpha + bg_r as f32 * bg_alpha) / out) as u8;
+    let g = ((fg_g as f32 * alpha + bg_g as f32 * bg_alpha) / out) as u8;
+    let b = ((fg_b as f32 * alpha + bg_b as f32 * bg_alpha) / out) as u8;
+    let a = (out * 255.0).round() as u8;
 
     [r, g, b, a]
 }
 
+/// Window system and GPU adapter, for crash reports.
+fn backend_info(window: &Window, pixels: &Pixels) -> String {
+    #[cfg(target_os = "linux")]
+    let window_system = {
+        use winit::platform::wayland::WindowExtWayland;
+        if window.wayland_surface().is_some() { "Wayland" } else { "X11" }
+    };
+    #[cfg(not(target_os = "linux"))]
+    let window_system = {
+        let _ = window;
+        std::env::consts::OS
+    };
+
+    let adapter = pixels.adapter().get_info();
+    format!(
+        "{}, {:?} on {} (driver: {} {})",
+        window_system, adapter.backend, adapter.name, adapter.driver, adapter.driver_info
+    )
+}
+
+/// The renderer for `window` with a `width`×`height` frame buffer. `software`
+/// asks wgpu for its fallback adapter (WARP on Windows, llvmpipe/lavapipe on Linux).
+/// `transparent` clears around the frame with nothing instead of black.
+fn build_pixels(
+    window: &Window,
+    width: u32,
+    height: u32,
+    vsync: bool,
+    software: bool,
+    transparent: bool,
+) -> Result<Pixels, pixels::Error> {
+    let window_size = window.inner_size();
+    let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, window);
+    let mut builder = PixelsBuilder::new(width, height, surface_texture).enable_vsync(vsync);
+    if transparent {
+        builder = builder.clear_color(pixels::wgpu::Color::TRANSPARENT);
+    }
+    if software {
+        builder = builder.request_adapter_options(pixels::wgpu::RequestAdapterOptions {
+            force_fallback_adapter: true,
+            ..Default::default()
+        });
+    }
+    builder.build()
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    println!("Starting RAM Lava Lamp...");
-    println!("Expected frame count: {}", ANIMATION_FRAMES);
-    println!("Controls: Ctrl + Up Arrow = Scale Up, Ctrl + Down Arrow = Scale Down, Esc = Exit");
+    logging::init();
+    let args = cli::Args::parse();
+    assets::set_flag_dirs(args.assets_dirs.clone());
+    if let Some(command) = &args.command {
+        let mut config = Config::load().ok().flatten().unwrap_or_default();
+        config.apply_env_overrides();
+        args.apply(&mut config);
+        if let Some(language) = &config.language {
+            i18n::set_language(language);
+        }
+        match command {
+            cli::Command::Bench { frames } => bench::run(&config, *frames),
+            cli::Command::Doctor => doctor::run(&config),
+            cli::Command::Output { format } => output::run(&config, *format),
+            cli::Command::Status { format } => output::status(&config, *format),
+            cli::Command::ExtractAssets { dir } => {
+                if let Err(e) = assets::extract(dir) {
+                    eprintln!("{}", tr!("extract-failed", dir = dir.display(), error = e));
+                    std::process::exit(1);
+                }
+            }
+            cli::Command::Pack { dir, output, size } => match pack::run(dir, output, *size) {
+                Ok(sidecar) => println!("{}", tr!("pack-written", path = output.display(), sidecar = sidecar.display())),
+                Err(e) => {
+                    eprintln!("{}", tr!("pack-failed", error = e));
+                    std::process::exit(1);
+                }
+            },
+        }
+        return Ok(());
+    }
+    crash::install();
+    log::info!("{}", tr!("starting"));
+    log::info!("{}", tr!("expected-frames", count = ANIMATION_FRAMES));
+    log::info!("{}", tr!("controls"));
+
+    // Nur ohne Config-Datei einrichten; eine unlesbare wird nicht überschrieben
+    let mut config = match Config::load() {
+        Ok(Some(config)) => config,
+        Ok(None) => onboarding::run(),
+        Err(_) => {
+            log::error!("    {}", tr!("config-read-defaults"));
+            Config::default()
+        }
+    };
+    config.apply_env_overrides();
+    args.apply(&mut config);
+    if let Some(language) = &config.language {
+        i18n::set_language(language);
+    }
+    logging::configure(&config.log);
+    crash::set_config(&toml::to_string_pretty(&config).unwrap_or_default());
 
-    let mut system = System::new_all();
+    // Das aktive Profil kann Größe und Ecke überschreiben
+    let startup = config.with_profile(config.profile.as_deref());
 
-    let event_loop = EventLoop::new();
+    let event_loop = EventLoopBuilder::<AppEvent>::with_user_event().build();
     let window = {
         let size = LogicalSize::new(WINDOW_SIZE as f64, WINDOW_SIZE as f64);
         WindowBuilder::new()
             .with_title("RAM Lava Lamp")
             .with_inner_size(size)
-            .with_min_inner_size(LogicalSize::new(WINDOW_SIZE as f64, WINDOW_SIZE as f64))
+            .with_min_inner_size(LogicalSize::new(WINDOW_SIZE as f64 / 2.0, WINDOW_SIZE as f64 / 2.0))
             // --- ÄNDERUNG 3: Max Size entfernt und Decorations auf true ---
             // .with_max_inner_size wurde entfernt!
             .with_resizable(true)
-            .with_decorations(true) // Setze dies auf true, damit der Window Manager besser mitarbeitet
+            .with_decorations(!startup.borderless) // Mit Rahmen arbeitet der Window Manager besser mit
+            // Lässt sich nachträglich nicht mehr ändern, siehe App::transparent_window
+            .with_transparent(startup.background.transparent)
             .build(&event_loop)?
     };
 
-    let mut pixels = {
-        let window_size = window.inner_size();
-        let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, &window);
-        Pixels::new(WINDOW_SIZE as u32, WINDOW_SIZE as u32, surface_texture)?
+    apply_size_mode(&window, startup.size, 1);
+    dock_to_corner(&window, startup.corner, startup.size, 1);
+    if startup.exclude_from_capture {
+        capture::set_excluded(&window, true);
+    }
+    widget::apply(&window, &startup);
+
+    let renderer = &startup.renderer;
+    let mut software = renderer.software;
+    let transparent = startup.background.transparent;
+    let pixels = match build_pixels(&window, WINDOW_SIZE as u32, WINDOW_SIZE as u32, startup.vsync, software, transparent) {
+        Err(e) if renderer.software_fallback && !software => {
+            log::warn!("{}", tr!("renderer-software-fallback", error = e));
+            software = true;
+            build_pixels(&window, WINDOW_SIZE as u32, WINDOW_SIZE as u32, startup.vsync, true, transparent)?
+        }
+        built => built?,
     };
+    crash::set_backend_info(backend_info(&window, &pixels));
+
+    print_once(&tr!("monitoring-started"));
+
+    let mut app = App::new(window, pixels, Sampler::new(), config);
+    app.args = args;
+    app.watchdog = watchdog::Watchdog::new(software);
+    app.sprites = lamp::SpriteCache::in_background(event_loop.create_proxy());
+    app.tray = tray::spawn(tray::LampTray::new(
+        event_loop.create_proxy(),
+        &app.config,
+        assets::list_themes(&app.config.assets.dirs),
+        app.sampler.plugin_names(),
+        app.base_config.profiles.keys().cloned().collect(),
+        app.profile.clone(),
+    ));
+    if app.tray.is_none() {
+        app.xembed = xembed::spawn(event_loop.create_proxy());
+    }
+    watch::spawn(event_loop.create_proxy());
+    alerts::spawn_oom_watcher(event_loop.create_proxy());
+    if app.config.gamepad.enabled {
+        gamepad::spawn(event_loop.create_proxy());
+    }
 
-    let mut current_size_mode = WindowSizeMode::Small;
-    let mut ctrl_pressed = false;
-
-    let mut current_animation: Option<(Vec<Rgba<u8>>, usize, usize)> = None;
-    let mut current_sprite_file = "";
-    let mut frame_index = 0;
-    let mut last_update = Instant::now();
-    let mut last_ram_check = Instant::now();
-    let mut current_ram_percent = 0.0;
-
-    system.refresh_memory();
-    print_once("RAM monitoring started");
-
-    event_loop.run(move |event, _, control_flow| {
-        match event {
-            Event::WindowEvent {
-                event: WindowEvent::CloseRequested,
-                ..
-            } => {
-                println!("Shutting down...");
-                *control_flow = ControlFlow::Exit;
-            }
-            Event::WindowEvent {
-                event: WindowEvent::KeyboardInput { input, .. },
-                ..
-            } => {
-                if let Some(keycode) = input.virtual_keycode {
-                    match input.state {
-                        winit::event::ElementState::Pressed => {
-                            match keycode {
-                                VirtualKeyCode::LControl | VirtualKeyCode::RControl => {
-                                    ctrl_pressed = true;
-                                }
-                                VirtualKeyCode::Escape => {
-                                    println!("Shutting down...");
-                                    *control_flow = ControlFlow::Exit;
-                                }
-                                VirtualKeyCode::Up => {
-                                    if ctrl_pressed {
-                                        let old_size_mode = current_size_mode;
-                                        current_size_mode = current_size_mode.scale_up();
-                                        if current_size_mode != old_size_mode {
-                                            let size = current_size_mode.get_size();
-                                            println!("Scaling window up to {}", current_size_mode.description());
-                                            let new_size = LogicalSize::new(size as f64, size as f64);
-                                            window.set_inner_size(new_size);
-                                            window.request_redraw();
-                                        }
-                                    }
-                                }
-                                VirtualKeyCode::Down => {
-                                    if ctrl_pressed {
-                                        let old_size_mode = current_size_mode;
-                                        current_size_mode = current_size_mode.scale_down();
-                                        if current_size_mode != old_size_mode {
-                                            let size = current_size_mode.get_size();
-                                            println!("Scaling window down to {}", current_size_mode.description());
-                                            let new_size = LogicalSize::new(size as f64, size as f64);
-                                            window.set_inner_size(new_size);
-                                            window.request_redraw();
-                                        }
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-                        winit::event::ElementState::Released => {
-                            match keycode {
-                                VirtualKeyCode::LControl | VirtualKeyCode::RControl => {
-                                    ctrl_pressed = false;
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-                }
-            }
-            Event::WindowEvent {
-                event: WindowEvent::Resized(physical_size),
-                ..
-            } => {
-                // Nur loggen, wenn sich wirklich was ändert, um Spam zu vermeiden
-                // println!("Window resized to: {}x{}", physical_size.width, physical_size.height);
-                if let Err(e) = pixels.resize_surface(physical_size.width, physical_size.height) {
-                    eprintln!("Failed to resize surface: {}", e);
-                }
-            }
-            Event::RedrawRequested(_) => {
-                if last_ram_check.elapsed() >= Duration::from_secs(1) {
-                    system.refresh_memory();
-
-                    let total_ram = system.total_memory();
-                    let used_ram = system.used_memory();
-                    current_ram_percent = if total_ram > 0 {
-                        (used_ram as f64 / total_ram as f64) * 100.0
-                    } else {
-                        0.0
-                    };
-
-                    last_ram_check = Instant::now();
-                }
-
-                let animation_speed = if current_ram_percent <= 30.0 {
-                    Duration::from_millis(200)
-                } else if current_ram_percent <= 50.0 {
-                    Duration::from_millis(150)
-                } else if current_ram_percent <= 80.0 {
-                    Duration::from_millis(100)
-                } else {
-                    Duration::from_millis(60)
-                };
-
-                let (sprite_file, color_name) = match current_ram_percent {
-                    p if p <= 30.0 => ("lavalampe_green.png", "Green"),
-                    p if p <= 50.0 => ("lavalampe_yellow.png", "Yellow"),
-                    p if p <= 80.0 => ("lavalampe_orange.png", "Orange"),
-                    _ => ("lavalampe_red.png", "Red"),
-                };
-
-                if current_sprite_file != sprite_file {
-                    println!("=== Switching to {} lava ({:.1}% RAM used) ===", color_name, current_ram_percent);
-
-                    match load_lava_animation(sprite_file) {
-                        Some(new_anim) => {
-                            println!("✓ Successfully loaded {}", sprite_file);
-                            current_animation = Some(new_anim);
-                            current_sprite_file = sprite_file;
-                            frame_index = 0;
-                        }
-                        None => {
-                            eprintln!("✗ Failed to load {}", sprite_file);
-                            if sprite_file != "lavalampe_green.png" {
-                                println!("Trying green as fallback...");
-                                if let Some(fallback) = load_lava_animation("lavalampe_green.png") {
-                                    println!("✓ Fallback to green successful");
-                                    current_animation = Some(fallback);
-                                    current_sprite_file = "lavalampe_green.png";
-                                    frame_index = 0;
-                                } else {
-                                    current_animation = None;
-                                    current_sprite_file = "";
-                                }
-                            } else {
-                                current_animation = None;
-                                current_sprite_file = "";
-                            }
-                        }
-                    }
-                }
-
-                let frame = pixels.frame_mut();
-
-                // Clear background
-                for pixel in frame.chunks_exact_mut(4) {
-                    pixel[0] = 0; pixel[1] = 0; pixel[2] = 0; pixel[3] = 255;
-                }
-
-                // Debug pattern if no animation
-                if current_animation.is_none() {
-                    let color = match current_ram_percent {
-                        p if p <= 30.0 => [0, 255, 0, 255],
-                        p if p <= 50.0 => [255, 255, 0, 255],
-                        p if p <= 80.0 => [255, 165, 0, 255],
-                        _ => [255, 0, 0, 255],
-                    };
-                    for pixel in frame.chunks_exact_mut(4) {
-                        pixel.copy_from_slice(&color);
-                    }
-                }
-
-                // Render animation
-                if let Some((sprite_data, sprite_width, _)) = &current_animation {
-                    let frames_available = *sprite_width / WINDOW_SIZE;
-                    if frames_available > 0 && (*sprite_width % WINDOW_SIZE == 0) {
-                        let actual_frame_count = frames_available.min(ANIMATION_FRAMES);
-
-                        if last_update.elapsed() >= animation_speed {
-                            frame_index = (frame_index + 1) % actual_frame_count;
-                            last_update = Instant::now();
-                        }
-
-                        let frame_x_start = frame_index * WINDOW_SIZE;
-
-                        for y in 0..WINDOW_SIZE {
-                            for x in 0..WINDOW_SIZE {
-                                let source_x = frame_x_start + x;
-                                let source_index = (y * *sprite_width) + source_x;
-                                let dest_index = (y * WINDOW_SIZE + x) * 4;
-
-                                if source_index < sprite_data.len() {
-                                    let source_pixel = sprite_data[source_index];
-                                    let background = [
-                                        frame[dest_index], frame[dest_index + 1],
-                                        frame[dest_index + 2], frame[dest_index + 3]
-                                    ];
-                                    let blended = blend_alpha(background, source_pixel);
-                                    frame[dest_index] = blended[0];
-                                    frame[dest_index + 1] = blended[1];
-                                    frame[dest_index + 2] = blended[2];
-                                    frame[dest_index + 3] = blended[3];
-                                }
-                            }
-                        }
-                    }
-                }
-
-                if let Err(e) = pixels.render() {
-                    eprintln!("pixels.render() failed: {}", e);
-                    *control_flow = ControlFlow::Exit;
-                }
-            }
-            Event::MainEventsCleared => {
-                window.request_redraw();
-            }
-            _ => {}
-        }
-    });
-}
\ No newline at end of file
+    event_loop.run(move |event, _, control_flow| app.handle_event(event, control_flow));
+}
@@ -0,0 +1,93 @@
+//! The lamp's own context menu, opened with a long press on a touchscreen.
+//! Tablets have no right mouse button and often no tray to reach the
+//! settings, so the menu offers the toggles of the tray menu right in the
+//! window: one entry per line, drawn over the lamps with the overlay font like
+//! a toast. Tapping (or clicking) an entry runs it, tapping anywhere else
+//! closes the menu.
+
+use crate::overlay::{self, GLYPH_HEIGHT};
+use crate::{AppEvent, WINDOW_SIZE};
+
+/// One line of the menu.
+pub struct Entry {
+    pub label: String,
+    /// Shown as `[X]` or `[ ]` in front of the label, for toggles.
+    pub checked: Option<bool>,
+    pub event: AppEvent,
+}
+
+impl Entry {
+    fn text(&self) -> String {
+        match self.checked {
+            Some(true) => format!("[X] {}", self.label),
+            Some(false) => format!("[ ] {}", self.label),
+            None => format!("    {}", self.label),
+        }
+    }
+}
+
+pub struct ContextMenu {
+    entries: Vec<Entry>,
+}
+
+/// Where the menu sits in a frame, in pixels.
+struct Placement {
+    scale: usize,
+    x: usize,
+    y: usize,
+    box_width: usize,
+    box_height: usize,
+    padding: usize,
+    line_height: usize,
+}
+
+impl ContextMenu {
+    pub fn new(entries: Vec<Entry>) -> ContextMenu {
+        ContextMenu { entries }
+    }
+
+    /// Draws the menu centered in a frame `width`×`height` pixels large.
+    pub fn draw(&self, frame: &mut [u8], width: usize, height: usize) {
+        let at = self.placement(width, height);
+        overlay::dim_box(frame, width, at.x, at.y, at.box_width, at.box_height);
+        for (i, entry) in self.entries.iter().enumerate() {
+            let y = at.y + at.padding + i * at.line_height;
+            overlay::draw_text(frame, width, at.x + at.padding, y, at.scale, &entry.text(), [255, 255, 255]);
+        }
+    }
+
+    /// What the entry at (`x`, `y`) in the frame does; `None` outside the entries.
+    pub fn event_at(&self, x: usize, y: usize, width: usize, height: usize) -> Option<AppEvent> {
+        let at = self.placement(width, height);
+        if x < at.x || x >= at.x + at.box_width || y < at.y || y >= at.y + at.box_height {
+            return None;
+        }
+        // Der Rand und der Zwischenraum unter einer Zeile gehören noch zu ihr
+        let index = ((y - at.y).saturating_sub(at.padding) / at.line_height).min(self.entries.len().saturating_sub(1));
+        self.entries.get(index).map(|entry| entry.event.clone())
+    }
+
+    fn placement(&self, width: usize, height: usize) -> Placement {
+        let line_width =
+            |scale: usize| self.entries.iter().map(|entry| overlay::text_width(&entry.text(), scale)).max().unwrap_or(0);
+        let lines = self.entries.len();
+        // Groß genug für den Finger, aber alles muss ins Fenster passen
+        let mut scale = (height / WINDOW_SIZE).max(1) * 2;
+        while scale > 1 && (line_width(scale) + 4 * scale > width || lines * (GLYPH_HEIGHT + 4) * scale + 4 * scale > height) {
+            scale -= 1;
+        }
+        let padding = 2 * scale;
+        let line_height = (GLYPH_HEIGHT + 4) * scale;
+        let box_width = (line_width(scale) + 2 * padding).min(width);
+        let box_height = (lines * line_height + 2 * padding - 4 * scale).min(height);
+        Placement {
+            scale,
+            x: (width - box_width) / 2,
+            y: (height - box_height) / 2,
+            box_width,
+            box_height,
+            padding,
+            line_height,
+        }
+    }
+}
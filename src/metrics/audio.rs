@@ -0,0 +1,113 @@
+//! Microphone/loopback level for the audio-reactive mode (`--features audio`).
+//!
+//! The cpal stream lives on its own thread (it is not `Send` on every
+//! platform); the callback keeps the loudest RMS level since the last read, so
+//! short beats still show up with long sampling intervals.
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{SampleFormat, SizedSample};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::i18n::tr;
+
+pub struct AudioInput {
+    pub device: Option<String>,
+    peak: Arc<Mutex<f32>>,
+    // Beim Droppen endet der Stream-Thread
+    _stop: mpsc::Sender<()>,
+}
+
+impl AudioInput {
+    /// Opens `device` (see `arecord -L` / `pactl list short sources`) or the default input.
+    pub fn start(device: Option<String>) -> Result<AudioInput, String> {
+        let peak = Arc::new(Mutex::new(0.0_f32));
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<(), String>>();
+
+        let shared = Arc::clone(&peak);
+        let wanted = device.clone();
+        thread::Builder::new()
+            .name("audio-input".into())
+            .spawn(move || match open_stream(wanted.as_deref(), shared) {
+                Ok(stream) => {
+                    let _ = ready_tx.send(Ok(()));
+                    let _ = stop_rx.recv();
+                    drop(stream);
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                }
+            })
+            .map_err(|e| e.to_string())?;
+
+        ready_rx.recv().map_err(|e| e.to_string())??;
+        Ok(AudioInput { device, peak, _stop: stop_tx })
+    }
+
+    /// Loudest level since the last call in dBFS (0 = full scale).
+    pub fn take_level_db(&self) -> f64 {
+        let rms = std::mem::take(&mut *self.peak.lock().unwrap());
+        20.0 * f64::from(rms.max(1e-6)).log10()
+    }
+}
+
+fn open_stream(device: Option<&str>, peak: Arc<Mutex<f32>>) -> Result<cpal::Stream, String> {
+    let host = cpal::default_host();
+    let device = match device {
+        Some(name) => host
+            .input_devices()
+            .map_err(|e| e.to_string())?
+            .find(|d| d.name().is_ok_and(|n| n == name))
+            .ok_or_else(|| tr!("audio-device-not-found", device = name))?,
+        None => host.default_input_device().ok_or_else(|| tr!("audio-no-device"))?,
+    };
+    let config = device.default_input_config().map_err(|e| e.to_string())?;
+    log::info!(
+        "{}",
+        tr!("audio-opened", device = device.name().unwrap_or_default(), rate = config.sample_rate().0)
+    );
+
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => build::<f32>(&device, &config.into(), peak),
+        SampleFormat::I16 => build::<i16>(&device, &config.into(), peak),
+        SampleFormat::U16 => build::<u16>(&device, &config.into(), peak),
+        SampleFormat::I32 => build::<i32>(&device, &config.into(), peak),
+        format => return Err(tr!("audio-unsupported-format", format = format)),
+    }
+    .map_err(|e| e.to_string())?;
+    stream.play().map_err(|e| e.to_string())?;
+    Ok(stream)
+}
+
+fn build<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    peak: Arc<Mutex<f32>>,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: SizedSample,
+    f32: cpal::FromSample<T>,
+{
+    device.build_input_stream(
+        config,
+        move |data: &[T], _| {
+            if data.is_empty() {
+                return;
+            }
+            let sum: f32 = data
+                .iter()
+                .map(|s| {
+                    let v: f32 = s.to_sample();
+                    v * v
+                })
+                .sum();
+            let rms = (sum / data.len() as f32).sqrt();
+            let mut peak = peak.lock().unwrap();
+            *peak = peak.max(rms);
+        },
+        |e| log::error!("{}", tr!("audio-stream-error", error = e)),
+        None,
+    )
+}
@@ -0,0 +1,51 @@
+//! RAM split into used, cache and free from `/proc/meminfo` (Linux), for the
+//! stacked fill. A single percentage hides that much of the "used" memory is
+//! page cache the kernel drops as soon as an application wants the space.
+
+use std::fs;
+
+use crate::i18n::tr;
+
+use super::zfs;
+
+/// Shares of the total RAM in percent; free is what's left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Breakdown {
+    /// Taken by applications and the kernel.
+    pub used: f64,
+    /// Page cache, buffers and reclaimable kernel caches (plus the ZFS ARC above its minimum).
+    pub cache: f64,
+}
+
+impl Breakdown {
+    /// With `exclude_zfs_arc`, the reclaimable part of the ARC counts as cache like it does for the RAM metric.
+    pub fn read(exclude_zfs_arc: bool) -> Result<Breakdown, String> {
+        let meminfo = fs::read_to_string("/proc/meminfo").map_err(|e| tr!("breakdown-read-failed", error = e))?;
+        let value = |name: &str| {
+            meminfo.lines().find_map(|line| {
+                let rest = line.strip_prefix(name)?.strip_prefix(':')?;
+                rest.split_whitespace().next()?.parse::<u64>().ok()
+            })
+        };
+        let missing = || tr!("breakdown-read-failed", error = "/proc/meminfo");
+        let total = value("MemTotal").ok_or_else(missing)?;
+        let free = value("MemFree").ok_or_else(missing)?;
+        // Shmem steht in Cached, lässt sich aber nicht einfach freigeben
+        let mut cache = (value("Buffers").ok_or_else(missing)?
+            + value("Cached").ok_or_else(missing)?
+            + value("SReclaimable").unwrap_or(0))
+        .saturating_sub(value("Shmem").unwrap_or(0));
+        if exclude_zfs_arc {
+            if let Some(arc) = zfs::ArcStats::read() {
+                // kB wie in /proc/meminfo
+                cache += arc.reclaimable() / 1024;
+            }
+        }
+        let cache = cache.min(total.saturating_sub(free));
+        let used = total.saturating_sub(free + cache);
+        Ok(Breakdown {
+            used: super::percent(used as f64, total as f64),
+            cache: super::percent(cache as f64, total as f64),
+        })
+    }
+}
@@ -0,0 +1,80 @@
+//! CPU clock and thermal throttling from `/sys/devices/system/cpu/cpu*/` (Linux).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+
+const CPU_DIR: &str = "/sys/devices/system/cpu";
+
+fn cpu_dirs() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(CPU_DIR) else { return Vec::new() };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.strip_prefix("cpu").is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        })
+        .map(|entry| entry.path())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Average current clock relative to `base_frequency` (Intel) or the maximum boost clock.
+pub fn frequency_percent(relative_to_base: bool) -> Result<f64, String> {
+    let mut current = 0.0;
+    let mut reference = 0.0;
+    for dir in cpu_dirs() {
+        let cpufreq = dir.join("cpufreq");
+        let Some(cur) = read_u64(&cpufreq.join("scaling_cur_freq")) else { continue };
+        let base = relative_to_base.then(|| read_u64(&cpufreq.join("base_frequency"))).flatten();
+        let Some(max) = base.or_else(|| read_u64(&cpufreq.join("cpuinfo_max_freq"))) else { continue };
+        current += cur as f64;
+        reference += max as f64;
+    }
+
+    if reference == 0.0 {
+        return Err(tr!("cpufreq-unavailable"));
+    }
+    Ok((current / reference * 100.0).min(100.0))
+}
+
+/// Counts thermal throttling events per core (`thermal_throttle/*_throttle_count`, Intel).
+pub struct ThrottleCounter {
+    last: Option<Vec<u64>>,
+}
+
+impl ThrottleCounter {
+    pub fn new() -> ThrottleCounter {
+        ThrottleCounter { last: None }
+    }
+
+    /// Share of cores that throttled since the previous call.
+    pub fn percent(&mut self) -> Result<f64, String> {
+        let counts: Vec<u64> = cpu_dirs()
+            .iter()
+            .filter_map(|dir| {
+                let throttle = dir.join("thermal_throttle");
+                let core = read_u64(&throttle.join("core_throttle_count"))?;
+                Some(core + read_u64(&throttle.join("package_throttle_count")).unwrap_or(0))
+            })
+            .collect();
+        if counts.is_empty() {
+            return Err(tr!("throttle-unavailable"));
+        }
+
+        let throttled = match &self.last {
+            Some(last) if last.len() == counts.len() => counts.iter().zip(last).filter(|(now, before)| now > before).count(),
+            _ => 0,
+        };
+        let percent = throttled as f64 / counts.len() as f64 * 100.0;
+        self.last = Some(counts);
+        Ok(percent)
+    }
+}
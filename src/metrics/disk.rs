@@ -0,0 +1,75 @@
+//! Read + write throughput of a block device.
+//!
+//! sysinfo 0.29 only has per-process disk counters, so the per-device numbers
+//! come straight from `/proc/diskstats` (Linux only), read on every sample.
+
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use crate::i18n::tr;
+
+/// `/proc/diskstats` counts in 512-byte sectors regardless of the device.
+const SECTOR_SIZE: u64 = 512;
+
+pub struct DiskCounter {
+    pub device: Option<String>,
+    last: Option<(Instant, u64)>,
+}
+
+impl DiskCounter {
+    /// `device` is a kernel name like `nvme0n1` or `sda`; `None` sums up all disks.
+    pub fn new(device: Option<String>) -> DiskCounter {
+        DiskCounter { device, last: None }
+    }
+
+    /// Bytes per second since the previous call (0 on the first one).
+    pub fn rate(&mut self) -> Result<f64, String> {
+        let bytes = total_bytes(self.device.as_deref())?;
+        let now = Instant::now();
+
+        let rate = match self.last {
+            Some((then, previous)) => {
+                let secs = now.duration_since(then).as_secs_f64();
+                if secs > 0.0 {
+                    bytes.saturating_sub(previous) as f64 / secs
+                } else {
+                    0.0
+                }
+            }
+            None => 0.0,
+        };
+        self.last = Some((now, bytes));
+        Ok(rate)
+    }
+}
+
+fn total_bytes(device: Option<&str>) -> Result<u64, String> {
+    let stats = fs::read_to_string("/proc/diskstats").map_err(|e| tr!("disk-stats-failed", error = e))?;
+
+    let mut found = false;
+    let mut sectors = 0;
+    for line in stats.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        // major minor name reads merged sectors_read ms writes merged sectors_written ...
+        if fields.len() < 10 {
+            continue;
+        }
+        let name = fields[2];
+        let wanted = match device {
+            Some(device) => name == device,
+            // Nur ganze Platten, sonst zählen Partitionen doppelt; loop/ram/zram sind keine Platten
+            None => Path::new("/sys/block").join(name).join("device").exists(),
+        };
+        if wanted {
+            found = true;
+            sectors += fields[5].parse::<u64>().unwrap_or(0) + fields[9].parse::<u64>().unwrap_or(0);
+        }
+    }
+
+    match (found, device) {
+        (true, _) => Ok(sectors * SECTOR_SIZE),
+        (false, Some(device)) => Err(tr!("disk-not-found", device = device)),
+        (false, None) => Err(tr!("disk-none")),
+    }
+}
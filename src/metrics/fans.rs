@@ -0,0 +1,67 @@
+//! Fan speeds from the hwmon sysfs interface (`/sys/class/hwmon/hwmon*/fan*_input`).
+//! sysinfo 0.29 only exposes temperatures, not fans.
+
+use std::fs;
+use std::path::Path;
+
+use crate::i18n::tr;
+
+const HWMON_DIR: &str = "/sys/class/hwmon";
+
+struct Fan {
+    /// `<chip>/<fanN>`, e.g. `thinkpad/fan1`.
+    id: String,
+    /// `fanN_label` if the driver provides one, e.g. `CPU Fan`.
+    label: Option<String>,
+    rpm: f64,
+}
+
+/// RPM of the fan matching `wanted` (id or label, case-insensitive), or of the fastest fan.
+pub fn rpm(wanted: Option<&str>) -> Result<f64, String> {
+    let fans = read_fans();
+    if fans.is_empty() {
+        return Err(tr!("fans-none"));
+    }
+
+    match wanted {
+        Some(wanted) => fans
+            .iter()
+            .find(|fan| {
+                fan.id.eq_ignore_ascii_case(wanted) || fan.label.as_deref().is_some_and(|l| l.eq_ignore_ascii_case(wanted))
+            })
+            .map(|fan| fan.rpm)
+            .ok_or_else(|| {
+                let available = fans.iter().map(|fan| fan.id.as_str()).collect::<Vec<_>>().join(", ");
+                tr!("fans-not-found", fan = wanted, available = available)
+            }),
+        None => Ok(fans.iter().map(|fan| fan.rpm).fold(0.0, f64::max)),
+    }
+}
+
+fn read_fans() -> Vec<Fan> {
+    let Ok(chips) = fs::read_dir(HWMON_DIR) else { return Vec::new() };
+    let mut fans = Vec::new();
+
+    for chip in chips.flatten() {
+        let chip_dir = chip.path();
+        let chip_name = read_trimmed(&chip_dir.join("name")).unwrap_or_else(|| chip.file_name().to_string_lossy().into());
+        let Ok(files) = fs::read_dir(&chip_dir) else { continue };
+
+        for file in files.flatten() {
+            let file_name = file.file_name().to_string_lossy().into_owned();
+            let Some(fan) = file_name.strip_suffix("_input").filter(|f| f.starts_with("fan")) else { continue };
+            let Some(rpm) = read_trimmed(&file.path()).and_then(|v| v.parse::<f64>().ok()) else { continue };
+            fans.push(Fan {
+                id: format!("{}/{}", chip_name, fan),
+                label: read_trimmed(&chip_dir.join(format!("{}_label", fan))),
+                rpm,
+            });
+        }
+    }
+    fans.sort_by(|a, b| a.id.cmp(&b.id));
+    fans
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|text| text.trim().to_string())
+}
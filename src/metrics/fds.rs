@@ -0,0 +1,59 @@
+//! Open file descriptors versus their limit, system wide (`/proc/sys/fs/file-nr`)
+//! or for one process (`/proc/<pid>/fd` against its `RLIMIT_NOFILE`).
+
+use std::fs;
+
+use crate::i18n::tr;
+
+/// Allocated file handles relative to `fs.file-max`.
+pub fn system_percent() -> Result<f64, String> {
+    let text = fs::read_to_string("/proc/sys/fs/file-nr").map_err(|e| tr!("fds-read-failed", error = e))?;
+    // "allocated  unused  max"
+    let fields: Vec<u64> = text.split_whitespace().filter_map(|f| f.parse().ok()).collect();
+    match fields.as_slice() {
+        [allocated, _, max] => Ok(super::percent(*allocated as f64, *max as f64)),
+        _ => Err(tr!("fds-read-failed", error = "/proc/sys/fs/file-nr")),
+    }
+}
+
+/// Highest fd usage of all processes matching `process` (a PID or a name as in
+/// `/proc/<pid>/comm`), relative to each one's soft limit.
+pub fn process_percent(process: &str) -> Result<f64, String> {
+    let pids = find_pids(process);
+    if pids.is_empty() {
+        return Err(tr!("process-not-found", process = process));
+    }
+
+    pids.iter()
+        .filter_map(|pid| {
+            // Fremde Prozesse sind ohne Rechte nicht lesbar und werden übersprungen
+            let open = fs::read_dir(format!("/proc/{}/fd", pid)).ok()?.count();
+            let limit = soft_limit(*pid)?;
+            Some(super::percent(open as f64, limit as f64))
+        })
+        .reduce(f64::max)
+        .ok_or_else(|| tr!("fds-no-permission", process = process))
+}
+
+/// PIDs of `process`, given as a PID or as a process name.
+pub fn find_pids(process: &str) -> Vec<u32> {
+    if let Ok(pid) = process.parse::<u32>() {
+        return if fs::metadata(format!("/proc/{}", pid)).is_ok() { vec![pid] } else { Vec::new() };
+    }
+
+    let Ok(entries) = fs::read_dir("/proc") else { return Vec::new() };
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|pid| {
+            fs::read_to_string(format!("/proc/{}/comm", pid)).is_ok_and(|comm| comm.trim_end() == process)
+        })
+        .collect()
+}
+
+/// Soft `Max open files` from `/proc/<pid>/limits`.
+fn soft_limit(pid: u32) -> Option<u64> {
+    let limits = fs::read_to_string(format!("/proc/{}/limits", pid)).ok()?;
+    let line = limits.lines().find(|line| line.starts_with("Max open files"))?;
+    line.trim_start_matches("Max open files").split_whitespace().next()?.parse().ok()
+}
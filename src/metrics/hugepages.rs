@@ -0,0 +1,43 @@
+//! Hugepage pool usage from `/proc/meminfo` (Linux).
+//!
+//! Databases and VM hosts reserve hugepages up front; the pool counts as used
+//! RAM from the start, so the RAM metric can't tell whether it is actually
+//! being filled.
+
+use std::fs;
+
+use crate::i18n::tr;
+
+pub struct Hugepages {
+    pub total: u64,
+    pub free: u64,
+    /// Promised to a mapping but not faulted in yet.
+    pub reserved: u64,
+}
+
+impl Hugepages {
+    pub fn read() -> Result<Hugepages, String> {
+        let meminfo = fs::read_to_string("/proc/meminfo").map_err(|e| tr!("hugepages-read-failed", error = e))?;
+        let value = |name: &str| {
+            meminfo.lines().find_map(|line| {
+                let rest = line.strip_prefix(name)?.strip_prefix(':')?;
+                rest.split_whitespace().next()?.parse::<u64>().ok()
+            })
+        };
+        let missing = || tr!("hugepages-read-failed", error = "/proc/meminfo");
+        Ok(Hugepages {
+            total: value("HugePages_Total").ok_or_else(missing)?,
+            free: value("HugePages_Free").ok_or_else(missing)?,
+            reserved: value("HugePages_Rsvd").ok_or_else(missing)?,
+        })
+    }
+
+    /// Pages in use or reserved, relative to the pool.
+    pub fn percent(&self) -> Result<f64, String> {
+        if self.total == 0 {
+            return Err(tr!("hugepages-none"));
+        }
+        let taken = self.total.saturating_sub(self.free) + self.reserved;
+        Ok(super::percent(taken.min(self.total) as f64, self.total as f64))
+    }
+}
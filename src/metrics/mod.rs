@@ -0,0 +1,554 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use sysinfo::{CpuExt, System, SystemExt};
+
+use crate::config::Layout;
+use crate::i18n::tr;
+use crate::plugins::{self, Plugin};
+
+#[cfg(feature = "audio")]
+mod audio;
+pub mod breakdown;
+mod cpufreq;
+mod disk;
+mod fans;
+mod fds;
+mod hugepages;
+pub mod numa;
+mod ping;
+mod process;
+mod smart;
+mod vram;
+mod zfs;
+
+/// The value the lamp visualizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Metric {
+    Ram,
+    Swap,
+    /// Usage of all CPU cores together.
+    Cpu,
+    /// Provided by the plugin named in `metrics.plugin`.
+    Plugin,
+    /// Newline-delimited values piped into standard input.
+    Stdin,
+    /// Microphone/loopback level; needs a build with `--features audio`.
+    Audio,
+    /// Round-trip time to `metrics.ping_host`.
+    Ping,
+    /// Read + write throughput of `metrics.disk_device`.
+    Disk,
+    /// Video memory used/total of GPU `metrics.gpu`.
+    Vram,
+    /// ZFS ARC size relative to its maximum.
+    #[serde(rename = "zfs-arc")]
+    ZfsArc,
+    /// Hugepages in use or reserved, relative to the pool.
+    Hugepages,
+    /// Load average over `metrics.load_minutes`, per CPU core.
+    Load,
+    /// Open file descriptors versus the limit, system wide or of `metrics.fd_process`.
+    Fds,
+    /// Fan speed between `metrics.fan_min_rpm` and `metrics.fan_max_rpm`.
+    Fan,
+    /// Disk health via smartctl: green while healthy, red once a rule is broken.
+    Smart,
+    /// Average CPU clock relative to the boost (or base) clock.
+    #[serde(rename = "cpu-freq")]
+    CpuFreq,
+    /// Share of cores that were thermally throttled since the last sample.
+    Throttling,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 17] = [
+        Metric::Ram,
+        Metric::Swap,
+        Metric::Cpu,
+        Metric::Vram,
+        Metric::ZfsArc,
+        Metric::Hugepages,
+        Metric::Load,
+        Metric::Fds,
+        Metric::Fan,
+        Metric::Smart,
+        Metric::CpuFreq,
+        Metric::Throttling,
+        Metric::Plugin,
+        Metric::Stdin,
+        Metric::Audio,
+        Metric::Ping,
+        Metric::Disk,
+    ];
+
+    /// Name as written in the config file (and passed to scripts).
+    pub fn id(&self) -> &'static str {
+        match self {
+            Metric::Ram => "ram",
+            Metric::Swap => "swap",
+            Metric::Cpu => "cpu",
+            Metric::Plugin => "plugin",
+            Metric::Stdin => "stdin",
+            Metric::Audio => "audio",
+            Metric::Ping => "ping",
+            Metric::Disk => "disk",
+            Metric::Vram => "vram",
+            Metric::ZfsArc => "zfs-arc",
+            Metric::Hugepages => "hugepages",
+            Metric::Load => "load",
+            Metric::Fds => "fds",
+            Metric::Fan => "fan",
+            Metric::Smart => "smart",
+            Metric::CpuFreq => "cpu-freq",
+            Metric::Throttling => "throttling",
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Metric::Ram => tr!("metric-ram"),
+            Metric::Swap => tr!("metric-swap"),
+            Metric::Cpu => tr!("metric-cpu"),
+            Metric::Plugin => tr!("metric-plugin"),
+            Metric::Stdin => tr!("metric-stdin"),
+            Metric::Audio => tr!("metric-audio"),
+            Metric::Ping => tr!("metric-ping"),
+            Metric::Disk => tr!("metric-disk"),
+            Metric::Vram => tr!("metric-vram"),
+            Metric::ZfsArc => tr!("metric-zfs-arc"),
+            Metric::Hugepages => tr!("metric-hugepages"),
+            Metric::Load => tr!("metric-load"),
+            Metric::Fds => tr!("metric-fds"),
+            Metric::Fan => tr!("metric-fan"),
+            Metric::Smart => tr!("metric-smart"),
+            Metric::CpuFreq => tr!("metric-cpu-freq"),
+            Metric::Throttling => tr!("metric-throttling"),
+        }
+    }
+
+    /// Metrics that need extra settings in `[metrics]` or a special launch
+    /// (`… | ram-lavalampe --metric stdin`); menus don't offer them directly.
+    pub fn needs_setup(&self) -> bool {
+        match self {
+            Metric::Plugin | Metric::Stdin => true,
+            Metric::Audio => !cfg!(feature = "audio"),
+            Metric::Ram
+            | Metric::Swap
+            | Metric::Cpu
+            | Metric::Vram
+            | Metric::ZfsArc
+            | Metric::Hugepages
+            | Metric::Load
+            | Metric::Fds
+            | Metric::Fan
+            | Metric::Smart
+            | Metric::CpuFreq
+            | Metric::Throttling
+            | Metric::Ping
+            | Metric::Disk => false,
+        }
+    }
+}
+
+/// The `[metrics]` config section: settings of the individual metric sources.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricSettings {
+    /// Name of the plugin used by `metric = "plugin"`.
+    pub plugin: Option<String>,
+    /// Value read from stdin that counts as 100 %.
+    pub stdin_max: f64,
+    /// Input device for `metric = "audio"`, e.g. a PulseAudio monitor source for loopback.
+    pub audio_device: Option<String>,
+    /// Level in dBFS shown as an empty lamp; 0 dBFS is a full one.
+    pub audio_floor_db: f64,
+    /// Host pinged by `metric = "ping"`.
+    pub ping_host: String,
+    /// Round-trip time shown as a full lamp; also the ping timeout.
+    pub ping_max_ms: f64,
+    /// Block device for `metric = "disk"` (`nvme0n1`, `sda`, ...); all disks when unset.
+    pub disk_device: Option<String>,
+    /// Throughput in MB/s shown as a full lamp.
+    pub disk_max_mb_s: f64,
+    /// GPU for `metric = "vram"`, 0 = first.
+    pub gpu: usize,
+    /// Don't count the reclaimable part of the ZFS ARC as used RAM.
+    pub exclude_zfs_arc: bool,
+    /// Show the RAM of this NUMA node only instead of the whole machine.
+    pub numa_node: Option<usize>,
+    /// Show the RAM of this process (name or PID) instead of the whole machine,
+    /// while it runs.
+    pub process: Option<String>,
+    /// RSS of `process` shown as a full lamp; the machine's RAM when unset.
+    pub process_budget_mb: Option<f64>,
+    /// Load average window for `metric = "load"`: 1, 5 or 15 minutes.
+    pub load_minutes: u32,
+    /// Process (name or PID) for `metric = "fds"`; the whole system when unset.
+    pub fd_process: Option<String>,
+    /// Fan for `metric = "fan"` (`chip/fanN` or its label); the fastest fan when unset.
+    pub fan: Option<String>,
+    /// Fan speed shown as an empty lamp.
+    pub fan_min_rpm: f64,
+    /// Fan speed shown as a full lamp.
+    pub fan_max_rpm: f64,
+    /// Disks checked by `metric = "smart"`; all disks smartctl finds when empty.
+    pub smart_devices: Vec<String>,
+    /// Temperature above which a disk counts as unhealthy.
+    pub smart_max_temp_c: f64,
+    /// Reallocated/pending sectors (ATA) or media errors (NVMe) still tolerated.
+    pub smart_max_bad_sectors: u64,
+    /// `metric = "cpu-freq"` relative to the base clock instead of the maximum boost clock.
+    pub cpu_freq_relative_to_base: bool,
+}
+
+impl Default for MetricSettings {
+    fn default() -> Self {
+        MetricSettings {
+            plugin: None,
+            stdin_max: 100.0,
+            audio_device: None,
+            audio_floor_db: -60.0,
+            ping_host: "1.1.1.1".into(),
+            ping_max_ms: 200.0,
+            disk_device: None,
+            disk_max_mb_s: 500.0,
+            gpu: 0,
+            exclude_zfs_arc: true,
+            numa_node: None,
+            process: None,
+            process_budget_mb: None,
+            load_minutes: 1,
+            fd_process: None,
+            fan: None,
+            fan_min_rpm: 0.0,
+            fan_max_rpm: 5000.0,
+            smart_devices: Vec::new(),
+            smart_max_temp_c: 60.0,
+            smart_max_bad_sectors: 0,
+            cpu_freq_relative_to_base: false,
+        }
+    }
+}
+
+/// Latest value read from stdin by the reader thread.
+#[derive(Default)]
+struct StdinState {
+    value: Option<f64>,
+    closed: bool,
+}
+
+/// Reads the metrics and keeps whatever state they need between samples.
+pub struct Sampler {
+    system: System,
+    plugins: Vec<Plugin>,
+    /// Started on first use, so stdin stays untouched unless the metric is picked.
+    stdin: Option<Arc<Mutex<StdinState>>>,
+    #[cfg(feature = "audio")]
+    audio: Option<audio::AudioInput>,
+    ping: Option<ping::Pinger>,
+    disk: Option<disk::DiskCounter>,
+    vram: Option<vram::Vram>,
+    smart: Option<smart::SmartMonitor>,
+    throttle: Option<cpufreq::ThrottleCounter>,
+    process: process::Watch,
+    /// Metrics whose sources stay open while another one is read.
+    keep_open: Vec<Metric>,
+    /// Last error per metric, so an unavailable source logs once instead of on every sample.
+    last_errors: BTreeMap<Metric, String>,
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        Sampler {
+            system: System::new_all(),
+            plugins: plugins::load_all(),
+            stdin: None,
+            #[cfg(feature = "audio")]
+            audio: None,
+            ping: None,
+            disk: None,
+            vram: None,
+            smart: None,
+            throttle: None,
+            process: process::Watch::default(),
+            keep_open: Vec::new(),
+            last_errors: BTreeMap::new(),
+        }
+    }
+
+    /// Keeps the sources of `metrics` open, for reading several metrics in turn.
+    /// Without this, reading one metric closes the others (microphone, ping thread, NVML).
+    pub fn keep_open(&mut self, metrics: Vec<Metric>) {
+        self.keep_open = metrics;
+    }
+
+    /// What 100% of `metric` is in MiB, for metrics measured in memory.
+    /// `None` for the others and for a single NUMA node.
+    pub fn capacity_mib(&self, metric: Metric, settings: &MetricSettings) -> Option<f64> {
+        let bytes = match metric {
+            Metric::Ram if settings.process.is_some() && self.process.running() => self.process_budget(settings),
+            Metric::Ram if settings.numa_node.is_none() => self.system.total_memory(),
+            Metric::Swap => self.system.total_swap(),
+            _ => return None,
+        };
+        (bytes > 0).then(|| bytes as f64 / (1024.0 * 1024.0))
+    }
+
+    pub fn plugin_names(&self) -> Vec<String> {
+        self.plugins.iter().map(|p| p.name.clone()).collect()
+    }
+
+    /// Current usage in percent. Sources without a value report 0 and log why.
+    pub fn sample(&mut self, metric: Metric, settings: &MetricSettings) -> f64 {
+        match self.read(metric, settings) {
+            Ok(percent) => {
+                self.last_errors.remove(&metric);
+                percent
+            }
+            Err(error) => {
+                if self.last_errors.get(&metric) != Some(&error) {
+                    log::error!("{}", tr!("metric-unavailable", metric = metric.description(), error = &error));
+                    self.last_errors.insert(metric, error);
+                }
+                0.0
+            }
+        }
+    }
+
+    /// One value per lamp of `layout`; a single-lamp layout samples `metric`.
+    pub fn sample_lamps(&mut self, layout: Layout, metric: Metric, settings: &MetricSettings) -> Vec<f64> {
+        let values: Result<Vec<f64>, String> = match layout {
+            Layout::Single => return vec![self.sample(metric, settings)],
+            Layout::PerCore => {
+                self.system.refresh_cpu();
+                Ok(self.system.cpus().iter().map(|cpu| cpu.cpu_usage() as f64).collect())
+            }
+            Layout::PerNumaNode => numa::nodes()
+                .into_iter()
+                .map(|node| numa::usage(node).map(|(used, total)| percent(used as f64, total as f64)))
+                .collect(),
+        };
+        match values {
+            Ok(values) => {
+                self.last_errors.remove(&layout.metric(metric));
+                values
+            }
+            Err(error) => {
+                let metric = layout.metric(metric);
+                if self.last_errors.get(&metric) != Some(&error) {
+                    log::error!("{}", tr!("metric-unavailable", metric = metric.description(), error = &error));
+                    self.last_errors.insert(metric, error);
+                }
+                vec![0.0]
+            }
+        }
+    }
+
+    /// The RSS that fills the lamp for `metrics.process`, in bytes.
+    fn process_budget(&self, settings: &MetricSettings) -> u64 {
+        settings
+            .process_budget_mb
+            .map_or_else(|| self.system.total_memory(), |mb| (mb * 1024.0 * 1024.0) as u64)
+    }
+
+    fn read(&mut self, metric: Metric, settings: &MetricSettings) -> Result<f64, String> {
+        // Zustand der anderen Quellen freigeben (Mikrofon, Ping-Thread, NVML, alte Zählerstände)
+        let release = |source: Metric| metric != source && !self.keep_open.contains(&source);
+        #[cfg(feature = "audio")]
+        if release(Metric::Audio) {
+            self.audio = None;
+        }
+        if release(Metric::Ping) {
+            self.ping = None;
+        }
+        if release(Metric::Disk) {
+            self.disk = None;
+        }
+        if release(Metric::Vram) {
+            self.vram = None;
+        }
+        if release(Metric::Smart) {
+            self.smart = None;
+        }
+        if release(Metric::Throttling) {
+            self.throttle = None;
+        }
+        if release(Metric::Ram) || settings.process.is_none() {
+            self.process.reset();
+        }
+
+        match metric {
+            Metric::Ram => {
+                if let Some(name) = &settings.process {
+                    let rss = process::rss(&mut self.system, name);
+                    self.process.update(name, rss.is_some());
+                    // Läuft der Prozess nicht, geht es mit dem ganzen System weiter
+                    if let Some(rss) = rss {
+                        return Ok(percent(rss as f64, self.process_budget(settings) as f64).min(100.0));
+                    }
+                }
+                if let Some(node) = settings.numa_node {
+                    let (used, total) = numa::usage(node)?;
+                    return Ok(percent(used as f64, total as f64));
+                }
+                self.system.refresh_memory();
+                let mut used = self.system.used_memory();
+                if settings.exclude_zfs_arc {
+                    if let Some(arc) = zfs::ArcStats::read() {
+                        used = used.saturating_sub(arc.reclaimable());
+                    }
+                }
+                Ok(percent(used as f64, self.system.total_memory() as f64))
+            }
+            Metric::Swap => {
+                self.system.refresh_memory();
+                Ok(percent(self.system.used_swap() as f64, self.system.total_swap() as f64))
+            }
+            Metric::Cpu => {
+                self.system.refresh_cpu();
+                Ok(self.system.global_cpu_info().cpu_usage() as f64)
+            }
+            Metric::Hugepages => hugepages::Hugepages::read()?.percent(),
+            Metric::Fds => match &settings.fd_process {
+                Some(process) => fds::process_percent(process),
+                None => fds::system_percent(),
+            },
+            Metric::Fan => {
+                let rpm = fans::rpm(settings.fan.as_deref())?;
+                let range = settings.fan_max_rpm - settings.fan_min_rpm;
+                Ok(((rpm - settings.fan_min_rpm) / range * 100.0).clamp(0.0, 100.0))
+            }
+            Metric::Smart => {
+                let rules = smart::Rules {
+                    devices: settings.smart_devices.clone(),
+                    max_temp_c: settings.smart_max_temp_c,
+                    max_bad_sectors: settings.smart_max_bad_sectors,
+                };
+                if self.smart.as_ref().is_some_and(|m| m.rules != rules) {
+                    self.smart = None;
+                }
+                self.smart.get_or_insert_with(|| smart::SmartMonitor::start(rules)).percent()
+            }
+            Metric::CpuFreq => cpufreq::frequency_percent(settings.cpu_freq_relative_to_base),
+            Metric::Throttling => self.throttle.get_or_insert_with(cpufreq::ThrottleCounter::new).percent(),
+            Metric::Load => {
+                let load = self.system.load_average();
+                let value = match settings.load_minutes {
+                    5 => load.five,
+                    15 => load.fifteen,
+                    _ => load.one,
+                };
+                // Last 1.0 pro Kern = alle Kerne ausgelastet = volle Lampe
+                let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+                Ok((value / cores as f64 * 100.0).clamp(0.0, 100.0))
+            }
+            Metric::ZfsArc => {
+                let arc = zfs::ArcStats::read().ok_or_else(|| tr!("zfs-not-loaded"))?;
+                Ok(percent(arc.size as f64, arc.c_max as f64))
+            }
+            Metric::Plugin => {
+                let name = settings.plugin.as_ref().ok_or_else(|| tr!("plugin-not-set"))?;
+                let plugin = self
+                    .plugins
+                    .iter()
+                    .find(|p| &p.name == name)
+                    .ok_or_else(|| tr!("plugin-not-found", name = name))?;
+                plugin.sample().ok_or_else(|| tr!("plugin-no-value", name = name))
+            }
+            Metric::Stdin => {
+                let state = self.stdin.get_or_insert_with(spawn_stdin_reader).lock().unwrap();
+                match state.value {
+                    Some(value) => Ok(percent(value, settings.stdin_max).clamp(0.0, 100.0)),
+                    None if state.closed => Err(tr!("stdin-closed")),
+                    None => Err(tr!("stdin-waiting")),
+                }
+            }
+            #[cfg(feature = "audio")]
+            Metric::Audio => {
+                // Gerät wechseln, wenn sich die Einstellung geändert hat
+                if self.audio.as_ref().is_some_and(|input| input.device != settings.audio_device) {
+                    self.audio = None;
+                }
+                if self.audio.is_none() {
+                    self.audio = Some(audio::AudioInput::start(settings.audio_device.clone())?);
+                }
+                let db = self.audio.as_ref().map_or(f64::MIN, |input| input.take_level_db());
+                let floor = settings.audio_floor_db;
+                Ok(((db - floor) / -floor * 100.0).clamp(0.0, 100.0))
+            }
+            #[cfg(not(feature = "audio"))]
+            Metric::Audio => Err(tr!("audio-not-built")),
+            Metric::Ping => {
+                if self.ping.as_ref().is_some_and(|p| p.host != settings.ping_host || p.max_ms != settings.ping_max_ms) {
+                    self.ping = None;
+                }
+                let pinger = self
+                    .ping
+                    .get_or_insert_with(|| ping::Pinger::start(settings.ping_host.clone(), settings.ping_max_ms));
+                pinger.percent()
+            }
+            Metric::Disk => {
+                if self.disk.as_ref().is_some_and(|d| d.device != settings.disk_device) {
+                    self.disk = None;
+                }
+                let counter = self.disk.get_or_insert_with(|| disk::DiskCounter::new(settings.disk_device.clone()));
+                let mb_s = counter.rate()? / 1_000_000.0;
+                Ok((mb_s / settings.disk_max_mb_s * 100.0).clamp(0.0, 100.0))
+            }
+            Metric::Vram => {
+                if self.vram.as_ref().is_some_and(|v| v.gpu != settings.gpu) {
+                    self.vram = None;
+                }
+                if self.vram.is_none() {
+                    self.vram = Some(vram::Vram::open(settings.gpu)?);
+                }
+                self.vram.as_ref().map_or(Ok(0.0), |v| v.percent())
+            }
+        }
+    }
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Sampler::new()
+    }
+}
+
+/// Reads stdin line by line; lines that are not a number are reported and skipped.
+/// After EOF the last value stays on display.
+fn spawn_stdin_reader() -> Arc<Mutex<StdinState>> {
+    let state = Arc::new(Mutex::new(StdinState::default()));
+    let shared = Arc::clone(&state);
+
+    let _ = thread::Builder::new().name("stdin-metric".into()).spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            // Erstes Feld genügt, damit auch "42.5 %" oder "42.5 load" funktionieren
+            let field = line.split_whitespace().next().unwrap_or(line).trim_end_matches('%');
+            match field.parse::<f64>() {
+                Ok(value) if value.is_finite() => shared.lock().unwrap().value = Some(value),
+                _ => log::error!("{}", tr!("stdin-bad-line", line = line)),
+            }
+        }
+        log::info!("{}", tr!("stdin-eof"));
+        shared.lock().unwrap().closed = true;
+    });
+
+    state
+}
+
+fn percent(used: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        (used / total) * 100.0
+    } else {
+        0.0
+    }
+}
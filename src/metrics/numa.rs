@@ -0,0 +1,46 @@
+//! Memory of a single NUMA node from `/sys/devices/system/node/node<N>/meminfo`.
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::i18n::tr;
+
+const NODE_DIR: &str = "/sys/devices/system/node";
+
+/// Node numbers present on this machine (just `[0]` without NUMA).
+pub fn nodes() -> Vec<usize> {
+    let mut nodes: Vec<usize> = fs::read_dir(NODE_DIR)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| entry.file_name().to_str()?.strip_prefix("node")?.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    nodes.sort_unstable();
+    nodes
+}
+
+/// Used and total bytes of `node`. "Used" leaves out page cache and reclaimable
+/// slab, matching what `MemAvailable` means for the whole system.
+pub fn usage(node: usize) -> Result<(u64, u64), String> {
+    let path = PathBuf::from(NODE_DIR).join(format!("node{}", node)).join("meminfo");
+    let meminfo = fs::read_to_string(&path).map_err(|_| tr!("numa-node-not-found", node = node))?;
+
+    // Zeilen: "Node 0 MemTotal:       16318508 kB"
+    let value = |name: &str| {
+        meminfo
+            .lines()
+            .find_map(|line| {
+                let mut fields = line.split_whitespace().skip(2);
+                (fields.next()?.strip_suffix(':')? == name).then(|| fields.next()?.parse::<u64>().ok())?
+            })
+            .unwrap_or(0)
+            * 1024
+    };
+
+    let total = value("MemTotal");
+    let cache = value("FilePages").saturating_sub(value("Shmem")) + value("SReclaimable");
+    let used = total.saturating_sub(value("MemFree")).saturating_sub(cache);
+    Ok((used, total))
+}
@@ -0,0 +1,98 @@
+//! Round-trip time to a host, measured with the system `ping` (which has the
+//! privileges for ICMP that the lamp doesn't) on a background thread.
+
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::i18n::tr;
+
+/// Time between two pings; one packet per second is what `ping` itself does.
+const PING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Result of the last ping: RTT in ms, or `None` if the packet was lost.
+type Rtt = Option<Result<Option<f64>, String>>;
+
+pub struct Pinger {
+    pub host: String,
+    pub max_ms: f64,
+    last: Arc<Mutex<Rtt>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Pinger {
+    pub fn start(host: String, max_ms: f64) -> Pinger {
+        let last = Arc::new(Mutex::new(None));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let (shared, stopped, target) = (Arc::clone(&last), Arc::clone(&stop), host.clone());
+        let _ = thread::Builder::new().name("ping".into()).spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                let started = Instant::now();
+                let result = ping_once(&target, max_ms);
+                *shared.lock().unwrap() = Some(result);
+                thread::sleep(PING_INTERVAL.saturating_sub(started.elapsed()));
+            }
+        });
+
+        Pinger { host, max_ms, last, stop }
+    }
+
+    /// RTT as a share of `max_ms`; a lost packet or timeout counts as 100 %.
+    pub fn percent(&self) -> Result<f64, String> {
+        match self.last.lock().unwrap().clone() {
+            None => Err(tr!("ping-waiting", host = &self.host)),
+            Some(Err(e)) => Err(e),
+            Some(Ok(None)) => Ok(100.0),
+            Some(Ok(Some(rtt))) => Ok((rtt / self.max_ms * 100.0).clamp(0.0, 100.0)),
+        }
+    }
+}
+
+impl Drop for Pinger {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn ping_once(host: &str, max_ms: f64) -> Result<Option<f64>, String> {
+    let mut command = Command::new("ping");
+    if cfg!(windows) {
+        command.args(["-n", "1", "-w", &format!("{}", max_ms.ceil() as u64)]);
+    } else if cfg!(target_os = "macos") {
+        command.args(["-c", "1", "-W", &format!("{}", max_ms.ceil() as u64)]);
+    } else {
+        // iputils erwartet Sekunden
+        command.args(["-c", "1", "-W", &format!("{}", (max_ms / 1000.0).ceil().max(1.0) as u64)]);
+    }
+    let output = command
+        .arg(host)
+        .env("LC_ALL", "C")
+        .output()
+        .map_err(|e| tr!("ping-failed", error = e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match parse_rtt(&stdout) {
+        Some(rtt) => Ok(Some(rtt)),
+        // No reply exits with 1 (2 on macOS); anything else is a real error (unknown host, ...)
+        None if output.status.code() == Some(if cfg!(target_os = "macos") { 2 } else { 1 }) => Ok(None),
+        None => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(tr!("ping-failed", error = stderr.trim()))
+        }
+    }
+}
+
+/// Finds `time=12.3 ms` (Unix) or `time<1ms` / `Zeit=12ms` (Windows) in the output.
+fn parse_rtt(output: &str) -> Option<f64> {
+    output.split_whitespace().find_map(|token| {
+        let (label, value) = token.split_once(['=', '<'])?;
+        if !label.chars().all(char::is_alphabetic) || label.eq_ignore_ascii_case("ttl") {
+            return None;
+        }
+        let value = value.trim_end_matches("ms");
+        value.parse::<f64>().ok().filter(|_| label.eq_ignore_ascii_case("time") || token.ends_with("ms"))
+    })
+}
@@ -0,0 +1,60 @@
+//! RAM of a single process (`metrics.process`, `--watch-process`) instead of
+//! the whole machine: the resident set size of every process matching a PID
+//! or name, summed, against a budget. While none runs, `metric = "ram"` shows
+//! the system again; a process that exits and starts again is picked up by
+//! name at the next sample.
+
+use sysinfo::{Pid, PidExt, ProcessExt, ProcessRefreshKind, System, SystemExt};
+
+use crate::i18n::tr;
+
+/// Summed RSS in bytes of all processes matching `process`, `None` if none runs.
+pub fn rss(system: &mut System, process: &str) -> Option<u64> {
+    system.refresh_processes_specifics(ProcessRefreshKind::new());
+    if let Ok(pid) = process.parse::<u32>() {
+        return system.process(Pid::from_u32(pid)).map(|p| p.memory());
+    }
+
+    // Unter Windows heißt "firefox" dort "firefox.exe"
+    let matches = |name: &str| name == process || name.strip_suffix(".exe") == Some(process);
+    system
+        .processes()
+        .values()
+        .filter(|p| matches(p.name()))
+        .map(|p| p.memory())
+        .reduce(|a, b| a + b)
+}
+
+/// Whether the watched process ran at the last sample, so that only starts
+/// and exits are logged.
+#[derive(Debug, Default)]
+pub struct Watch {
+    last: Option<(String, bool)>,
+}
+
+impl Watch {
+    /// Notes whether `process` runs now and logs when that changed.
+    pub fn update(&mut self, process: &str, running: bool) {
+        let changed = self.last.as_ref().is_none_or(|(name, was)| name != process || *was != running);
+        if !changed {
+            return;
+        }
+        let first = self.last.as_ref().is_none_or(|(name, _)| name != process);
+        match (running, first) {
+            (true, _) => log::info!("{}", tr!("process-watching", process = process)),
+            (false, true) => log::warn!("{}", tr!("process-watch-fallback", process = process)),
+            (false, false) => log::warn!("{}", tr!("process-watch-exited", process = process)),
+        }
+        self.last = Some((process.to_string(), running));
+    }
+
+    /// Whether the process ran at the last sample.
+    pub fn running(&self) -> bool {
+        self.last.as_ref().is_some_and(|(_, running)| *running)
+    }
+
+    /// Forgets the process, e.g. when another metric is shown.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
@@ -0,0 +1,147 @@
+//! Disk health from `smartctl --json` (smartmontools), checked against simple
+//! rules. The lamp stays green while every disk passes and turns red as soon
+//! as one breaks a rule — an early-warning light, not a gauge.
+
+use serde_json::Value;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::i18n::tr;
+
+/// SMART data changes slowly and smartctl may wake sleeping disks; once a minute is plenty.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The health rules, from the `[metrics]` section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rules {
+    /// Devices to check (`/dev/sda`, `/dev/nvme0`); all disks from `smartctl --scan` when empty.
+    pub devices: Vec<String>,
+    pub max_temp_c: f64,
+    /// Reallocated + pending sectors (ATA) or media errors (NVMe) that are still tolerated.
+    pub max_bad_sectors: u64,
+}
+
+/// Outcome of the last check: the broken rules, empty if all disks are fine.
+type Verdict = Option<Result<Vec<String>, String>>;
+
+pub struct SmartMonitor {
+    pub rules: Rules,
+    last: Arc<Mutex<Verdict>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl SmartMonitor {
+    pub fn start(rules: Rules) -> SmartMonitor {
+        let last = Arc::new(Mutex::new(None));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let (shared, stopped, thread_rules) = (Arc::clone(&last), Arc::clone(&stop), rules.clone());
+        let _ = thread::Builder::new().name("smart".into()).spawn(move || {
+            let mut reported: Vec<String> = Vec::new();
+            while !stopped.load(Ordering::Relaxed) {
+                let result = check_all(&thread_rules);
+                // Jeden neuen Befund einmal melden, nicht bei jeder Prüfung
+                if let Ok(problems) = &result {
+                    for problem in problems.iter().filter(|p| !reported.contains(p)) {
+                        log::warn!("{}", tr!("smart-problem", problem = problem));
+                    }
+                    reported = problems.clone();
+                }
+                *shared.lock().unwrap() = Some(result);
+
+                let checked = Instant::now();
+                while checked.elapsed() < CHECK_INTERVAL && !stopped.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(500));
+                }
+            }
+        });
+
+        SmartMonitor { rules, last, stop }
+    }
+
+    /// 0 % while healthy, 100 % if any rule is broken.
+    pub fn percent(&self) -> Result<f64, String> {
+        match self.last.lock().unwrap().clone() {
+            None => Err(tr!("smart-waiting")),
+            Some(Err(e)) => Err(e),
+            Some(Ok(problems)) => Ok(if problems.is_empty() { 0.0 } else { 100.0 }),
+        }
+    }
+}
+
+impl Drop for SmartMonitor {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn check_all(rules: &Rules) -> Result<Vec<String>, String> {
+    let devices = if rules.devices.is_empty() { scan()? } else { rules.devices.clone() };
+    if devices.is_empty() {
+        return Err(tr!("smart-no-devices"));
+    }
+
+    let mut problems = Vec::new();
+    for device in &devices {
+        let report = smartctl(&["--json", "--health", "--attributes", device])?;
+        problems.extend(evaluate(device, &report, rules));
+    }
+    Ok(problems)
+}
+
+fn scan() -> Result<Vec<String>, String> {
+    let report = smartctl(&["--json", "--scan"])?;
+    Ok(report["devices"]
+        .as_array()
+        .map(|devices| devices.iter().filter_map(|d| d["name"].as_str().map(String::from)).collect())
+        .unwrap_or_default())
+}
+
+fn smartctl(args: &[&str]) -> Result<Value, String> {
+    // Der Exit-Code ist eine Bitmaske (auch bei "Platte meldet Fehler"), daher nur das JSON auswerten
+    let output = Command::new("smartctl").args(args).output().map_err(|e| tr!("smart-failed", error = e))?;
+    let report: Value = serde_json::from_slice(&output.stdout).map_err(|e| tr!("smart-failed", error = e))?;
+
+    let messages = report["smartctl"]["messages"].as_array().cloned().unwrap_or_default();
+    if let Some(error) = messages.iter().find(|m| m["severity"] == "error") {
+        return Err(tr!("smart-failed", error = error["string"].as_str().unwrap_or_default()));
+    }
+    Ok(report)
+}
+
+/// Broken rules of one device, as readable messages.
+fn evaluate(device: &str, report: &Value, rules: &Rules) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if report["smart_status"]["passed"] == false {
+        problems.push(tr!("smart-failing", device = device));
+    }
+
+    if let Some(temp) = report["temperature"]["current"].as_f64() {
+        if temp > rules.max_temp_c {
+            problems.push(tr!("smart-too-hot", device = device, temp = temp, max = rules.max_temp_c));
+        }
+    }
+
+    // ATA: 5 = Reallocated_Sector_Ct, 197 = Current_Pending_Sector; NVMe: media_errors
+    let ata_bad: u64 = report["ata_smart_attributes"]["table"]
+        .as_array()
+        .map(|table| {
+            table
+                .iter()
+                .filter(|attr| matches!(attr["id"].as_u64(), Some(5 | 197)))
+                .filter_map(|attr| attr["raw"]["value"].as_u64())
+                .sum()
+        })
+        .unwrap_or(0);
+    let nvme_bad = report["nvme_smart_health_information_log"]["media_errors"].as_u64().unwrap_or(0);
+    let bad = ata_bad + nvme_bad;
+    if bad > rules.max_bad_sectors {
+        problems.push(tr!("smart-bad-sectors", device = device, count = bad, max = rules.max_bad_sectors));
+    }
+
+    problems
+}
@@ -0,0 +1,70 @@
+//! Video memory in use: NVML for NVIDIA cards (the library is loaded at
+//! runtime, so the lamp still starts without the driver), sysfs for AMD
+//! (`/sys/class/drm/card*/device/mem_info_vram_*`).
+
+use nvml_wrapper::Nvml;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::i18n::tr;
+
+enum Source {
+    Nvml(Box<Nvml>),
+    Sysfs(PathBuf),
+}
+
+pub struct Vram {
+    pub gpu: usize,
+    source: Source,
+}
+
+impl Vram {
+    /// Opens GPU number `gpu` (0 = first) of the first vendor that has any.
+    pub fn open(gpu: usize) -> Result<Vram, String> {
+        if let Ok(nvml) = Nvml::init() {
+            if nvml.device_count().is_ok_and(|count| (gpu as u32) < count) {
+                return Ok(Vram { gpu, source: Source::Nvml(Box::new(nvml)) });
+            }
+        }
+
+        let mut cards: Vec<PathBuf> = fs::read_dir("/sys/class/drm")
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path().join("device"))
+                    .filter(|device| device.join("mem_info_vram_total").exists())
+                    .collect()
+            })
+            .unwrap_or_default();
+        cards.sort();
+        // card0 und renderD128 zeigen auf dasselbe Gerät
+        cards.dedup_by(|a, b| fs::canonicalize(&*a).ok() == fs::canonicalize(&*b).ok());
+
+        match cards.into_iter().nth(gpu) {
+            Some(device) => Ok(Vram { gpu, source: Source::Sysfs(device) }),
+            None => Err(tr!("vram-no-gpu", gpu = gpu)),
+        }
+    }
+
+    pub fn percent(&self) -> Result<f64, String> {
+        let (used, total) = match &self.source {
+            Source::Nvml(nvml) => {
+                let memory = nvml
+                    .device_by_index(self.gpu as u32)
+                    .and_then(|device| device.memory_info())
+                    .map_err(|e| tr!("vram-read-failed", error = e))?;
+                (memory.used, memory.total)
+            }
+            Source::Sysfs(device) => {
+                let read = |file: &str| {
+                    fs::read_to_string(device.join(file))
+                        .ok()
+                        .and_then(|text| text.trim().parse::<u64>().ok())
+                        .ok_or_else(|| tr!("vram-read-failed", error = device.join(file).display()))
+                };
+                (read("mem_info_vram_used")?, read("mem_info_vram_total")?)
+            }
+        };
+        Ok(super::percent(used as f64, total as f64))
+    }
+}
@@ -0,0 +1,37 @@
+//! ZFS ARC statistics from `/proc/spl/kstat/zfs/arcstats` (ZFS on Linux).
+//!
+//! The kernel counts the ARC as used memory although ZFS hands it back under
+//! pressure, so on a NAS the RAM metric sits near 100 % all the time.
+
+use std::fs;
+
+const ARCSTATS: &str = "/proc/spl/kstat/zfs/arcstats";
+
+pub struct ArcStats {
+    /// Current ARC size in bytes.
+    pub size: u64,
+    /// Size the ARC never shrinks below.
+    pub c_min: u64,
+    /// Size the ARC may grow to.
+    pub c_max: u64,
+}
+
+impl ArcStats {
+    /// `None` when ZFS isn't loaded.
+    pub fn read() -> Option<ArcStats> {
+        let text = fs::read_to_string(ARCSTATS).ok()?;
+        // Zeilen: "name type data", die ersten zwei Zeilen sind Kopfzeilen
+        let value = |name: &str| {
+            text.lines().find_map(|line| {
+                let mut fields = line.split_whitespace();
+                (fields.next() == Some(name)).then(|| fields.nth(1)?.parse::<u64>().ok()).flatten()
+            })
+        };
+        Some(ArcStats { size: value("size")?, c_min: value("c_min")?, c_max: value("c_max")? })
+    }
+
+    /// The part of the ARC ZFS would give back if applications needed the memory.
+    pub fn reclaimable(&self) -> u64 {
+        self.size.saturating_sub(self.c_min)
+    }
+}
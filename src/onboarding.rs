@@ -0,0 +1,78 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use crate::config::{self, Config, Corner};
+use crate::i18n::tr;
+use crate::metrics::Metric;
+use crate::WindowSizeMode;
+
+/// Asks for the basic settings on the first start and writes them to the config file.
+/// Without a terminal on stdin (e.g. started from a launcher) the defaults are written instead.
+pub fn run() -> Config {
+    let mut config = Config::default();
+
+    if io::stdin().is_terminal() {
+        println!();
+        println!("{}", tr!("onboarding-welcome"));
+        println!("{}", tr!("onboarding-intro"));
+        println!();
+
+        config.size = choose(&tr!("onboarding-size"), &WindowSizeMode::ALL, config.size, |m| m.description().into());
+        config.corner = choose(&tr!("onboarding-corner"), &Corner::ALL, config.corner, Corner::description);
+        let metrics: Vec<Metric> = Metric::ALL.into_iter().filter(|m| !m.needs_setup()).collect();
+        config.metric = choose(&tr!("onboarding-metric"), &metrics, config.metric, |m| m.description());
+        config.autostart = confirm(&tr!("onboarding-autostart"), config.autostart);
+        println!();
+    } else {
+        log::info!("{}", tr!("onboarding-no-terminal"));
+    }
+
+    match config.save() {
+        Ok(path) => log::info!("{}", tr!("config-written", path = path.display())),
+        Err(e) => log::error!("{}", tr!("config-write-failed", error = e)),
+    }
+
+    if let Err(e) = config::set_autostart(config.autostart) {
+        log::error!("{}", tr!("autostart-failed", error = e));
+    }
+
+    config
+}
+
+fn read_answer() -> String {
+    let _ = io::stdout().flush();
+    let mut line = String::new();
+    let _ = io::stdin().lock().read_line(&mut line);
+    line.trim().to_lowercase()
+}
+
+fn choose<T: Copy + PartialEq>(question: &str, options: &[T], default: T, label: fn(&T) -> String) -> T {
+    println!("{}", question);
+    for (i, option) in options.iter().enumerate() {
+        let marker = if *option == default { format!(" {}", tr!("onboarding-default")) } else { String::new() };
+        println!("  {}) {}{}", i + 1, label(option), marker);
+    }
+
+    loop {
+        print!("> ");
+        let answer = read_answer();
+        if answer.is_empty() {
+            return default;
+        }
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=options.len()).contains(&n) => return options[n - 1],
+            _ => println!("{}", tr!("onboarding-enter-number", max = options.len())),
+        }
+    }
+}
+
+fn confirm(question: &str, default: bool) -> bool {
+    loop {
+        print!("{} [{}] ", question, if default { tr!("onboarding-yes-no-default-yes") } else { tr!("onboarding-yes-no") });
+        match read_answer().as_str() {
+            "" => return default,
+            "y" | "yes" | "j" | "ja" => return true,
+            "n" | "no" | "nein" => return false,
+            _ => println!("{}", tr!("onboarding-answer-yes-no")),
+        }
+    }
+}
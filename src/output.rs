@@ -0,0 +1,215 @@
+//! `--output <format>`: instead of opening a window, samples the configured
+//! metric and prints one line per sample for a status bar. The colors come
+//! from the same thresholds (and script, if any) as the lamp's.
+//!
+//! - `i3bar`: the i3bar JSON protocol for `status_command` in i3 and sway,
+//!   one block per lamp.
+//! - `waybar`: one JSON object per line for a Waybar custom module
+//!   (`return-type: json`), with the color as CSS class.
+//! - `polybar`: one line of text with Polybar color tags, for a script module.
+//!
+//! Log messages go to stderr so they don't end up in the bar. The loop ends
+//! when the bar closes the pipe.
+//!
+//! `ram-lavalampe status` prints a single sample and exits, for status lines
+//! that run a command every few seconds (tmux's `#(...)`).
+
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::app;
+use crate::config::Config;
+use crate::logging;
+use crate::metrics::{Metric, Sampler};
+use crate::script::{Mapping, Script};
+use crate::tier::Tier;
+
+/// Time between the two samples a rate (CPU, disk I/O) needs.
+const RATE_WARMUP: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    I3bar,
+    Waybar,
+    Polybar,
+}
+
+impl Format {
+    pub const ALL: [Format; 3] = [Format::I3bar, Format::Waybar, Format::Polybar];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Format::I3bar => "i3bar",
+            Format::Waybar => "waybar",
+            Format::Polybar => "polybar",
+        }
+    }
+}
+
+/// Formats of the one-shot `status` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusFormat {
+    /// `RAM 42%`
+    Plain,
+    /// `#[fg=green]▮ 42%#[default]` for `status-right` and friends.
+    Tmux,
+}
+
+impl StatusFormat {
+    pub const ALL: [StatusFormat; 2] = [StatusFormat::Plain, StatusFormat::Tmux];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            StatusFormat::Plain => "plain",
+            StatusFormat::Tmux => "tmux",
+        }
+    }
+}
+
+pub fn run(config: &Config, format: Format) {
+    logging::use_stderr();
+    let layout = config.layout;
+    let metric = layout.metric(config.metric);
+    let mut sampler = Sampler::new();
+    let mut script = app::load_script(config);
+    let mut stdout = io::stdout().lock();
+
+    if format == Format::I3bar {
+        // Kopf und Anfang des endlosen Arrays
+        if writeln!(stdout, "{}\n[", json!({ "version": 1 })).is_err() {
+            return;
+        }
+    }
+    loop {
+        let readings = read(&mut sampler, &mut script, config);
+        let line = match format {
+            Format::I3bar => i3bar_line(metric, &readings),
+            Format::Waybar => waybar_line(metric, &readings),
+            Format::Polybar => polybar_line(&readings),
+        };
+        if writeln!(stdout, "{}", line).and_then(|_| stdout.flush()).is_err() {
+            return;
+        }
+        thread::sleep(config.sampling_interval());
+    }
+}
+
+/// Prints one sample in `format` and returns.
+pub fn status(config: &Config, format: StatusFormat) {
+    logging::use_stderr();
+    let metric = config.layout.metric(config.metric);
+    let mut sampler = Sampler::new();
+    let mut script = app::load_script(config);
+    // Raten brauchen einen Wert davor, sonst wäre die CPU beim ersten Sample immer bei 0 %
+    if matches!(metric, Metric::Cpu | Metric::Disk) {
+        sampler.sample_lamps(config.layout, config.metric, &config.metrics);
+        thread::sleep(RATE_WARMUP);
+    }
+    let readings = read(&mut sampler, &mut script, config);
+    let line = match format {
+        StatusFormat::Plain => readings
+            .iter()
+            .map(|(percent, _)| format!("{} {:.0}%", metric.description(), percent))
+            .collect::<Vec<_>>()
+            .join(" "),
+        StatusFormat::Tmux => readings
+            .iter()
+            .map(|(percent, mapping)| format!("#[fg={}]▮ {:.0}%#[default]", tmux_color(mapping.tier), percent))
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+    println!("{}", line);
+}
+
+/// Samples every lamp of the configured layout and maps it like the lamp would.
+fn read(sampler: &mut Sampler, script: &mut Option<Script>, config: &Config) -> Vec<(f64, Mapping)> {
+    let metric = config.layout.metric(config.metric);
+    sampler
+        .sample_lamps(config.layout, config.metric, &config.metrics)
+        .into_iter()
+        .map(|percent| {
+            let mapping = match script {
+                Some(script) => script.map(percent, metric.id(), config),
+                None => Mapping::default_for(percent, config),
+            };
+            (percent, mapping)
+        })
+        .collect()
+}
+
+/// One status line of the i3bar protocol: an array of blocks and a comma,
+/// since the whole output is one endless array.
+fn i3bar_line(metric: Metric, readings: &[(f64, Mapping)]) -> String {
+    let blocks: Vec<_> = readings
+        .iter()
+        .enumerate()
+        .map(|(i, (percent, mapping))| {
+            let label = match readings.len() {
+                1 => metric.description(),
+                _ => format!("{} {}", metric.description(), i + 1),
+            };
+            json!({
+                "name": env!("CARGO_PKG_NAME"),
+                "instance": format!("{}-{}", metric.id(), i),
+                "full_text": format!("{} {:.0}%", label, percent),
+                "short_text": format!("{:.0}%", percent),
+                "color": hex_color(mapping.tier),
+            })
+        })
+        .collect();
+    format!("{},", json!(blocks))
+}
+
+/// A Waybar module line. Several lamps share one module: the text lists all
+/// of them, `percentage` (for `format-icons`) and the class follow the fullest.
+fn waybar_line(metric: Metric, readings: &[(f64, Mapping)]) -> String {
+    let text = readings.iter().map(|(percent, _)| format!("{:.0}%", percent)).collect::<Vec<_>>().join(" ");
+    let tooltip = readings
+        .iter()
+        .map(|(percent, mapping)| format!("{}: {:.1}% ({})", metric.description(), percent, mapping.tier.name()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let Some((percent, mapping)) = peak(readings) else { return String::new() };
+    // Klassen für das Stylesheet: die Farbe, dazu "boiling" oder "sleeping"
+    let mut class = vec![mapping.tier.name()];
+    class.extend(mapping.extreme.map(|extreme| extreme.name()));
+    json!({
+        "text": text,
+        "tooltip": tooltip,
+        "class": class,
+        "percentage": percent.round() as u32,
+    })
+    .to_string()
+}
+
+/// A Polybar line: each lamp's percentage in its lava color.
+fn polybar_line(readings: &[(f64, Mapping)]) -> String {
+    readings
+        .iter()
+        .map(|(percent, mapping)| format!("%{{F{}}}{:.0}%%{{F-}}", hex_color(mapping.tier), percent))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn peak(readings: &[(f64, Mapping)]) -> Option<(f64, &Mapping)> {
+    readings.iter().map(|(percent, mapping)| (*percent, mapping)).max_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+/// tmux color names; tmux has no "orange", 208 is the closest of its 256.
+fn tmux_color(tier: Tier) -> &'static str {
+    match tier {
+        Tier::Green => "green",
+        Tier::Yellow => "yellow",
+        Tier::Orange => "colour208",
+        Tier::Red => "red",
+    }
+}
+
+/// `#rrggbb` of the lava color.
+fn hex_color(tier: Tier) -> String {
+    let [r, g, b, _] = tier.debug_color();
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
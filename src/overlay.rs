@@ -0,0 +1,167 @@
+//! Text on top of the lamps: short messages that appear for a moment after
+//! a change, like the speed after scrolling, and banners that stay.
+//!
+//! The lamp has no font files to load, so text is drawn with a built-in 5×7
+//! pixel font (digits, capital letters and a little punctuation; lowercase
+//! is drawn as uppercase). Glyphs are scaled up with the lamp like the pixel
+//! art itself.
+
+use std::time::{Duration, Instant};
+
+use crate::WINDOW_SIZE;
+
+/// How long a message stays on screen.
+pub const TOAST_DURATION: Duration = Duration::from_millis(1500);
+
+const GLYPH_WIDTH: usize = 5;
+pub const GLYPH_HEIGHT: usize = 7;
+
+/// A message shown over the lamps until it expires.
+pub struct Toast {
+    text: String,
+    until: Instant,
+}
+
+impl Toast {
+    pub fn new(text: String) -> Toast {
+        Toast::lasting(text, TOAST_DURATION)
+    }
+
+    /// A message that stays longer, for warnings.
+    pub fn lasting(text: String, duration: Duration) -> Toast {
+        Toast { text, until: Instant::now() + duration }
+    }
+
+    /// When the message disappears.
+    pub fn until(&self) -> Instant {
+        self.until
+    }
+
+    /// Draws the message centered near the bottom of a frame `width`×`height`
+    /// pixels large, on a dark box so it's readable over any lava.
+    pub fn draw(&self, frame: &mut [u8], width: usize, height: usize) {
+        draw_boxed(frame, width, height, &self.text, false);
+    }
+}
+
+/// Draws a line that stays put, centered at the top of the frame, on a dark
+/// box like a toast. For readouts like the difference to the baseline.
+pub fn draw_banner(frame: &mut [u8], width: usize, height: usize, text: &str) {
+    draw_boxed(frame, width, height, text, true);
+}
+
+fn draw_boxed(frame: &mut [u8], width: usize, height: usize, text: &str, top: bool) {
+    // Auf einer 128er-Lampe 2 px pro Font-Pixel, damit es von weitem lesbar ist;
+    // lange Meldungen werden kleiner, bevor sie abgeschnitten werden
+    let mut scale = (height / WINDOW_SIZE).max(1) * 2;
+    while scale > 1 && text_width(text, scale) + 4 * scale > width {
+        scale -= 1;
+    }
+    let padding = 2 * scale;
+    let text_width = text_width(text, scale).min(width);
+    let box_width = (text_width + 2 * padding).min(width);
+    let box_height = (GLYPH_HEIGHT * scale + 2 * padding).min(height);
+    let x = (width - box_width) / 2;
+    let y = if top { (height / 16).min(height - box_height) } else { height.saturating_sub(box_height + height / 8) };
+
+    dim_box(frame, width, x, y, box_width, box_height);
+    draw_text(frame, width, x + padding, y + padding, scale, text, [255, 255, 255]);
+}
+
+/// Darkens a `box_width`×`box_height` box at (`x`, `y`) so text on it is readable.
+pub fn dim_box(frame: &mut [u8], width: usize, x: usize, y: usize, box_width: usize, box_height: usize) {
+    for row in y..y + box_height {
+        for pixel in frame[(row * width + x) * 4..(row * width + x + box_width) * 4].chunks_exact_mut(4) {
+            for channel in &mut pixel[..3] {
+                *channel /= 4;
+            }
+        }
+    }
+}
+
+/// Width of `text` drawn at `scale`, in pixels.
+pub fn text_width(text: &str, scale: usize) -> usize {
+    let count = text.chars().count();
+    (count * (GLYPH_WIDTH + 1)).saturating_sub(1) * scale
+}
+
+/// Draws `text` with its top left corner at (`x`, `y`) into a frame `width`
+/// pixels wide, each font pixel `scale`×`scale` large. Whatever doesn't fit is cut off.
+pub fn draw_text(frame: &mut [u8], width: usize, x: usize, y: usize, scale: usize, text: &str, color: [u8; 3]) {
+    let height = frame.len() / 4 / width.max(1);
+    for (i, c) in text.chars().enumerate() {
+        let rows = glyph(c);
+        let left = x + i * (GLYPH_WIDTH + 1) * scale;
+        for (gy, bits) in rows.iter().enumerate() {
+            for gx in 0..GLYPH_WIDTH {
+                if bits & (0b10000 >> gx) == 0 {
+                    continue;
+                }
+                for py in y + gy * scale..y + (gy + 1) * scale {
+                    for px in left + gx * scale..left + (gx + 1) * scale {
+                        if px < width && py < height {
+                            frame[(py * width + px) * 4..][..3].copy_from_slice(&color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Rows of `c`, top to bottom, 5 bits each with the leftmost pixel in the high bit.
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        '×' => [0, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0],
+        'Y' => [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        '.' => [0, 0, 0, 0, 0, 0b01100, 0b01100],
+        ',' => [0, 0, 0, 0, 0b01100, 0b00100, 0b01000],
+        ':' => [0, 0b01100, 0b01100, 0, 0b01100, 0b01100, 0],
+        '%' => [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011],
+        '+' => [0, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0],
+        '-' => [0, 0, 0, 0b11111, 0, 0, 0],
+        '~' => [0, 0, 0b01000, 0b10101, 0b00010, 0, 0],
+        '/' => [0, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0],
+        '!' => [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0, 0b00100],
+        '?' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0, 0b00100],
+        '[' => [0b01110, 0b01000, 0b01000, 0b01000, 0b01000, 0b01000, 0b01110],
+        ']' => [0b01110, 0b00010, 0b00010, 0b00010, 0b00010, 0b00010, 0b01110],
+        // Leerzeichen und alles, wofür es keine Glyphe gibt
+        _ => [0; GLYPH_HEIGHT],
+    }
+}
@@ -0,0 +1,112 @@
+//! `ram-lavalampe pack <dir> -o <sheet.png>`: assembles numbered frames
+//! (`frame_1.png`, `frame_2.png`, … or `0001.png`, …) into a strip the lamp
+//! can load, plus a TOML sidecar recording how it was made.
+//!
+//! Frames are ordered by the number in their name, so `frame_10` comes after
+//! `frame_9` without zero padding; PNGs without a number (like a sheet packed
+//! earlier) are skipped. All frames must have the same size; they
+//! are scaled to the frame size of the strip (128 unless `--size` says
+//! otherwise), with nearest neighbor when enlarging so pixel art stays crisp.
+
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+use crate::{ANIMATION_FRAMES, WINDOW_SIZE};
+
+/// Default output file.
+pub const DEFAULT_OUTPUT: &str = "sheet.png";
+
+/// Contents of the sidecar `<sheet>.toml`. The lamp doesn't read it; it's a
+/// record for theme authors of what went into the sheet.
+#[derive(Serialize)]
+struct Sidecar {
+    frames: usize,
+    frame_size: u32,
+    /// Size of the frames before scaling, like `32x32`.
+    source_size: String,
+    sources: Vec<String>,
+}
+
+/// Packs the PNGs in `dir` into `output` with `size`×`size` frames
+/// (128 if `None`). Returns the path of the sidecar.
+pub fn run(dir: &Path, output: &Path, size: Option<u32>) -> Result<PathBuf, String> {
+    let frames = numbered_frames(dir)?;
+    if frames.is_empty() {
+        return Err(tr!("pack-no-frames", dir = dir.display()));
+    }
+    if frames.len() > ANIMATION_FRAMES {
+        return Err(tr!("pack-too-many-frames", frames = frames.len(), max = ANIMATION_FRAMES));
+    }
+
+    let size = size.unwrap_or(WINDOW_SIZE as u32);
+    if size < WINDOW_SIZE as u32 {
+        return Err(tr!("pack-size-too-small", size = size, min = WINDOW_SIZE));
+    }
+    let mut strip = RgbaImage::new(frames.len() as u32 * size, size);
+    let mut source_size = None;
+    for (i, path) in frames.iter().enumerate() {
+        let frame = image::open(path).map_err(|e| format!("{}: {}", path.display(), e))?.to_rgba8();
+        let (width, height) = frame.dimensions();
+        match source_size {
+            None => source_size = Some((width, height)),
+            Some((first_width, first_height)) if (first_width, first_height) != (width, height) => {
+                let message = tr!(
+                    "pack-size-differs",
+                    size = format!("{}×{}", width, height),
+                    first = format!("{}×{}", first_width, first_height)
+                );
+                return Err(format!("{}: {}", path.display(), message));
+            }
+            Some(_) => {}
+        }
+
+        let filter = if frame.height() < size { FilterType::Nearest } else { FilterType::CatmullRom };
+        let frame = if frame.dimensions() == (size, size) { frame } else { imageops::resize(&frame, size, size, filter) };
+        imageops::replace(&mut strip, &frame, (i as u32 * size) as i64, 0);
+    }
+    strip.save(output).map_err(|e| format!("{}: {}", output.display(), e))?;
+
+    let sidecar = Sidecar {
+        frames: frames.len(),
+        frame_size: size,
+        source_size: source_size.map(|(width, height)| format!("{}x{}", width, height)).unwrap_or_default(),
+        sources: frames.iter().filter_map(|path| path.file_name()).map(|name| name.to_string_lossy().into_owned()).collect(),
+    };
+    let sidecar_path = output.with_extension("toml");
+    let text = format!(
+        "# {}\n{}",
+        tr!("pack-sidecar-comment", dir = dir.display()),
+        toml::to_string_pretty(&sidecar).map_err(|e| e.to_string())?
+    );
+    fs::write(&sidecar_path, text).map_err(|e| format!("{}: {}", sidecar_path.display(), e))?;
+    Ok(sidecar_path)
+}
+
+/// The PNGs in `dir`, ordered by the last number in their name.
+fn numbered_frames(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let mut frames = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if !path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png")) {
+            continue;
+        }
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        let Some(number) = last_number(&stem) else {
+            println!("{}", tr!("pack-skipped", path = path.display()));
+            continue;
+        };
+        frames.push((number, stem, path));
+    }
+    frames.sort();
+    Ok(frames.into_iter().map(|(_, _, path)| path).collect())
+}
+
+fn last_number(stem: &str) -> Option<u64> {
+    let end = stem.rfind(|c: char| c.is_ascii_digit())? + 1;
+    let start = stem[..end].rfind(|c: char| !c.is_ascii_digit()).map_or(0, |i| i + 1);
+    stem[start..end].parse().ok()
+}
@@ -0,0 +1,109 @@
+//! Palette remapping: `[assets] palette` replaces colors in every sprite as
+//! it's loaded, so one art set can match any desktop theme without editing
+//! the images.
+//!
+//! With `palette_tolerance` above 0, shades near a source color are replaced
+//! too. A pixel keeps its difference to the source color, so highlights and
+//! shadows carry over to the replacement.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+
+use crate::i18n::tr;
+
+/// An RGB color, written as `"#rrggbb"` (or `"#rgb"`) in the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Color(pub [u8; 3]);
+
+impl std::str::FromStr for Color {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Color, String> {
+        let invalid = || tr!("palette-bad-color", color = text);
+        let hex = text.trim().strip_prefix('#').unwrap_or(text.trim());
+        if !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let channel = |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| invalid());
+        match hex.len() {
+            6 => Ok(Color([channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?])),
+            // #abc ist #aabbcc
+            3 => Ok(Color([channel(&hex[0..1])? * 17, channel(&hex[1..2])? * 17, channel(&hex[2..3])? * 17])),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let [r, g, b] = self.0;
+        write!(f, "#{:02x}{:02x}{:02x}", r, g, b)
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The replacements of `[assets] palette`, ready to apply to pixels.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Palette {
+    entries: Vec<([u8; 3], [u8; 3])>,
+    tolerance: u8,
+}
+
+impl Palette {
+    pub fn new(map: &BTreeMap<Color, Color>, tolerance: u8) -> Palette {
+        Palette { entries: map.iter().map(|(from, to)| (from.0, to.0)).collect(), tolerance }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Identifies the palette in cached copies of recolored sheets; 0 if empty.
+    pub fn fingerprint(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+        let mut hasher = DefaultHasher::new();
+        self.entries.hash(&mut hasher);
+        self.tolerance.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Recolors RGBA `pixels` in place. A pixel within the tolerance of
+    /// several source colors takes the closest one.
+    pub fn apply(&self, pixels: &mut [u8]) {
+        if self.is_empty() {
+            return;
+        }
+        for pixel in pixels.chunks_exact_mut(4) {
+            if pixel[3] == 0 {
+                continue;
+            }
+            let distance = |from: &[u8; 3]| (0..3).map(|c| pixel[c].abs_diff(from[c])).max().unwrap_or(0);
+            let Some((from, to)) = self
+                .entries
+                .iter()
+                .filter(|(from, _)| distance(from) <= self.tolerance)
+                .min_by_key(|(from, _)| distance(from))
+            else {
+                continue;
+            };
+            for c in 0..3 {
+                pixel[c] = (to[c] as i16 + pixel[c] as i16 - from[c] as i16).clamp(0, 255) as u8;
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! A short burst of particles when a lamp changes color, so the change is
+//! noticeable from across the room: sparks flying out of the lamp when it
+//! gets hotter, bubbles drifting up when it calms down.
+//!
+//! Particles live in the 128×128 coordinates of a lamp and are scaled to the
+//! lamp size when drawn. While any are alive the lamp redraws at about 60
+//! frames per second; afterwards it goes back to sleeping between frames.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::tier::Tier;
+use crate::WINDOW_SIZE;
+
+/// Time between redraws while particles are moving.
+pub const STEP: Duration = Duration::from_millis(16);
+
+/// Where sparks start: the middle of the glass.
+const SPARK_ORIGIN: (f32, f32) = (64.0, 56.0);
+/// Where bubbles start: just above the base.
+const BUBBLE_ORIGIN: (f32, f32) = (64.0, 96.0);
+/// Pull on sparks in px/s², so they fall back in an arc.
+const GRAVITY: f32 = 140.0;
+
+struct Particle {
+    slot: usize,
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    age: f32,
+    life: f32,
+    color: [u8; 3],
+    spark: bool,
+}
+
+/// All live particles of the window.
+pub struct Particles {
+    live: Vec<Particle>,
+    /// State of the xorshift generator scattering the particles.
+    seed: u32,
+}
+
+impl Default for Particles {
+    fn default() -> Self {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.subsec_nanos());
+        Particles { live: Vec::new(), seed: nanos | 1 }
+    }
+}
+
+impl Particles {
+    /// Starts a burst of `count` particles in lamp slot `slot` for a change
+    /// from `from` to `to`.
+    pub fn burst(&mut self, slot: usize, from: Tier, to: Tier, count: usize) {
+        let spark = to > from;
+        let [r, g, b, _] = to.debug_color();
+        for _ in 0..count {
+            let particle = if spark {
+                let angle = self.random(0.0, std::f32::consts::TAU);
+                let speed = self.random(40.0, 95.0);
+                Particle {
+                    slot,
+                    x: SPARK_ORIGIN.0,
+                    y: SPARK_ORIGIN.1,
+                    vx: angle.cos() * speed,
+                    // Etwas mehr nach oben, damit die Funken über die Lampe fliegen
+                    vy: angle.sin() * speed - 40.0,
+                    age: 0.0,
+                    life: self.random(0.5, 0.9),
+                    color: [r, g, b],
+                    spark,
+                }
+            } else {
+                Particle {
+                    slot,
+                    x: BUBBLE_ORIGIN.0 + self.random(-14.0, 14.0),
+                    y: BUBBLE_ORIGIN.1 + self.random(-6.0, 6.0),
+                    vx: 0.0,
+                    vy: -self.random(25.0, 55.0),
+                    age: 0.0,
+                    life: self.random(1.0, 1.6),
+                    color: [r, g, b],
+                    spark,
+                }
+            };
+            self.live.push(particle);
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.live.is_empty()
+    }
+
+    /// Moves every particle by `elapsed` and drops the ones that burned out.
+    /// Returns whether there was anything to move, i.e. the frame changed.
+    pub fn tick(&mut self, elapsed: Duration) -> bool {
+        if self.live.is_empty() {
+            return false;
+        }
+        let dt = elapsed.as_secs_f32();
+        for particle in &mut self.live {
+            particle.age += dt;
+            if particle.spark {
+                particle.vy += GRAVITY * dt;
+            } else {
+                // Blasen schlingern beim Aufsteigen
+                particle.vx = (particle.age * 6.0 + particle.life * 10.0).sin() * 8.0;
+            }
+            particle.x += particle.vx * dt;
+            particle.y += particle.vy * dt;
+        }
+        self.live.retain(|particle| particle.age < particle.life);
+        true
+    }
+
+    /// Draws the particles into a frame that is `slots` lamps wide and `side`
+    /// pixels high. Sparks add light, bubbles are translucent rings.
+    pub fn draw(&self, frame: &mut [u8], slots: usize, side: usize) {
+        let scale = side as f32 / WINDOW_SIZE as f32;
+        let frame_width = slots * side;
+        for particle in self.live.iter().filter(|particle| particle.slot < slots) {
+            let fade = 1.0 - particle.age / particle.life;
+            let radius = if particle.spark { 1.2 } else { 2.5 } * scale;
+            let (cx, cy) = (particle.x * scale, particle.y * scale);
+            let reach = radius.ceil() as isize + 1;
+            for dy in -reach..=reach {
+                for dx in -reach..=reach {
+                    let (x, y) = (cx as isize + dx, cy as isize + dy);
+                    if x < 0 || y < 0 || x >= side as isize || y >= side as isize {
+                        continue;
+                    }
+                    let distance = ((x as f32 + 0.5 - cx).powi(2) + (y as f32 + 0.5 - cy).powi(2)).sqrt();
+                    let coverage = if particle.spark {
+                        (radius + 0.5 - distance).clamp(0.0, 1.0)
+                    } else {
+                        // Nur der Rand der Blase ist sichtbar
+                        (1.0 - (distance - radius).abs() / scale.max(1.0)).clamp(0.0, 1.0) * 0.7
+                    };
+                    let alpha = coverage * fade;
+                    if alpha <= 0.0 {
+                        continue;
+                    }
+
+                    let i = (y as usize * frame_width + particle.slot * side + x as usize) * 4;
+                    for (channel, &color) in frame[i..i + 3].iter_mut().zip(&particle.color) {
+                        let (old, color) = (*channel as f32, color as f32);
+                        *channel = if particle.spark {
+                            (old + color * alpha).min(255.0) as u8
+                        } else {
+                            (old + (color - old) * alpha) as u8
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    /// Uniform random number in `low..high`.
+    fn random(&mut self, low: f32, high: f32) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        low + (high - low) * (self.seed as f32 / u32::MAX as f32)
+    }
+}
@@ -0,0 +1,44 @@
+//! Per-user directories, following each platform's conventions:
+//!
+//! |        | Linux (XDG)                         | macOS                                      | Windows                             |
+//! |--------|-------------------------------------|--------------------------------------------|-------------------------------------|
+//! | config | `$XDG_CONFIG_HOME/ram-lavalampe`    | `~/Library/Application Support/<bundle>`   | `%APPDATA%\E-Geraet\ram-lavalampe\config`   |
+//! | data   | `$XDG_DATA_HOME/ram-lavalampe`      | `~/Library/Application Support/<bundle>`   | `%APPDATA%\E-Geraet\ram-lavalampe\data`     |
+//! | state  | `$XDG_STATE_HOME/ram-lavalampe`     | same as data                               | `%LOCALAPPDATA%\E-Geraet\ram-lavalampe\data`|
+//! | cache  | `$XDG_CACHE_HOME/ram-lavalampe`     | `~/Library/Caches/<bundle>`                | `%LOCALAPPDATA%\E-Geraet\ram-lavalampe\cache`|
+//!
+//! `<bundle>` is `io.github.E-Geraet.ram-lavalampe`. Unset XDG variables fall
+//! back to `~/.config`, `~/.local/share`, `~/.local/state` and `~/.cache`;
+//! Flatpak sets them to the sandbox's own directories.
+
+use directories::{BaseDirs, ProjectDirs};
+use std::path::PathBuf;
+
+fn project() -> Option<ProjectDirs> {
+    ProjectDirs::from("io.github", "E-Geraet", "ram-lavalampe")
+}
+
+/// The config file and nothing else.
+pub fn config_dir() -> Option<PathBuf> {
+    project().map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+/// Files the user adds: themes, plugins, crash reports.
+pub fn data_dir() -> Option<PathBuf> {
+    project().map(|dirs| dirs.data_dir().to_path_buf())
+}
+
+/// Logs. Only Linux has a separate state directory; elsewhere the local data directory.
+pub fn state_dir() -> Option<PathBuf> {
+    project().map(|dirs| dirs.state_dir().unwrap_or(dirs.data_local_dir()).to_path_buf())
+}
+
+/// Raw sprite copies; can be deleted at any time.
+pub fn cache_dir() -> Option<PathBuf> {
+    project().map(|dirs| dirs.cache_dir().to_path_buf())
+}
+
+/// `$XDG_CONFIG_HOME/autostart` (XDG desktops only).
+pub fn autostart_dir() -> Option<PathBuf> {
+    BaseDirs::new().map(|dirs| dirs.config_dir().join("autostart"))
+}
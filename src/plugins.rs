@@ -0,0 +1,104 @@
+//! Third-party metric providers, loaded from `plugins/*.so` in the data
+//! directory (`.dylib`/`.dll` elsewhere) at startup.
+//!
+//! A plugin is a `cdylib` exporting one C function:
+//!
+//! ```c
+//! typedef struct {
+//!     uint32_t abi_version;        /* LAVALAMPE_PLUGIN_ABI_VERSION (1) */
+//!     const char *name;            /* unique, used as `metrics.plugin` in the config */
+//!     double (*sample)(void);      /* 0..100, negative = no value right now */
+//! } LavalampePlugin;
+//!
+//! const LavalampePlugin *lavalampe_plugin_v1(void);
+//! ```
+//!
+//! The returned struct and name must stay valid until the process exits;
+//! `sample` is only ever called from the main thread.
+
+use libloading::{Library, Symbol};
+use std::env;
+use std::ffi::{c_char, CStr};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+use crate::paths;
+
+/// Bumped on any incompatible change of [`PluginInfo`].
+pub const ABI_VERSION: u32 = 1;
+const ENTRY_POINT: &[u8] = b"lavalampe_plugin_v1\0";
+
+#[repr(C)]
+pub struct PluginInfo {
+    pub abi_version: u32,
+    pub name: *const c_char,
+    pub sample: extern "C" fn() -> f64,
+}
+
+pub struct Plugin {
+    pub name: String,
+    sample: extern "C" fn() -> f64,
+    // Hält den Code hinter `sample` geladen
+    _library: Library,
+}
+
+impl Plugin {
+    /// Current value in percent, `None` if the plugin has nothing to report.
+    pub fn sample(&self) -> Option<f64> {
+        let value = (self.sample)();
+        (value.is_finite() && value >= 0.0).then(|| value.min(100.0))
+    }
+
+    fn load(path: &Path) -> Result<Plugin, String> {
+        // SAFETY: loading a library runs its initializers; plugins are trusted like
+        // any other program the user installs into their data folder.
+        unsafe {
+            let library = Library::new(path).map_err(|e| e.to_string())?;
+            let entry: Symbol<extern "C" fn() -> *const PluginInfo> =
+                library.get(ENTRY_POINT).map_err(|e| e.to_string())?;
+            let info = entry().as_ref().ok_or_else(|| tr!("plugin-no-info"))?;
+            if info.abi_version != ABI_VERSION {
+                return Err(tr!("plugin-wrong-abi", found = info.abi_version, expected = ABI_VERSION));
+            }
+            if info.name.is_null() {
+                return Err(tr!("plugin-no-info"));
+            }
+            let name = CStr::from_ptr(info.name).to_string_lossy().into_owned();
+            Ok(Plugin { name, sample: info.sample, _library: library })
+        }
+    }
+}
+
+/// `plugins` in the data directory, see [`paths`](crate::paths).
+pub fn plugin_dir() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join("plugins"))
+}
+
+/// Loads every plugin in the plugin folder. Broken plugins are reported and skipped.
+pub fn load_all() -> Vec<Plugin> {
+    let Some(dir) = plugin_dir() else { return Vec::new() };
+    let Ok(entries) = fs::read_dir(&dir) else { return Vec::new() };
+
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == env::consts::DLL_EXTENSION))
+        .collect();
+    paths.sort();
+
+    let mut plugins: Vec<Plugin> = Vec::new();
+    for path in paths {
+        match Plugin::load(&path) {
+            Ok(plugin) if plugins.iter().any(|p| p.name == plugin.name) => {
+                log::error!("{}", tr!("plugin-duplicate", name = plugin.name, path = path.display()));
+            }
+            Ok(plugin) => {
+                log::info!("{}", tr!("plugin-loaded", name = plugin.name, path = path.display()));
+                plugins.push(plugin);
+            }
+            Err(e) => log::error!("{}", tr!("plugin-load-failed", path = path.display(), error = e)),
+        }
+    }
+    plugins
+}
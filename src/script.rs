@@ -0,0 +1,149 @@
+//! Optional user script (Rhai) that decides how a sample is shown.
+//!
+//! The script defines `fn sample(percent, metric, hour)` and returns either
+//! - a tier name: `"green"`, `"yellow"`, `"orange"`, `"red"`,
+//! - a number: the percentage to use instead (mapped with the thresholds), or
+//! - a map with any of `tier`, `percent` and `frame_ms` (time per animation frame).
+//!
+//! Returning `()` keeps the default mapping for that sample.
+
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::clock;
+use crate::config::Config;
+use crate::i18n::tr;
+use crate::tier::{Extreme, Tier};
+
+/// Guards the render loop against runaway scripts (`loop {}`).
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// How a sample is displayed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mapping {
+    pub tier: Tier,
+    pub frame_duration: Duration,
+    /// Boiling over or asleep, at the ends of the scale.
+    pub extreme: Option<Extreme>,
+}
+
+impl Mapping {
+    /// The built-in mapping: thresholds decide the tier, the tier (or with
+    /// `[speed] continuous` the percentage) decides the speed.
+    pub fn default_for(percent: f64, config: &Config) -> Mapping {
+        let thresholds = config.active_thresholds();
+        let tier = Tier::from_percent(percent, thresholds);
+        let extreme = match tier {
+            Tier::Red if thresholds.boiling.is_some_and(|boiling| percent > boiling) => Some(Extreme::Boiling),
+            Tier::Green if thresholds.idle.is_some_and(|idle| percent < idle) => Some(Extreme::Sleeping),
+            _ => None,
+        };
+        Mapping { tier, frame_duration: config.speed.frame_duration(percent, tier), extreme }
+    }
+
+    /// The sheet to show: the tier's, or the theme's own sheet for the
+    /// extreme if `extreme_sheets` has it.
+    pub fn sprite_file(&self, extreme_sheets: &[Extreme]) -> &'static str {
+        match self.extreme {
+            Some(extreme) if extreme_sheets.contains(&extreme) => extreme.sprite_file(),
+            _ => self.tier.sprite_file(),
+        }
+    }
+
+    /// Asleep without a sheet for it: the animation stands still and the lamp
+    /// pulses slowly instead.
+    pub fn dozing(&self, extreme_sheets: &[Extreme]) -> bool {
+        self.extreme == Some(Extreme::Sleeping) && !extreme_sheets.contains(&Extreme::Sleeping)
+    }
+}
+
+pub struct Script {
+    path: PathBuf,
+    engine: Engine,
+    ast: AST,
+    /// Last runtime error, so a broken script logs once instead of on every sample.
+    last_error: Option<String>,
+}
+
+impl Script {
+    pub fn load(path: &Path) -> Result<Script, String> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        let ast = engine.compile_file(path.to_path_buf()).map_err(|e| e.to_string())?;
+        if !ast.iter_functions().any(|f| f.name == "sample" && f.params.len() == 3) {
+            return Err(tr!("script-no-function"));
+        }
+        Ok(Script { path: path.to_path_buf(), engine, ast, last_error: None })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Runs the script for one sample. Errors fall back to the default mapping.
+    pub fn map(&mut self, percent: f64, metric: &str, config: &Config) -> Mapping {
+        let default = Mapping::default_for(percent, config);
+        let args = (percent, metric.to_string(), clock::local_minutes() as i64 / 60);
+        let result = self
+            .engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &self.ast, "sample", args)
+            .map_err(|e| e.to_string())
+            .and_then(|value| interpret(value, default, config));
+
+        match result {
+            Ok(mapping) => {
+                self.last_error = None;
+                mapping
+            }
+            Err(error) => {
+                if self.last_error.as_ref() != Some(&error) {
+                    log::error!("{}", tr!("script-failed", path = self.path.display(), error = &error));
+                    self.last_error = Some(error);
+                }
+                default
+            }
+        }
+    }
+}
+
+fn interpret(value: Dynamic, default: Mapping, config: &Config) -> Result<Mapping, String> {
+    if value.is_unit() {
+        return Ok(default);
+    }
+    if let Some(percent) = as_number(&value) {
+        return Ok(Mapping::default_for(percent, config));
+    }
+    if value.is_string() {
+        return tier_from(&value).map(|tier| Mapping { tier, frame_duration: config.speed.tier_duration(tier), extreme: None });
+    }
+
+    let map = value.try_cast::<Map>().ok_or_else(|| tr!("script-bad-result"))?;
+    let mut mapping = match map.get("percent") {
+        Some(percent) => Mapping::default_for(as_number(percent).ok_or_else(|| tr!("script-bad-result"))?, config),
+        None => default,
+    };
+    if let Some(tier) = map.get("tier") {
+        let tier = tier_from(tier)?;
+        // Eine andere Farbe als aus dem Prozentwert passt nicht mehr zu dessen Extrem
+        if tier != mapping.tier {
+            mapping.extreme = None;
+        }
+        mapping.tier = tier;
+        mapping.frame_duration = config.speed.tier_duration(tier);
+    }
+    if let Some(frame_ms) = map.get("frame_ms") {
+        let frame_ms = as_number(frame_ms).filter(|ms| *ms > 0.0).ok_or_else(|| tr!("script-bad-result"))?;
+        mapping.frame_duration = Duration::from_secs_f64(frame_ms / 1000.0);
+    }
+    Ok(mapping)
+}
+
+fn as_number(value: &Dynamic) -> Option<f64> {
+    value.as_float().ok().or_else(|| value.as_int().ok().map(|i| i as f64))
+}
+
+fn tier_from(value: &Dynamic) -> Result<Tier, String> {
+    let name = value.clone().into_string().map_err(|_| tr!("script-bad-result"))?;
+    Tier::from_name(&name).ok_or_else(|| tr!("script-unknown-tier", tier = name))
+}
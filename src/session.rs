@@ -0,0 +1,165 @@
+//! Statistics of one run, printed on exit (and written as JSON with
+//! `[session] summary_file`), so even a casual run tells you something:
+//! how full memory got, how long it spent in each color and how often it
+//! went red.
+//!
+//! With several lamps the fullest one counts, like in crash reports.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::i18n::tr;
+use crate::logging::format_timestamp;
+use crate::tier::Tier;
+
+/// The `[session]` config section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Print the summary to stdout on exit.
+    pub summary: bool,
+    /// Also write it as JSON here; relative to the config folder.
+    pub summary_file: Option<PathBuf>,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        SessionConfig { summary: true, summary_file: None }
+    }
+}
+
+/// What the summary is made of, collected sample by sample.
+pub struct Session {
+    started: Instant,
+    started_at: SystemTime,
+    metric: String,
+    samples: u64,
+    sum: f64,
+    max: f64,
+    tier_time: [Duration; 4],
+    /// Tier of the previous sample.
+    tier: Option<Tier>,
+    /// Time of the previous sample; `None` after a pause.
+    last_sample: Option<Instant>,
+    red_excursions: u32,
+}
+
+/// The JSON written to `summary_file`.
+#[derive(Serialize)]
+struct Summary<'a> {
+    started: String,
+    duration_secs: f64,
+    metric: &'a str,
+    samples: u64,
+    average_percent: f64,
+    max_percent: f64,
+    /// Seconds per tier, by color name.
+    time_per_tier: BTreeMap<&'static str, f64>,
+    red_excursions: u32,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Session {
+            started: Instant::now(),
+            started_at: SystemTime::now(),
+            metric: String::new(),
+            samples: 0,
+            sum: 0.0,
+            max: 0.0,
+            tier_time: [Duration::ZERO; 4],
+            tier: None,
+            last_sample: None,
+            red_excursions: 0,
+        }
+    }
+}
+
+impl Session {
+    /// Takes the peak of one sample and the tier it's shown in.
+    pub fn record(&mut self, metric: &str, percent: f64, tier: Tier) {
+        let now = Instant::now();
+        if let (Some(last_sample), Some(last_tier)) = (self.last_sample, self.tier) {
+            self.tier_time[last_tier as usize] += now - last_sample;
+        }
+        if tier == Tier::Red && self.tier != Some(Tier::Red) {
+            self.red_excursions += 1;
+        }
+        self.tier = Some(tier);
+        self.last_sample = Some(now);
+
+        self.metric = metric.to_string();
+        self.samples += 1;
+        self.sum += percent;
+        self.max = self.max.max(percent);
+    }
+
+    /// Monitoring was paused: the time until the next sample doesn't count.
+    pub fn interrupt(&mut self) {
+        self.last_sample = None;
+    }
+
+    /// Prints the summary and writes it to `path` as JSON, as configured.
+    pub fn finish(&self, config: &SessionConfig, path: Option<&Path>) {
+        if self.samples == 0 {
+            return;
+        }
+        let average = self.sum / self.samples as f64;
+        // Die Farbe des letzten Samples gilt bis jetzt
+        let mut tier_time = self.tier_time;
+        if let (Some(last_sample), Some(tier)) = (self.last_sample, self.tier) {
+            tier_time[tier as usize] += last_sample.elapsed();
+        }
+        if config.summary {
+            println!("{}", tr!("session-header"));
+            println!("{}", tr!("session-duration", duration = format_duration(self.started.elapsed())));
+            println!(
+                "{}",
+                tr!(
+                    "session-percent",
+                    metric = &self.metric,
+                    average = format!("{:.1}", average),
+                    max = format!("{:.1}", self.max)
+                )
+            );
+            let per_tier: Vec<String> = Tier::ALL
+                .iter()
+                .map(|tier| format!("{} {}", tr!(tier.color_key()), format_duration(tier_time[*tier as usize])))
+                .collect();
+            println!("{}", tr!("session-tiers", tiers = per_tier.join(", ")));
+            println!("{}", tr!("session-red-excursions", count = self.red_excursions));
+        }
+
+        let Some(path) = path else { return };
+        let summary = Summary {
+            started: format_timestamp(self.started_at),
+            duration_secs: self.started.elapsed().as_secs_f64(),
+            metric: &self.metric,
+            samples: self.samples,
+            average_percent: average,
+            max_percent: self.max,
+            time_per_tier: Tier::ALL.iter().map(|tier| (tier.name(), tier_time[*tier as usize].as_secs_f64())).collect(),
+            red_excursions: self.red_excursions,
+        };
+        let written = serde_json::to_string_pretty(&summary)
+            .map_err(|e| e.to_string())
+            .and_then(|json| fs::write(path, json + "\n").map_err(|e| e.to_string()));
+        match written {
+            Ok(()) => println!("{}", tr!("session-written", path = path.display())),
+            Err(e) => eprintln!("{}", tr!("session-write-failed", path = path.display(), error = e)),
+        }
+    }
+}
+
+/// `1h 02m 05s`, `3m 10s` or `42s`.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {:02}s", m, s),
+        (h, m, s) => format!("{}h {:02}m {:02}s", h, m, s),
+    }
+}
@@ -0,0 +1,747 @@
+//! Sprite sheets: loading, validation and how the frames are kept in memory.
+//!
+//! By default a sheet is decoded into one RGBA buffer (about 11 MB per color
+//! for 169 frames). With `storage = "mmap"` it is converted once into a raw
+//! file in the cache directory, one frame after another, and memory-mapped:
+//! only the frames actually shown are paged in, and the kernel can drop them
+//! again under memory pressure instead of swapping. With `storage = "on-demand"`
+//! only the compressed PNG stays in memory and a few frames at a time are
+//! decoded from it as the animation reaches them.
+
+use image::imageops::{self, FilterType};
+use image::io::Reader as ImageReader;
+use image::RgbaImage;
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::i18n::tr;
+use crate::palette::{Color, Palette};
+use crate::config;
+use crate::tier::{Extreme, Tier};
+use crate::{aseprite, assets, lottie, paths, svg};
+use crate::{ANIMATION_FRAMES, WINDOW_SIZE};
+
+/// Bytes of one frame row.
+const ROW_BYTES: usize = WINDOW_SIZE * 4;
+/// Bytes of one frame.
+const FRAME_BYTES: usize = ROW_BYTES * WINDOW_SIZE;
+
+const RAW_MAGIC: &[u8; 8] = b"LAVARAW2";
+/// Magic, frame count, source modification time, source size and
+/// [palette fingerprint](Palette::fingerprint).
+const RAW_HEADER_LEN: usize = 8 + 8 + 8 + 8 + 8;
+
+/// Frames decoded per pass in on-demand mode. Every pass inflates the whole
+/// PNG, so this trades memory (64 KB per frame) against CPU.
+const DECODE_WINDOW_FRAMES: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SpriteStorage {
+    /// Decode every sheet into memory.
+    Decoded,
+    /// Memory-map a raw copy of each sheet from the cache directory.
+    Mmap,
+    /// Keep the PNG and decode a few frames at a time.
+    OnDemand,
+}
+
+impl SpriteStorage {
+    /// Name as written in the config file.
+    pub fn id(&self) -> &'static str {
+        match self {
+            SpriteStorage::Decoded => "decoded",
+            SpriteStorage::Mmap => "mmap",
+            SpriteStorage::OnDemand => "on-demand",
+        }
+    }
+}
+
+/// The `[assets]` config section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetConfig {
+    pub storage: SpriteStorage,
+    /// Memory the loaded sheets may take before the least recently used ones are dropped.
+    pub cache_budget_mb: u64,
+    /// Extra directories searched for sheets and themes, before the built-in ones.
+    #[serde(deserialize_with = "assets::deserialize_dirs")]
+    pub dirs: Vec<PathBuf>,
+    /// Fills of a shared `lavalampe.json` that take the tier's color.
+    pub lottie_keypath: String,
+    /// Colors replaced in every sheet as it's loaded, `"#source" = "#replacement"`.
+    pub palette: BTreeMap<Color, Color>,
+    /// How far (per channel, 0-255) a pixel may be from a palette source color
+    /// and still be replaced.
+    pub palette_tolerance: u8,
+    /// Files used instead of the theme's sheets, by name (`green` … `red`,
+    /// `boiling`, `sleeping`); relative to the config folder.
+    pub sheets: BTreeMap<String, PathBuf>,
+}
+
+impl Default for AssetConfig {
+    fn default() -> Self {
+        // Reicht für alle vier dekodierten Farben
+        AssetConfig {
+            storage: SpriteStorage::Decoded,
+            cache_budget_mb: 64,
+            dirs: Vec::new(),
+            lottie_keypath: "lava.**".into(),
+            palette: BTreeMap::new(),
+            palette_tolerance: 0,
+            sheets: BTreeMap::new(),
+        }
+    }
+}
+
+impl AssetConfig {
+    pub fn palette(&self) -> Palette {
+        Palette::new(&self.palette, self.palette_tolerance)
+    }
+
+    /// The file from `sheets` that replaces the sheet `filename`, if any.
+    fn custom_sheet(&self, filename: &str) -> Option<PathBuf> {
+        let (name, _) = named_sheets().find(|(_, file)| *file == filename)?;
+        self.sheets.get(name).map(|path| config::relative_to_config(path))
+    }
+}
+
+/// Names of the sheets `[assets.sheets]` can replace, with their files.
+pub fn named_sheets() -> impl Iterator<Item = (&'static str, &'static str)> {
+    let tiers = Tier::ALL.into_iter().map(|tier| (tier.name(), tier.sprite_file()));
+    tiers.chain(Extreme::ALL.into_iter().map(|extreme| (extreme.name(), extreme.sprite_file())))
+}
+
+/// The animation frames of one color.
+pub enum SpriteSheet {
+    /// The strip as in the PNG: frames side by side. `durations` are the
+    /// per-frame times of an Aseprite file, empty for PNGs.
+    Decoded { pixels: Vec<u8>, width: usize, durations: Vec<Duration> },
+    /// Raw frames one after another, after a [`RAW_HEADER_LEN`] byte header.
+    Mapped { map: Mmap, frames: usize },
+    /// The compressed PNG plus the frames `first..first + DECODE_WINDOW_FRAMES`
+    /// (wrapping around), one after another.
+    OnDemand { png: Cow<'static, [u8]>, width: usize, first: Option<usize>, window: Vec<u8>, palette: Palette },
+    /// An SVG template or Lottie animation plus the last frame rasterized
+    /// from it, `(frame, side)`.
+    Vector { art: VectorArt, rendered: Option<(usize, usize)>, pixels: Vec<u8>, palette: Palette },
+}
+
+/// What a [`SpriteSheet::Vector`] is rendered from.
+pub enum VectorArt {
+    Svg(svg::Template),
+    Lottie(lottie::Animation),
+}
+
+impl VectorArt {
+    fn frame_count(&self) -> usize {
+        match self {
+            VectorArt::Svg(template) => template.frame_count(),
+            VectorArt::Lottie(animation) => animation.frame_count(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            VectorArt::Svg(template) => template.len(),
+            VectorArt::Lottie(animation) => animation.len(),
+        }
+    }
+
+    fn render(&mut self, frame: usize, side: usize) -> Result<Vec<u8>, String> {
+        match self {
+            VectorArt::Svg(template) => template.render(frame, side),
+            VectorArt::Lottie(animation) => animation.render(frame, side),
+        }
+    }
+}
+
+/// Where a sheet is read from.
+enum Source {
+    File(PathBuf),
+    /// One of the classic sheets built into the executable.
+    Embedded(&'static str, &'static [u8]),
+}
+
+impl Source {
+    /// The sheet `filename` of `theme`. In the same place, an SVG of the same
+    /// name wins over a Lottie file of that name, then the shared
+    /// `lavalampe.json`, then an Aseprite file of that name, then the shared
+    /// `lavalampe.aseprite`, then the PNG.
+    fn locate(theme: Option<&str>, filename: &str, assets: &AssetConfig) -> Option<Source> {
+        // Eigene Dateien aus der Konfiguration gehen jedem Theme vor
+        if let Some(path) = assets.custom_sheet(filename) {
+            return Some(Source::File(path));
+        }
+        let with_extension = |extension| Path::new(filename).with_extension(extension).to_string_lossy().into_owned();
+        let (svg, json) = (with_extension("svg"), with_extension("json"));
+        let (aseprite, ase) = (with_extension("aseprite"), with_extension("ase"));
+        let [shared_aseprite, shared_ase] = aseprite::SHARED_FILES;
+        // Gemeinsame Dateien enthalten nur die vier Farben, keine Extra-Sheets wie "boiling"
+        let names: &[&str] = if Tier::ALL.iter().any(|tier| tier.sprite_file() == filename) {
+            &[&svg, &json, lottie::SHARED_FILE, &aseprite, &ase, shared_aseprite, shared_ase, filename]
+        } else {
+            &[&svg, &json, &aseprite, &ase, filename]
+        };
+        match assets::find(&assets.dirs, theme, names) {
+            Some(path) => Some(Source::File(path)),
+            None => assets::embedded(filename).map(|(name, bytes)| Source::Embedded(name, bytes)),
+        }
+    }
+
+    /// Width and height from the image header, without decoding.
+    fn dimensions(&self) -> image::ImageResult<(u32, u32)> {
+        match self {
+            Source::File(path) => ImageReader::open(path)?.with_guessed_format()?.into_dimensions(),
+            Source::Embedded(_, bytes) => ImageReader::new(Cursor::new(bytes)).with_guessed_format()?.into_dimensions(),
+        }
+    }
+
+    fn is_svg(&self) -> bool {
+        self.has_extension("svg")
+    }
+
+    fn is_lottie(&self) -> bool {
+        self.has_extension("json")
+    }
+
+    fn is_aseprite(&self) -> bool {
+        self.has_extension("aseprite") || self.has_extension("ase")
+    }
+
+    /// Whether this is a vector sheet, rendered at the size the lamp is shown at.
+    fn is_vector(&self) -> bool {
+        self.is_svg() || self.is_lottie()
+    }
+
+    fn has_extension(&self, extension: &str) -> bool {
+        matches!(self, Source::File(path) if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case(extension)))
+    }
+
+    fn read(&self) -> io::Result<Cow<'static, [u8]>> {
+        match self {
+            Source::File(path) => fs::read(path).map(Cow::Owned),
+            Source::Embedded(_, bytes) => Ok(Cow::Borrowed(bytes)),
+        }
+    }
+
+    /// Identifies the version of the PNG a raw copy was made from: modification
+    /// time and size of a file, or a hash and the size of the built-in sheet.
+    fn stamp(&self) -> io::Result<(u64, u64)> {
+        match self {
+            Source::File(path) => {
+                let metadata = fs::metadata(path)?;
+                let mtime = metadata.modified()?.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+                Ok((mtime, metadata.len()))
+            }
+            Source::Embedded(_, bytes) => {
+                // FNV-1a, nur um Sheets verschiedener Programmversionen zu unterscheiden
+                let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+                    (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+                });
+                Ok((hash, bytes.len() as u64))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Embedded(filename, _) => write!(f, "{} ({})", filename, tr!("asset-built-in")),
+        }
+    }
+}
+
+impl SpriteSheet {
+    pub fn frame_count(&self) -> usize {
+        match self {
+            SpriteSheet::Decoded { width, .. } => (width / WINDOW_SIZE).min(ANIMATION_FRAMES),
+            SpriteSheet::Mapped { frames, .. } => *frames,
+            SpriteSheet::OnDemand { width, .. } => (width / WINDOW_SIZE).min(ANIMATION_FRAMES),
+            SpriteSheet::Vector { art, .. } => art.frame_count(),
+        }
+    }
+
+    /// Side of the frames [`row`](Self::row) returns: the sprite size, or for
+    /// vector sheets the size given to the last [`prepare`](Self::prepare).
+    pub fn side(&self) -> usize {
+        match self {
+            SpriteSheet::Vector { rendered: Some((_, side)), .. } => *side,
+            _ => WINDOW_SIZE,
+        }
+    }
+
+    /// How long each frame is shown, if the sheet says so (Aseprite files).
+    /// Otherwise the lamp's speed applies to every frame.
+    pub fn frame_durations(&self) -> Option<&[Duration]> {
+        match self {
+            SpriteSheet::Decoded { durations, .. } if !durations.is_empty() => Some(durations),
+            _ => None,
+        }
+    }
+
+    /// Heap memory the sheet holds. Mapped frames live in the page cache,
+    /// which the kernel manages (and reclaims) on its own.
+    pub fn memory_size(&self) -> usize {
+        match self {
+            SpriteSheet::Decoded { pixels, .. } => pixels.len(),
+            SpriteSheet::Mapped { .. } => 0,
+            SpriteSheet::OnDemand { png: Cow::Owned(png), window, .. } => png.len() + window.len(),
+            SpriteSheet::OnDemand { window, .. } => window.len(),
+            SpriteSheet::Vector { art, pixels, .. } => art.len() + pixels.len(),
+        }
+    }
+
+    /// Makes sure `frame` can be read with [`row`](Self::row). On-demand sheets
+    /// decode it here, vector sheets rasterize it at `side`×`side`; `false` if that failed.
+    pub fn prepare(&mut self, frame: usize, side: usize) -> bool {
+        let frames = self.frame_count();
+        if let SpriteSheet::Vector { art, rendered, pixels, palette } = self {
+            if *rendered == Some((frame, side)) {
+                return true;
+            }
+            return match art.render(frame, side) {
+                Ok(mut frame_pixels) => {
+                    palette.apply(&mut frame_pixels);
+                    *rendered = Some((frame, side));
+                    *pixels = frame_pixels;
+                    true
+                }
+                Err(e) => {
+                    log::error!("{}", tr!("asset-frame-decode-failed", frame = frame, error = e));
+                    false
+                }
+            };
+        }
+        let SpriteSheet::OnDemand { png, width, first, window, palette } = self else { return true };
+
+        let in_window = first.is_some_and(|first| (frame + frames - first) % frames < DECODE_WINDOW_FRAMES);
+        if in_window {
+            return true;
+        }
+        match decode_frames(png, *width, frame, DECODE_WINDOW_FRAMES.min(frames)) {
+            Ok(mut decoded) => {
+                palette.apply(&mut decoded);
+                *first = Some(frame);
+                *window = decoded;
+                true
+            }
+            Err(e) => {
+                log::error!("{}", tr!("asset-frame-decode-failed", frame = frame, error = e));
+                false
+            }
+        }
+    }
+
+    /// Row `y` of frame `frame` as RGBA bytes.
+    pub fn row(&self, frame: usize, y: usize) -> &[u8] {
+        let start = match self {
+            SpriteSheet::Decoded { width, .. } => (y * width + frame * WINDOW_SIZE) * 4,
+            SpriteSheet::Mapped { .. } => RAW_HEADER_LEN + frame * FRAME_BYTES + y * ROW_BYTES,
+            SpriteSheet::OnDemand { first, .. } => {
+                let offset = (frame + self.frame_count() - first.unwrap_or(frame)) % self.frame_count();
+                offset * FRAME_BYTES + y * ROW_BYTES
+            }
+            SpriteSheet::Vector { pixels, .. } => {
+                let row_bytes = self.side() * 4;
+                return &pixels[y * row_bytes..(y + 1) * row_bytes];
+            }
+        };
+        let bytes: &[u8] = match self {
+            SpriteSheet::Decoded { pixels, .. } => pixels,
+            SpriteSheet::Mapped { map, .. } => map,
+            SpriteSheet::OnDemand { window, .. } => window,
+            SpriteSheet::Vector { .. } => unreachable!(),
+        };
+        &bytes[start..start + ROW_BYTES]
+    }
+
+    /// Loads `filename` of `theme` (or the classic sheet) the way `assets.storage` says.
+    pub fn load(theme: Option<&str>, filename: &str, assets: &AssetConfig) -> Option<SpriteSheet> {
+        let Some(source) = Source::locate(theme, filename, assets) else {
+            log::error!("{}", tr!("asset-not-found", file = filename));
+            return None;
+        };
+        let palette = assets.palette();
+        if source.is_vector() {
+            return load_vector(&source, filename, assets, palette);
+        }
+        if source.is_aseprite() {
+            return load_aseprite(&source, filename, &palette);
+        }
+
+        match assets.storage {
+            SpriteStorage::Decoded => {}
+            SpriteStorage::Mmap => match map_raw(&source, theme, filename, &palette) {
+                Ok(sheet) => return Some(sheet),
+                // Dann eben klassisch dekodieren
+                Err(e) => log::warn!("{}", tr!("asset-mmap-failed", path = &source, error = e)),
+            },
+            SpriteStorage::OnDemand => match load_compressed(&source, &palette) {
+                Ok(sheet) => return sheet,
+                Err(e) => log::warn!("{}", tr!("asset-on-demand-failed", path = &source, error = e)),
+            },
+        }
+
+        let mut image = decode(&source)?;
+        palette.apply(&mut image);
+        let width = image.width() as usize;
+        Some(SpriteSheet::Decoded { pixels: image.into_raw(), width, durations: Vec::new() })
+    }
+}
+
+/// Checks the four sheets of `theme` from their headers alone and returns
+/// every problem found, so a broken theme is reported in one go at startup
+/// instead of color by color as the lamp gets to them.
+pub fn check_theme(theme: Option<&str>, assets: &AssetConfig) -> Vec<String> {
+    let mut problems = Vec::new();
+    // Framegröße und -anzahl jeder lesbaren Farbe, für den Vergleich untereinander
+    let mut sheets = Vec::new();
+
+    for tier in Tier::ALL {
+        let file = tier.sprite_file();
+        let Some(source) = Source::locate(theme, file, assets) else {
+            problems.push(format!("{}: {}", file, tr!("sheet-missing")));
+            continue;
+        };
+        if source.is_vector() {
+            match read_vector(&source, file, assets) {
+                Ok(art) => sheets.push((source.to_string(), file, None, art.frame_count())),
+                Err(e) => problems.push(format!("{}: {}", source, e)),
+            }
+            continue;
+        }
+        if source.is_aseprite() {
+            // Die Farben dürfen verschieden lange Tags haben, daher kein Vergleich
+            let frames = source.read().map_err(|e| e.to_string()).and_then(|bytes| aseprite::frame_count(&bytes, tier));
+            if let Err(e) = frames {
+                problems.push(format!("{}: {}", source, e));
+            }
+            continue;
+        }
+        let (width, height) = match source.dimensions() {
+            Ok((width, height)) => (width as usize, height as usize),
+            Err(e) => {
+                problems.push(format!("{}: {}", source, e));
+                continue;
+            }
+        };
+
+        if height < WINDOW_SIZE {
+            let message = tr!("sheet-too-small", height = height, expected = WINDOW_SIZE);
+            problems.push(format!("{}: {}", source, message));
+            continue;
+        }
+        if !width.is_multiple_of(height) {
+            let message = tr!("sheet-not-divisible", width = width, frame = height);
+            problems.push(format!("{}: {}", source, message));
+            continue;
+        }
+        let frames = width / height;
+        if frames > ANIMATION_FRAMES {
+            let message = tr!("sheet-too-many-frames", frames = frames, max = ANIMATION_FRAMES);
+            problems.push(format!("{}: {}", source, message));
+        }
+        sheets.push((source.to_string(), file, Some(height), frames.min(ANIMATION_FRAMES)));
+    }
+
+    if let Some((_, first, frame, frames)) = sheets.first() {
+        for (source, _, other_frame, other_frames) in &sheets[1..] {
+            // Vektor-Frames haben keine feste Größe
+            if let (Some(frame), Some(other_frame)) = (frame, other_frame) {
+                if other_frame != frame {
+                    let message = tr!("sheet-frame-size-differs", size = other_frame, other = first, other_size = frame);
+                    problems.push(format!("{}: {}", source, message));
+                }
+            }
+            if other_frames != frames {
+                let message = tr!("sheet-frame-count-differs", frames = other_frames, other = first, other_frames = frames);
+                problems.push(format!("{}: {}", source, message));
+            }
+        }
+    }
+    problems
+}
+
+/// Whether any color of `theme` comes as an SVG or Lottie file.
+pub fn has_vector(theme: Option<&str>, assets: &AssetConfig) -> bool {
+    Tier::ALL.iter().any(|tier| Source::locate(theme, tier.sprite_file(), assets).is_some_and(|s| s.is_vector()))
+}
+
+/// Whether `theme` (or the built-in sheets) has the sheet `filename`.
+pub fn has_sheet(theme: Option<&str>, filename: &str, assets: &AssetConfig) -> bool {
+    Source::locate(theme, filename, assets).is_some()
+}
+
+/// Where the sheet `filename` of `theme` is loaded from, for `doctor`.
+pub fn describe_source(theme: Option<&str>, filename: &str, assets: &AssetConfig) -> Option<String> {
+    Source::locate(theme, filename, assets).map(|source| source.to_string())
+}
+
+/// Logs the result of [`check_theme`].
+pub fn report_problems(theme: Option<&str>, problems: &[String]) {
+    if problems.is_empty() {
+        return;
+    }
+    let theme = theme.map_or_else(|| tr!("theme-classic"), str::to_owned);
+    log::error!("{}", tr!("sheet-problems", theme = theme, count = problems.len()));
+    for problem in problems {
+        log::error!("    {}", problem);
+    }
+}
+
+/// Reads an SVG template or Lottie animation; every storage mode keeps just
+/// its source in memory.
+fn load_vector(source: &Source, filename: &str, assets: &AssetConfig, palette: Palette) -> Option<SpriteSheet> {
+    log::info!("{}", tr!("asset-loading", path = source));
+    match read_vector(source, filename, assets) {
+        Ok(art) => Some(SpriteSheet::Vector { art, rendered: None, pixels: Vec::new(), palette }),
+        Err(e) => {
+            log::error!("    {}", tr!("asset-decode-failed", path = source, error = e));
+            None
+        }
+    }
+}
+
+/// `filename` is the PNG name of the color; a shared `lavalampe.json` is
+/// recolored for it.
+fn read_vector(source: &Source, filename: &str, assets: &AssetConfig) -> Result<VectorArt, String> {
+    let bytes = source.read().map_err(|e| e.to_string())?.into_owned();
+    if source.is_svg() {
+        let text = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+        return svg::Template::parse(text).map(VectorArt::Svg);
+    }
+
+    let Source::File(path) = source else { unreachable!() };
+    let resources = path.parent().unwrap_or(Path::new("."));
+    // rlottie cacht geparste Dateien unter diesem Schlüssel; wegen des
+    // Umfärbens bekommt jede Farbe ihren eigenen
+    let cache_key = format!("{}#{}", path.display(), filename);
+    let mut animation = lottie::Animation::parse(bytes, &cache_key, resources)?;
+    if path.file_name().is_some_and(|name| name == lottie::SHARED_FILE) {
+        if let Some(tier) = Tier::ALL.into_iter().find(|tier| tier.sprite_file() == filename) {
+            animation.tint(&assets.lottie_keypath, tier.debug_color());
+        }
+    }
+    Ok(VectorArt::Lottie(animation))
+}
+
+/// Reads the frames of the color `filename` from an Aseprite file. Always
+/// decoded; the other storage modes work on PNGs.
+fn load_aseprite(source: &Source, filename: &str, palette: &Palette) -> Option<SpriteSheet> {
+    log::info!("{}", tr!("asset-loading", path = source));
+    let tier = Tier::ALL.into_iter().find(|tier| tier.sprite_file() == filename).unwrap_or(Tier::Green);
+    match source.read().map_err(|e| e.to_string()).and_then(|bytes| aseprite::load(&bytes, tier)) {
+        Ok(mut strip) => {
+            palette.apply(&mut strip.pixels);
+            Some(SpriteSheet::Decoded { pixels: strip.pixels, width: strip.width, durations: strip.durations })
+        }
+        Err(e) => {
+            log::error!("    {}", tr!("asset-decode-failed", path = source, error = e));
+            None
+        }
+    }
+}
+
+/// Decodes and checks a sprite sheet.
+fn decode(source: &Source) -> Option<RgbaImage> {
+    log::info!("{}", tr!("asset-loading", path = source));
+
+    let img = match source.read() {
+        Ok(bytes) => match image::load_from_memory(&bytes) {
+            Ok(image) => image.to_rgba8(),
+            Err(e) => {
+                log::error!("    {}", tr!("asset-decode-failed", path = source, error = e));
+                return None;
+            }
+        },
+        Err(e) => {
+            log::error!("    {}", tr!("asset-open-failed", path = source, error = e));
+            return None;
+        }
+    };
+
+    let (width, height) = img.dimensions();
+    let width = width as usize;
+    let height = height as usize;
+
+    if height < WINDOW_SIZE {
+        log::error!("    {}", tr!("asset-wrong-height", height = height, expected = WINDOW_SIZE));
+        return None;
+    }
+
+    // Frames sind quadratisch: so hoch wie das Sheet
+    let expected_width = ANIMATION_FRAMES * height;
+    if width != expected_width && !width.is_multiple_of(height) {
+        log::error!("    {}", tr!("asset-wrong-width", width = width, frame = height));
+        return None;
+    }
+
+    if height > WINDOW_SIZE {
+        return Some(downscale(&img, width / height));
+    }
+    Some(img)
+}
+
+/// Scales frames larger than the render size down to it, once at load time,
+/// instead of letting every redraw sample the big frames.
+fn downscale(img: &RgbaImage, frames: usize) -> RgbaImage {
+    let from = img.height();
+    log::info!("    {}", tr!("asset-downscaling", from = from, to = WINDOW_SIZE));
+    // Alle Frames werden mit demselben Faktor skaliert, die Grenzen bleiben also exakt
+    imageops::resize(img, (frames * WINDOW_SIZE) as u32, WINDOW_SIZE as u32, FilterType::CatmullRom)
+}
+
+/// Reads the PNG without decoding it. `Ok(None)` if it fails the same checks as [`decode`].
+fn load_compressed(source: &Source, palette: &Palette) -> Result<Option<SpriteSheet>, String> {
+    log::info!("{}", tr!("asset-loading", path = source));
+    let png = source.read().map_err(|e| e.to_string())?;
+
+    let reader = png::Decoder::new(Cursor::new(&png[..])).read_info().map_err(|e| e.to_string())?;
+    let info = reader.info();
+    if info.interlaced {
+        return Err(tr!("asset-interlaced"));
+    }
+    let width = info.width as usize;
+    let height = info.height as usize;
+
+    if height > WINDOW_SIZE {
+        return Err(tr!("asset-needs-downscaling", height = height));
+    }
+    if height != WINDOW_SIZE {
+        log::error!("    {}", tr!("asset-wrong-height", height = height, expected = WINDOW_SIZE));
+        return Ok(None);
+    }
+    let expected_width = ANIMATION_FRAMES * WINDOW_SIZE;
+    if width != expected_width && !width.is_multiple_of(WINDOW_SIZE) {
+        log::error!("    {}", tr!("asset-wrong-width", width = width, frame = WINDOW_SIZE));
+        return Ok(None);
+    }
+
+    Ok(Some(SpriteSheet::OnDemand { png, width, first: None, window: Vec::new(), palette: palette.clone() }))
+}
+
+/// Inflates the PNG row by row and keeps `count` frames starting at `first`
+/// (wrapping around), as RGBA, one frame after another.
+fn decode_frames(png: &[u8], width: usize, first: usize, count: usize) -> Result<Vec<u8>, String> {
+    let frames = (width / WINDOW_SIZE).min(ANIMATION_FRAMES);
+    let mut decoder = png::Decoder::new(Cursor::new(png));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+    let (color_type, _) = reader.output_color_type();
+
+    let mut window = vec![0; count * FRAME_BYTES];
+    let mut rgba = vec![0; width * 4];
+    for y in 0..WINDOW_SIZE {
+        let row = reader.next_row().map_err(|e| e.to_string())?.ok_or_else(|| tr!("asset-truncated"))?;
+        to_rgba(row.data(), color_type, &mut rgba);
+
+        for i in 0..count {
+            let frame = (first + i) % frames;
+            let source = frame * ROW_BYTES;
+            let dest = i * FRAME_BYTES + y * ROW_BYTES;
+            window[dest..dest + ROW_BYTES].copy_from_slice(&rgba[source..source + ROW_BYTES]);
+        }
+    }
+    Ok(window)
+}
+
+/// Converts one 8-bit row to RGBA.
+fn to_rgba(row: &[u8], color_type: png::ColorType, out: &mut [u8]) {
+    let pixels = out.chunks_exact_mut(4);
+    match color_type {
+        png::ColorType::Rgba => out.copy_from_slice(&row[..out.len()]),
+        png::ColorType::Rgb => pixels.zip(row.chunks_exact(3)).for_each(|(o, p)| o.copy_from_slice(&[p[0], p[1], p[2], 255])),
+        png::ColorType::GrayscaleAlpha => {
+            pixels.zip(row.chunks_exact(2)).for_each(|(o, p)| o.copy_from_slice(&[p[0], p[0], p[0], p[1]]))
+        }
+        // Paletten werden durch normalize_to_color8 bereits aufgelöst
+        png::ColorType::Grayscale | png::ColorType::Indexed => {
+            pixels.zip(row.iter()).for_each(|(o, p)| o.copy_from_slice(&[*p, *p, *p, 255]))
+        }
+    }
+}
+
+/// Maps the raw copy of `source`, (re)creating it when the PNG changed.
+fn map_raw(source: &Source, theme: Option<&str>, filename: &str, palette: &Palette) -> io::Result<SpriteSheet> {
+    let folder = match source {
+        Source::File(_) => theme.unwrap_or("classic"),
+        Source::Embedded(..) => "built-in",
+    };
+    let dir = sprite_cache_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, tr!("config-no-dir")))?
+        .join(folder);
+    let raw_path = dir.join(filename).with_extension("raw");
+    let (mtime, len) = source.stamp()?;
+    // Eine andere Palette braucht eine neue Kopie
+    let stamp = (mtime, len, palette.fingerprint());
+
+    if let Some(sheet) = open_raw(&raw_path, stamp)? {
+        return Ok(sheet);
+    }
+
+    let mut image = decode(source)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, tr!("asset-load-failed", file = filename)))?;
+    palette.apply(&mut image);
+    fs::create_dir_all(&dir)?;
+    write_raw(&raw_path, &image, stamp)?;
+    log::info!("{}", tr!("asset-raw-written", path = raw_path.display()));
+
+    open_raw(&raw_path, stamp)?.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "raw file"))
+}
+
+/// `None` if the file is missing or belongs to an older version of the PNG
+/// or another palette.
+fn open_raw(path: &Path, (mtime, len, palette): (u64, u64, u64)) -> io::Result<Option<SpriteSheet>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    // Die Datei wird nur ersetzt (rename), nie an Ort und Stelle geändert
+    let map = unsafe { Mmap::map(&file)? };
+    if map.len() < RAW_HEADER_LEN || &map[..8] != RAW_MAGIC {
+        return Ok(None);
+    }
+    let field = |i: usize| u64::from_le_bytes(map[8 + i * 8..16 + i * 8].try_into().unwrap());
+    let frames = field(0) as usize;
+    if field(1) != mtime || field(2) != len || field(3) != palette || map.len() != RAW_HEADER_LEN + frames * FRAME_BYTES {
+        return Ok(None);
+    }
+    Ok(Some(SpriteSheet::Mapped { map, frames }))
+}
+
+/// Writes the frames one after another, so each frame is one contiguous block.
+fn write_raw(path: &Path, image: &RgbaImage, (mtime, len, palette): (u64, u64, u64)) -> io::Result<()> {
+    let width = image.width() as usize;
+    let frames = (width / WINDOW_SIZE).min(ANIMATION_FRAMES);
+    let pixels = image.as_raw();
+
+    let tmp = path.with_extension("raw.tmp");
+    let mut out = io::BufWriter::new(File::create(&tmp)?);
+    out.write_all(RAW_MAGIC)?;
+    for value in [frames as u64, mtime, len, palette] {
+        out.write_all(&value.to_le_bytes())?;
+    }
+    for frame in 0..frames {
+        for y in 0..WINDOW_SIZE {
+            let start = (y * width + frame * WINDOW_SIZE) * 4;
+            out.write_all(&pixels[start..start + ROW_BYTES])?;
+        }
+    }
+    out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    fs::rename(&tmp, path)
+}
+
+/// `sprites` in the cache directory, see [`paths`](crate::paths).
+pub fn sprite_cache_dir() -> Option<PathBuf> {
+    paths::cache_dir().map(|dir| dir.join("sprites"))
+}
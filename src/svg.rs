@@ -0,0 +1,121 @@
+//! SVG sprite sheets: one SVG per color, rasterized with resvg at the size the
+//! lamp is shown at, so it stays crisp at 1024×1024 without giant PNGs.
+//!
+//! The SVG is a template. Before each frame is rendered, every `{{…}}` is
+//! replaced by a number:
+//!
+//! - `{{t}}`: the animation phase, from 0 to just below 1
+//! - `{{frame}}`: the frame number
+//! - `{{sin}}` / `{{cos}}`: sine and cosine of the phase (one full turn per loop)
+//!
+//! each optionally scaled and shifted, e.g. `cy="{{sin*40+64}}"` moves a blob
+//! between 24 and 104. The frame count comes from a `data-frames` attribute on
+//! the root element and defaults to the usual 169; an SVG without placeholders
+//! is a single still frame.
+
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg::{Options, Tree};
+use std::f64::consts::TAU;
+
+use crate::i18n::tr;
+use crate::ANIMATION_FRAMES;
+
+pub struct Template {
+    text: String,
+    frames: usize,
+}
+
+impl Template {
+    /// Checks that `text` renders (as frame 0) and reads the frame count.
+    pub fn parse(text: String) -> Result<Template, String> {
+        let frames = if text.contains("{{") {
+            frame_count_attribute(&text).unwrap_or(ANIMATION_FRAMES).clamp(1, ANIMATION_FRAMES)
+        } else {
+            1
+        };
+        let template = Template { text, frames };
+        template.tree(0)?;
+        Ok(template)
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames
+    }
+
+    /// Source size in bytes.
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Renders `frame` into a `side`×`side` RGBA buffer (straight alpha),
+    /// stretched to fill it like the PNG frames.
+    pub fn render(&self, frame: usize, side: usize) -> Result<Vec<u8>, String> {
+        let tree = self.tree(frame)?;
+        let mut pixmap = Pixmap::new(side as u32, side as u32).ok_or_else(|| format!("{0}×{0}", side))?;
+        let size = tree.size();
+        let transform = Transform::from_scale(side as f32 / size.width(), side as f32 / size.height());
+        resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+        Ok(pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect())
+    }
+
+    fn tree(&self, frame: usize) -> Result<Tree, String> {
+        let text = substitute(&self.text, frame, frame as f64 / self.frames as f64)?;
+        Tree::from_str(&text, &Options::default()).map_err(|e| e.to_string())
+    }
+}
+
+fn substitute(text: &str, frame: usize, t: f64) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let end = rest[start..].find("}}").ok_or_else(|| tr!("svg-unclosed-placeholder"))? + start;
+        let value = evaluate(rest[start + 2..end].trim(), frame, t)?;
+        out.push_str(&format!("{:.4}", value));
+        rest = &rest[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// `name`, `name*factor`, `name+offset` or `name*factor+offset` (or `-offset`).
+fn evaluate(expression: &str, frame: usize, t: f64) -> Result<f64, String> {
+    let invalid = || tr!("svg-bad-placeholder", placeholder = expression);
+    let split = expression.find(['*', '+', '-']).unwrap_or(expression.len());
+    let mut value = match expression[..split].trim() {
+        "t" => t,
+        "frame" => frame as f64,
+        "sin" => (t * TAU).sin(),
+        "cos" => (t * TAU).cos(),
+        _ => return Err(invalid()),
+    };
+
+    let mut tail = expression[split..].trim();
+    if let Some(factor) = tail.strip_prefix('*') {
+        let factor = factor.trim_start();
+        // Ein Vorzeichen direkt nach dem * gehört noch zum Faktor
+        let end = factor.get(1..).and_then(|f| f.find(['+', '-'])).map_or(factor.len(), |i| i + 1);
+        value *= factor[..end].trim().parse::<f64>().map_err(|_| invalid())?;
+        tail = factor[end..].trim();
+    }
+    if !tail.is_empty() {
+        value += tail.replace(' ', "").parse::<f64>().map_err(|_| invalid())?;
+    }
+    Ok(value)
+}
+
+/// `data-frames="…"` anywhere in the document; in practice on the root `<svg>`.
+fn frame_count_attribute(text: &str) -> Option<usize> {
+    let start = text.find("data-frames=")? + "data-frames=".len();
+    let quote = text[start..].chars().next()?;
+    let value = &text[start + 1..];
+    value[..value.find(quote)?].trim().parse().ok()
+}
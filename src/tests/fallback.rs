@@ -0,0 +1,147 @@
+//! What the lamp shows when sheets are missing or broken, the extremes a theme
+//! has no sheets for, and the renderer watchdog.
+
+use std::time::Duration;
+
+use super::{solid, Harness, Sheet, TestTheme, BOILING, GREEN, RED};
+use crate::config::Config;
+use crate::tier::{Extreme, Tier};
+use crate::watchdog::{RendererConfig, Verdict, Watchdog};
+
+const FRAMES: usize = 8;
+
+#[test]
+fn a_missing_sheet_falls_back_to_the_classic_one() {
+    let theme = TestTheme::new(&[("lavalampe_green.png", Sheet::Solid(GREEN, FRAMES))]);
+    let mut themed = Harness::with_values(theme.config(), &[90.0]);
+    let mut classic = Harness::with_values(Config::default(), &[90.0]);
+    themed.run(Duration::from_millis(500));
+    classic.run(Duration::from_millis(500));
+
+    assert_eq!(themed.tier(), Tier::Red);
+    assert_eq!(themed.lamp.frame_index, classic.lamp.frame_index);
+    assert!(themed.frame == classic.frame, "the classic red sheet should be shown");
+}
+
+#[test]
+fn a_configured_sheet_replaces_the_themes() {
+    let theme = TestTheme::solid(FRAMES);
+    let custom = TestTheme::new(&[("calm.png", Sheet::Solid(BOILING, FRAMES))]);
+    let mut config = theme.config();
+    config.assets.sheets.insert("green".into(), custom.path("calm.png"));
+    let mut harness = Harness::with_values(config, &[10.0]);
+    harness.run(Duration::from_millis(500));
+
+    assert_eq!(harness.tier(), Tier::Green);
+    assert_eq!(harness.center(), solid(BOILING, harness.lamp.frame_index));
+}
+
+#[test]
+fn a_broken_sheet_falls_back_to_green() {
+    let theme = TestTheme::new(&[
+        ("lavalampe_green.png", Sheet::Solid(GREEN, FRAMES)),
+        ("lavalampe_red.png", Sheet::Broken),
+    ]);
+    let mut harness = Harness::with_values(theme.config(), &[90.0]);
+    harness.run(Duration::from_millis(500));
+
+    // Die Farbe bleibt Rot, gezeigt wird das grüne Sheet des Themes
+    assert_eq!(harness.tier(), Tier::Red);
+    assert_eq!(harness.center(), solid(GREEN, harness.lamp.frame_index));
+}
+
+#[test]
+fn without_any_sheet_the_lamp_shows_its_color() {
+    let theme = TestTheme::new(&[
+        ("lavalampe_green.png", Sheet::Broken),
+        ("lavalampe_orange.png", Sheet::Broken),
+    ]);
+    let mut harness = Harness::with_values(theme.config(), &[60.0]);
+    harness.run(Duration::from_millis(500));
+
+    assert_eq!(harness.tier(), Tier::Orange);
+    assert!(harness.is_filled_with(Tier::Orange.debug_color()));
+    // Ohne Sheet läuft die Animation trotzdem weiter
+    assert!(harness.lamp.frame_index > 0);
+}
+
+#[test]
+fn boiling_without_a_sheet_draws_bubbles_over_red() {
+    let theme = TestTheme::solid(FRAMES);
+    let mut config = theme.config();
+    config.thresholds.boiling = Some(95.0);
+    let mut harness = Harness::with_values(config, &[99.0]);
+    harness.run(Duration::from_millis(500));
+
+    assert_eq!(harness.lamp.mapping.extreme, Some(Extreme::Boiling));
+    let red = solid(RED, harness.lamp.frame_index);
+    let bubbles = harness.frame.chunks_exact(4).filter(|pixel| *pixel != red).count();
+    assert!(bubbles > 0, "no bubbles drawn");
+    assert!(bubbles < harness.frame.len() / 4 / 2, "bubbles should only cover part of the lamp");
+}
+
+#[test]
+fn boiling_uses_the_themes_own_sheet() {
+    let theme = TestTheme::new(&[
+        ("lavalampe_red.png", Sheet::Solid(RED, FRAMES)),
+        ("lavalampe_boiling.png", Sheet::Solid(BOILING, FRAMES)),
+    ]);
+    let mut config = theme.config();
+    config.thresholds.boiling = Some(95.0);
+    let mut harness = Harness::with_values(config, &[99.0]);
+    harness.run(Duration::from_millis(500));
+
+    assert!(harness.is_filled_with(solid(BOILING, harness.lamp.frame_index)));
+}
+
+#[test]
+fn sleeping_without_a_sheet_freezes_and_dims() {
+    let theme = TestTheme::solid(FRAMES);
+    let mut config = theme.config();
+    config.thresholds.idle = Some(5.0);
+    let mut harness = Harness::with_values(config, &[1.0]);
+    harness.run(Duration::from_secs(2));
+
+    assert_eq!(harness.lamp.mapping.extreme, Some(Extreme::Sleeping));
+    assert_eq!(harness.lamp.frame_index, 0);
+    let [_, green, ..] = harness.center();
+    assert!(green < GREEN[1], "the lamp should be dimmed");
+}
+
+#[test]
+fn the_renderer_is_restarted_then_in_software_then_given_up() {
+    let config = RendererConfig::default();
+    let mut watchdog = Watchdog::new(false);
+    let fail = |watchdog: &mut Watchdog| {
+        assert_eq!(watchdog.failed(&config), Verdict::Retry);
+        assert_eq!(watchdog.failed(&config), Verdict::Retry);
+        watchdog.failed(&config)
+    };
+
+    // Ein gelungener Frame dazwischen setzt die Zählung zurück
+    assert_eq!(watchdog.failed(&config), Verdict::Retry);
+    watchdog.succeeded();
+    assert_eq!(fail(&mut watchdog), Verdict::Restart { software: false });
+    assert_eq!(fail(&mut watchdog), Verdict::Restart { software: false });
+    for _ in 0..3 {
+        assert_eq!(fail(&mut watchdog), Verdict::Restart { software: true });
+    }
+    assert!(!watchdog.gave_up());
+    assert_eq!(fail(&mut watchdog), Verdict::GiveUp);
+    assert!(watchdog.gave_up());
+}
+
+#[test]
+fn without_software_fallback_the_renderer_stays_on_the_gpu() {
+    let config = RendererConfig { software_fallback: false, ..RendererConfig::default() };
+    let mut watchdog = Watchdog::new(false);
+    for _ in 0..5 * 3 {
+        assert_ne!(watchdog.failed(&config), Verdict::Restart { software: true });
+    }
+}
+
+#[test]
+fn without_the_watchdog_a_failed_frame_quits() {
+    let config = RendererConfig { watchdog: false, ..RendererConfig::default() };
+    assert_eq!(Watchdog::new(false).failed(&config), Verdict::Quit);
+}
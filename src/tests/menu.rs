@@ -0,0 +1,32 @@
+//! The context menu of a long press: where its entries are and what they do.
+
+use crate::menu::{ContextMenu, Entry};
+use crate::{AppEvent, WINDOW_SIZE};
+
+fn menu() -> ContextMenu {
+    ContextMenu::new(vec![
+        Entry { label: "CRT".into(), checked: Some(true), event: AppEvent::ToggleCrt },
+        Entry { label: "Quit".into(), checked: None, event: AppEvent::Quit },
+    ])
+}
+
+#[test]
+fn entries_are_picked_where_they_are_drawn() {
+    let side = WINDOW_SIZE;
+    let mut frame = vec![255; side * side * 4];
+    let menu = menu();
+    menu.draw(&mut frame, side, side);
+
+    // Die Box ist abgedunkelt, der Rest unberührt
+    let center = (side / 2 * side + side / 2) * 4;
+    assert!(frame[center] < 255, "the menu should cover the middle of the lamp");
+    assert_eq!(frame[..4], [255, 255, 255, 255]);
+
+    let rows: Vec<usize> = (0..side).filter(|y| frame[(y * side + side / 2) * 4] < 255).collect();
+    let (top, bottom) = (rows[0], rows[rows.len() - 1]);
+    assert!(matches!(menu.event_at(side / 2, top + 3, side, side), Some(AppEvent::ToggleCrt)));
+    assert!(matches!(menu.event_at(side / 2, bottom - 3, side, side), Some(AppEvent::Quit)));
+    assert!(menu.event_at(side / 2, top.saturating_sub(1), side, side).is_none());
+    assert!(menu.event_at(side / 2, bottom + 1, side, side).is_none());
+    assert!(menu.event_at(0, side / 2, side, side).is_none());
+}
@@ -0,0 +1,222 @@
+//! Headless tests of the whole pipeline: a simulated metric goes through the
+//! same mapping, animation and compositing code as in the window, into a
+//! frame buffer in memory, on a virtual clock. No window, GPU or real
+//! sensor is needed, so they run anywhere `cargo test` does.
+//!
+//! Sheets come from small generated themes (see [`TestTheme`]) whose frames
+//! are solid colors with the frame number in the blue channel, so a single
+//! pixel tells which sheet and frame ended up on screen.
+
+mod fallback;
+mod menu;
+mod pipeline;
+mod process;
+mod quiet_hours;
+
+use image::{Rgba, RgbaImage};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use crate::background::Background;
+use crate::config::Config;
+use crate::effects::PostProcess;
+use crate::lamp::{self, Lamp, SpriteCache};
+use crate::script::Mapping;
+use crate::sprites;
+use crate::tier::{Extreme, Tier};
+use crate::WINDOW_SIZE;
+
+/// Time between two redraws of [`Harness::run`], about what the window
+/// does at the fastest tier.
+const FRAME_STEP: Duration = Duration::from_millis(20);
+
+/// One lamp without a window.
+pub struct Harness {
+    pub config: Config,
+    pub lamp: Lamp,
+    sprites: SpriteCache,
+    extreme_sheets: Vec<Extreme>,
+    background: Background,
+    post: PostProcess,
+    /// The composited frame, `WINDOW_SIZE` pixels square.
+    pub frame: Vec<u8>,
+    /// Virtual time since the start.
+    pub now: Duration,
+    last_sample: Option<Duration>,
+    metric: Box<dyn FnMut(Duration) -> f64>,
+}
+
+impl Harness {
+    /// A lamp showing `metric`, which is asked for the value at each sample.
+    pub fn new(config: Config, metric: impl FnMut(Duration) -> f64 + 'static) -> Harness {
+        let theme = config.active_theme();
+        let extreme_sheets = Extreme::ALL
+            .into_iter()
+            .filter(|extreme| sprites::has_sheet(theme, extreme.sprite_file(), &config.assets))
+            .collect();
+        Harness {
+            lamp: Lamp::new(0.0, Mapping::default_for(0.0, &config)),
+            config,
+            sprites: SpriteCache::default(),
+            extreme_sheets,
+            background: Background::default(),
+            post: PostProcess::default(),
+            frame: vec![0; WINDOW_SIZE * WINDOW_SIZE * 4],
+            now: Duration::ZERO,
+            last_sample: None,
+            metric: Box::new(metric),
+        }
+    }
+
+    /// A lamp showing `values` one sample after the other, then the last one.
+    pub fn with_values(config: Config, values: &[f64]) -> Harness {
+        let mut values = Vec::from(values).into_iter();
+        let mut last = 0.0;
+        Harness::new(config, move |_| {
+            last = values.next().unwrap_or(last);
+            last
+        })
+    }
+
+    /// Moves the clock on by `elapsed` and draws a frame, sampling first if one is due.
+    pub fn step(&mut self, elapsed: Duration) {
+        self.now += elapsed;
+        if self.last_sample.is_none_or(|last| self.now - last >= self.config.sampling_interval()) {
+            self.sample();
+        }
+        self.sprites.tick(self.config.assets.cache_budget_mb);
+        lamp::tick_all(std::slice::from_mut(&mut self.lamp), &mut self.sprites, &self.config, &self.extreme_sheets, elapsed);
+        self.draw();
+    }
+
+    /// Runs for `duration` at [`FRAME_STEP`].
+    pub fn run(&mut self, duration: Duration) {
+        let end = self.now + duration;
+        while self.now < end {
+            self.step(FRAME_STEP.min(end - self.now));
+        }
+    }
+
+    /// Runs until just after the next sample.
+    pub fn next_sample(&mut self) {
+        let due = self.last_sample.map_or(Duration::ZERO, |last| last + self.config.sampling_interval());
+        self.run(due.saturating_sub(self.now));
+        if self.last_sample != Some(self.now) {
+            self.step(Duration::ZERO);
+        }
+    }
+
+    /// What [`App`](crate::app::App) does with a sample, for a single lamp without a script.
+    fn sample(&mut self) {
+        self.last_sample = Some(self.now);
+        let percent = (self.metric)(self.now);
+        let mapping = Mapping::default_for(percent, &self.config);
+        self.lamp.record(percent, &self.config.speed);
+        self.lamp.mapping = mapping;
+    }
+
+    fn draw(&mut self) {
+        let lamps = std::slice::from_mut(&mut self.lamp);
+        self.background.draw(&mut self.frame, 1, WINDOW_SIZE, lamps, None, &self.config.background);
+        lamp::draw_all(&mut self.frame, 1, WINDOW_SIZE, lamps, &mut self.sprites, &self.config, &self.extreme_sheets);
+        self.post.run(&mut self.frame, WINDOW_SIZE, WINDOW_SIZE, &self.config.effects);
+    }
+
+    pub fn tier(&self) -> Tier {
+        self.lamp.mapping.tier
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let i = (y * WINDOW_SIZE + x) * 4;
+        [self.frame[i], self.frame[i + 1], self.frame[i + 2], self.frame[i + 3]]
+    }
+
+    /// The pixel in the middle of the lamp.
+    pub fn center(&self) -> [u8; 4] {
+        self.pixel(WINDOW_SIZE / 2, WINDOW_SIZE / 2)
+    }
+
+    /// Whether every pixel of the frame is `color`.
+    pub fn is_filled_with(&self, color: [u8; 4]) -> bool {
+        self.frame.chunks_exact(4).all(|pixel| pixel == color)
+    }
+}
+
+/// A sheet of a [`TestTheme`].
+pub enum Sheet {
+    /// `frames` frames filled with `[r, g, frame number]`.
+    Solid([u8; 2], usize),
+    /// A file that isn't a PNG.
+    Broken,
+}
+
+/// Red and green of the solid sheets, one per tier.
+pub const GREEN: [u8; 2] = [0, 200];
+pub const YELLOW: [u8; 2] = [200, 200];
+pub const ORANGE: [u8; 2] = [200, 100];
+pub const RED: [u8; 2] = [200, 0];
+pub const BOILING: [u8; 2] = [250, 250];
+
+/// A theme written to a directory of its own, removed again when dropped.
+pub struct TestTheme {
+    dir: PathBuf,
+    name: String,
+}
+
+impl TestTheme {
+    pub fn new(sheets: &[(&str, Sheet)]) -> TestTheme {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!("test-{}", COUNT.fetch_add(1, Ordering::Relaxed));
+        let dir = std::env::temp_dir().join(format!("ram-lavalampe-{}-{}", std::process::id(), name));
+        let theme_dir = dir.join("themes").join(&name);
+        fs::create_dir_all(&theme_dir).unwrap();
+        for (file, sheet) in sheets {
+            let path = theme_dir.join(file);
+            match sheet {
+                Sheet::Solid([r, g], frames) => {
+                    let side = WINDOW_SIZE as u32;
+                    let image = RgbaImage::from_fn(side * *frames as u32, side, |x, _| Rgba([*r, *g, (x / side) as u8, 255]));
+                    image.save(&path).unwrap();
+                }
+                Sheet::Broken => fs::write(&path, b"not a png").unwrap(),
+            }
+        }
+        TestTheme { dir, name }
+    }
+
+    /// The four tiers as solid sheets of `frames` frames each.
+    pub fn solid(frames: usize) -> TestTheme {
+        TestTheme::new(&[
+            ("lavalampe_green.png", Sheet::Solid(GREEN, frames)),
+            ("lavalampe_yellow.png", Sheet::Solid(YELLOW, frames)),
+            ("lavalampe_orange.png", Sheet::Solid(ORANGE, frames)),
+            ("lavalampe_red.png", Sheet::Solid(RED, frames)),
+        ])
+    }
+
+    /// Where the sheet `file` of this theme was written to.
+    pub fn path(&self, file: &str) -> PathBuf {
+        self.dir.join("themes").join(&self.name).join(file)
+    }
+
+    /// The default config with this theme.
+    pub fn config(&self) -> Config {
+        let mut config = Config::default();
+        config.assets.dirs = vec![self.dir.clone()];
+        config.theme = Some(self.name.clone());
+        config
+    }
+}
+
+impl Drop for TestTheme {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// A pixel of a solid sheet: its color and frame.
+pub fn solid(color: [u8; 2], frame: usize) -> [u8; 4] {
+    [color[0], color[1], frame as u8, 255]
+}
@@ -0,0 +1,123 @@
+//! From the sample to the pixel: tier transitions and animation timing.
+
+use std::time::Duration;
+
+use super::{solid, Harness, TestTheme, GREEN, ORANGE, RED, YELLOW};
+use crate::tier::Tier;
+
+const FRAMES: usize = 8;
+
+#[test]
+fn the_tier_follows_the_thresholds() {
+    let theme = TestTheme::solid(FRAMES);
+    // Jede Schwelle gehört noch zur Farbe darunter
+    let values = [10.0, 30.0, 30.1, 50.0, 50.1, 80.0, 80.1, 100.0, 20.0];
+    let expected = [Tier::Green, Tier::Green, Tier::Yellow, Tier::Yellow, Tier::Orange, Tier::Orange, Tier::Red, Tier::Red, Tier::Green];
+    let mut harness = Harness::with_values(theme.config(), &values);
+
+    for (value, tier) in values.iter().zip(expected) {
+        harness.next_sample();
+        assert_eq!(harness.tier(), tier, "tier at {}%", value);
+        let color = match tier {
+            Tier::Green => GREEN,
+            Tier::Yellow => YELLOW,
+            Tier::Orange => ORANGE,
+            Tier::Red => RED,
+        };
+        assert_eq!(harness.center(), solid(color, harness.lamp.frame_index), "frame at {}%", value);
+    }
+}
+
+#[test]
+fn configured_thresholds_apply() {
+    let theme = TestTheme::solid(FRAMES);
+    let mut config = theme.config();
+    config.thresholds.yellow = 60.0;
+    let mut harness = Harness::with_values(config, &[50.0]);
+    harness.next_sample();
+    assert_eq!(harness.tier(), Tier::Green);
+}
+
+#[test]
+fn frames_advance_at_the_speed_of_the_tier() {
+    let theme = TestTheme::solid(FRAMES);
+
+    // 200 ms pro Frame
+    let mut calm = Harness::with_values(theme.config(), &[10.0]);
+    calm.run(Duration::from_millis(1100));
+    assert_eq!(calm.lamp.frame_index, 5);
+    assert_eq!(calm.center(), solid(GREEN, 5));
+
+    // 60 ms pro Frame: 18 Frames in 1,1 s, nach einer Runde durch alle acht
+    let mut busy = Harness::with_values(theme.config(), &[90.0]);
+    busy.run(Duration::from_millis(1110));
+    assert_eq!(busy.lamp.frame_index, 18 % FRAMES);
+    assert_eq!(busy.center(), solid(RED, 18 % FRAMES));
+}
+
+#[test]
+fn the_continuous_speed_follows_the_percentage() {
+    let theme = TestTheme::solid(FRAMES);
+    let mut config = theme.config();
+    config.speed.continuous = true;
+    // Bei 100 % die schnellste Zeit, 60 ms, in Grün statt Rot
+    config.thresholds.yellow = 100.0;
+    let mut harness = Harness::with_values(config, &[100.0]);
+    harness.run(Duration::from_millis(330));
+    assert_eq!(harness.tier(), Tier::Green);
+    assert_eq!(harness.lamp.frame_index, 5);
+}
+
+#[test]
+fn a_stall_catches_up_at_most_four_frames() {
+    let theme = TestTheme::solid(FRAMES);
+    let mut harness = Harness::with_values(theme.config(), &[10.0]);
+    harness.run(Duration::from_millis(100));
+    assert_eq!(harness.lamp.frame_index, 0);
+
+    // Zehn Sekunden suspendiert: 50 Frames wären fällig
+    harness.step(Duration::from_secs(10));
+    assert_eq!(harness.lamp.frame_index, 4);
+    assert_eq!(harness.center(), solid(GREEN, 4));
+}
+
+#[test]
+fn falling_values_play_backwards() {
+    let theme = TestTheme::solid(FRAMES);
+    let mut config = theme.config();
+    config.speed.reverse_when_falling = true;
+    let mut harness = Harness::with_values(config, &[25.0, 20.0, 15.0, 10.0]);
+    harness.next_sample();
+    harness.next_sample();
+    assert!(harness.lamp.reverse);
+
+    // Fünf Frames zu 200 ms zurück
+    let before = harness.lamp.frame_index;
+    harness.run(Duration::from_millis(1100));
+    assert_eq!(harness.lamp.frame_index, (before + FRAMES - 5) % FRAMES);
+    assert_eq!(harness.center(), solid(GREEN, harness.lamp.frame_index));
+}
+
+#[test]
+fn rising_values_turn_the_animation_around_again() {
+    let theme = TestTheme::solid(FRAMES);
+    let mut config = theme.config();
+    config.speed.reverse_when_falling = true;
+    config.speed.trend_samples = 2;
+    let mut harness = Harness::with_values(config, &[25.0, 20.0, 28.0]);
+    harness.next_sample();
+    harness.next_sample();
+    assert!(harness.lamp.reverse);
+    harness.next_sample();
+    assert!(!harness.lamp.reverse);
+}
+
+#[test]
+fn tier_speeds_come_from_the_config() {
+    let theme = TestTheme::solid(FRAMES);
+    let mut config = theme.config();
+    config.speed.green_ms = 100;
+    let mut harness = Harness::with_values(config, &[10.0]);
+    harness.run(Duration::from_millis(550));
+    assert_eq!(harness.lamp.frame_index, 5);
+}
@@ -0,0 +1,34 @@
+//! Watching the RAM of a single process, and the fallback to the whole system.
+
+use crate::metrics::{Metric, MetricSettings, Sampler};
+
+#[test]
+fn a_process_fills_its_budget() {
+    let mut sampler = Sampler::new();
+    let settings = MetricSettings {
+        process: Some(std::process::id().to_string()),
+        process_budget_mb: Some(1_000_000.0),
+        ..MetricSettings::default()
+    };
+    let percent = sampler.sample(Metric::Ram, &settings);
+    assert!(percent > 0.0 && percent < 1.0, "the test process should use a little of 1 TB, got {}%", percent);
+    assert_eq!(sampler.capacity_mib(Metric::Ram, &settings), Some(1_000_000.0));
+
+    // Mehr RSS als Budget ist eine volle Lampe
+    let tight = MetricSettings { process_budget_mb: Some(0.001), ..settings };
+    assert_eq!(sampler.sample(Metric::Ram, &tight), 100.0);
+}
+
+#[test]
+fn without_the_process_the_whole_system_is_shown() {
+    let mut sampler = Sampler::new();
+    let settings = MetricSettings {
+        process: Some("no-such-lava-process".into()),
+        process_budget_mb: Some(1.0),
+        exclude_zfs_arc: false,
+        ..MetricSettings::default()
+    };
+    let percent = sampler.sample(Metric::Ram, &settings);
+    assert!(percent > 0.0 && percent < 100.0, "expected the system's RAM usage, got {}%", percent);
+    assert_eq!(sampler.capacity_mib(Metric::Ram, &settings), sampler.capacity_mib(Metric::Ram, &MetricSettings::default()));
+}
@@ -0,0 +1,50 @@
+//! Parsing of `alerts.quiet_hours` and which times of day it covers.
+
+use crate::alerts::QuietHours;
+
+fn quiet(text: &str) -> QuietHours {
+    QuietHours::try_from(text.to_string()).unwrap()
+}
+
+fn minutes(hours: u32, minutes: u32) -> u32 {
+    hours * 60 + minutes
+}
+
+#[test]
+fn a_span_within_one_day_covers_its_start_but_not_its_end() {
+    let lunch = quiet("12:00-13:30");
+    assert!(!lunch.contains(minutes(11, 59)));
+    assert!(lunch.contains(minutes(12, 0)));
+    assert!(lunch.contains(minutes(13, 29)));
+    assert!(!lunch.contains(minutes(13, 30)));
+    assert!(!lunch.contains(minutes(0, 0)));
+}
+
+#[test]
+fn a_span_past_midnight_wraps_around() {
+    let night = quiet("22:00-07:00");
+    assert!(!night.contains(minutes(21, 59)));
+    assert!(night.contains(minutes(22, 0)));
+    assert!(night.contains(minutes(23, 59)));
+    assert!(night.contains(minutes(0, 0)));
+    assert!(night.contains(minutes(6, 59)));
+    assert!(!night.contains(minutes(7, 0)));
+    assert!(!night.contains(minutes(12, 0)));
+}
+
+#[test]
+fn spaces_around_the_times_are_allowed_and_written_back_without() {
+    assert_eq!(String::from(quiet(" 8:05 - 9:00 ")), "08:05-09:00");
+}
+
+#[test]
+fn an_empty_span_is_rejected() {
+    assert!(QuietHours::try_from("07:00-07:00".to_string()).is_err());
+}
+
+#[test]
+fn malformed_spans_are_rejected() {
+    for text in ["", "22:00", "22:00-", "-07:00", "22-07", "24:00-07:00", "22:60-07:00", "ten:00-07:00", "22:00-07:00-08:00"] {
+        assert!(QuietHours::try_from(text.to_string()).is_err(), "{text:?}");
+    }
+}
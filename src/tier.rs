@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use crate::config::Thresholds;
+
+/// The four lava colors, from calm to critical.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tier {
+    Green,
+    Yellow,
+    Orange,
+    Red,
+}
+
+/// Looks at the very ends of the scale: boiling over above
+/// `thresholds.boiling`, asleep below `thresholds.idle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extreme {
+    Boiling,
+    Sleeping,
+}
+
+impl Extreme {
+    pub const ALL: [Extreme; 2] = [Extreme::Boiling, Extreme::Sleeping];
+
+    /// Optional sheet a theme can ship for this, shown instead of the tier's.
+    pub fn sprite_file(&self) -> &'static str {
+        match self {
+            Extreme::Boiling => "lavalampe_boiling.png",
+            Extreme::Sleeping => "lavalampe_sleeping.png",
+        }
+    }
+
+    /// Lowercase name, e.g. for CSS classes.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Extreme::Boiling => "boiling",
+            Extreme::Sleeping => "sleeping",
+        }
+    }
+
+    /// Catalog key of the message logged when a lamp gets there.
+    pub fn message_key(&self) -> &'static str {
+        match self {
+            Extreme::Boiling => "boiling-over",
+            Extreme::Sleeping => "falling-asleep",
+        }
+    }
+}
+
+impl Tier {
+    pub const ALL: [Tier; 4] = [Tier::Green, Tier::Yellow, Tier::Orange, Tier::Red];
+
+    /// Each threshold is the highest percentage that still belongs to the tier below it.
+    pub fn from_percent(percent: f64, thresholds: &Thresholds) -> Tier {
+        match percent {
+            p if p <= thresholds.yellow => Tier::Green,
+            p if p <= thresholds.orange => Tier::Yellow,
+            p if p <= thresholds.red => Tier::Orange,
+            _ => Tier::Red,
+        }
+    }
+
+    /// Parses a lowercase color name as used in scripts.
+    pub fn from_name(name: &str) -> Option<Tier> {
+        match name {
+            "green" => Some(Tier::Green),
+            "yellow" => Some(Tier::Yellow),
+            "orange" => Some(Tier::Orange),
+            "red" => Some(Tier::Red),
+            _ => None,
+        }
+    }
+
+    /// Lowercase color name, the inverse of [`from_name`](Self::from_name).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tier::Green => "green",
+            Tier::Yellow => "yellow",
+            Tier::Orange => "orange",
+            Tier::Red => "red",
+        }
+    }
+
+    pub fn sprite_file(&self) -> &'static str {
+        match self {
+            Tier::Green => "lavalampe_green.png",
+            Tier::Yellow => "lavalampe_yellow.png",
+            Tier::Orange => "lavalampe_orange.png",
+            Tier::Red => "lavalampe_red.png",
+        }
+    }
+
+    /// Catalog key of the color name.
+    pub fn color_key(&self) -> &'static str {
+        match self {
+            Tier::Green => "color-green",
+            Tier::Yellow => "color-yellow",
+            Tier::Orange => "color-orange",
+            Tier::Red => "color-red",
+        }
+    }
+
+    /// Default time per animation frame (`[speed]` can change it): the fuller
+    /// the memory, the more frantic the lava.
+    pub fn frame_duration(&self) -> Duration {
+        match self {
+            Tier::Green => Duration::from_millis(200),
+            Tier::Yellow => Duration::from_millis(150),
+            Tier::Orange => Duration::from_millis(100),
+            Tier::Red => Duration::from_millis(60),
+        }
+    }
+
+    /// Solid color shown when no sprite sheet could be loaded.
+    pub fn debug_color(&self) -> [u8; 4] {
+        match self {
+            Tier::Green => [0, 255, 0, 255],
+            Tier::Yellow => [255, 255, 0, 255],
+            Tier::Orange => [255, 165, 0, 255],
+            Tier::Red => [255, 0, 0, 255],
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Touch gestures for tablets and touchscreen laptops: drag with one finger
+//! to move the window, pinch with two to resize it, hold one finger still
+//! for the context menu and tap to pick an entry from it.
+//!
+//! Winit only reports where each finger is; this turns those reports into
+//! gestures. A long press fires while the finger is still down, so the loop
+//! has to wake up for it ([`Gestures::long_press_due`]).
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use winit::dpi::PhysicalPosition;
+use winit::event::{Touch, TouchPhase};
+
+/// How long a finger has to stay down for a long press.
+const LONG_PRESS: Duration = Duration::from_millis(600);
+/// How far a finger may wander, in physical pixels, and still be a press.
+const TAP_SLOP: f64 = 12.0;
+/// Change in finger distance that counts as one pinch step.
+const PINCH_STEP: f64 = 1.4;
+
+pub enum Gesture {
+    /// Move the window by this much.
+    Drag(PhysicalPosition<f64>),
+    /// The finger was lifted after dragging.
+    DragEnded,
+    /// Fingers spread apart (`true`) or pinched together (`false`).
+    Pinch(bool),
+    LongPress,
+    /// A finger was lifted again before it became a long press or a drag.
+    Tap(PhysicalPosition<f64>),
+}
+
+/// Fingers currently on the window.
+#[derive(Default)]
+pub struct Gestures {
+    /// Where each finger is, by id.
+    fingers: BTreeMap<u64, PhysicalPosition<f64>>,
+    /// First finger down: when and where, as long as it could still be a long press.
+    press: Option<(Instant, PhysicalPosition<f64>)>,
+    /// Where the window was grabbed, while dragging.
+    grab: Option<PhysicalPosition<f64>>,
+    /// Finger distance at the last pinch step.
+    pinch: Option<f64>,
+}
+
+impl Gestures {
+    /// Takes one touch report; returns what it completes, if anything.
+    pub fn touch(&mut self, touch: Touch) -> Option<Gesture> {
+        match touch.phase {
+            TouchPhase::Started => {
+                self.fingers.insert(touch.id, touch.location);
+                if self.fingers.len() == 1 {
+                    self.press = Some((Instant::now(), touch.location));
+                } else {
+                    // Ein zweiter Finger macht daraus ein Zoomen
+                    self.press = None;
+                    self.grab = None;
+                    self.pinch = self.spread();
+                }
+                None
+            }
+            TouchPhase::Moved => {
+                // Finger, die außerhalb des Fensters aufgesetzt wurden, zählen nicht
+                self.fingers.insert(touch.id, touch.location)?;
+                if self.fingers.len() >= 2 {
+                    return self.pinch_step();
+                }
+                if let Some((_, start)) = self.press {
+                    if distance(start, touch.location) < TAP_SLOP {
+                        return None;
+                    }
+                    self.press = None;
+                    self.grab = Some(start);
+                }
+                // Die Position ist relativ zum Fenster: um den Abstand zum Griffpunkt
+                // verschoben, liegt der Finger wieder an derselben Stelle des Fensters
+                let grab = self.grab?;
+                Some(Gesture::Drag(PhysicalPosition::new(touch.location.x - grab.x, touch.location.y - grab.y)))
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.fingers.remove(&touch.id);
+                if self.fingers.len() < 2 {
+                    self.pinch = None;
+                }
+                let pressed = self.press.take().is_some();
+                if self.fingers.is_empty() && self.grab.take().is_some() {
+                    return Some(Gesture::DragEnded);
+                }
+                (pressed && self.fingers.is_empty() && touch.phase == TouchPhase::Ended).then_some(Gesture::Tap(touch.location))
+            }
+        }
+    }
+
+    /// When a finger held still becomes a long press.
+    pub fn long_press_due(&self) -> Option<Instant> {
+        self.press.map(|(started, _)| started + LONG_PRESS)
+    }
+
+    /// Fires the long press once it's due.
+    pub fn poll(&mut self, now: Instant) -> Option<Gesture> {
+        let due = self.long_press_due()?;
+        if now < due {
+            return None;
+        }
+        self.press = None;
+        Some(Gesture::LongPress)
+    }
+
+    fn pinch_step(&mut self) -> Option<Gesture> {
+        let spread = self.spread()?;
+        let last = self.pinch?;
+        let grow = if spread > last * PINCH_STEP {
+            true
+        } else if spread < last / PINCH_STEP {
+            false
+        } else {
+            return None;
+        };
+        self.pinch = Some(spread);
+        Some(Gesture::Pinch(grow))
+    }
+
+    /// Distance between the first two fingers.
+    fn spread(&self) -> Option<f64> {
+        let mut fingers = self.fingers.values();
+        Some(distance(*fingers.next()?, *fingers.next()?))
+    }
+}
+
+fn distance(a: PhysicalPosition<f64>, b: PhysicalPosition<f64>) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
@@ -0,0 +1,412 @@
+use std::time::Duration;
+
+use ksni::blocking::{Handle, TrayMethods};
+use ksni::menu::{CheckmarkItem, MenuItem, RadioGroup, RadioItem, StandardItem, SubMenu};
+use ksni::{Icon, ToolTip};
+use winit::event_loop::EventLoopProxy;
+
+use crate::config::{Config, Layout};
+use crate::i18n::tr;
+use crate::metrics::Metric;
+use crate::overlay;
+use crate::tier::Tier;
+use crate::{AppEvent, WindowSizeMode};
+
+pub const SAMPLING_INTERVALS: [Duration; 5] = [
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_secs(2),
+    Duration::from_secs(5),
+];
+
+/// StatusNotifierItem tray icon. Holds a copy of the settings it shows so the
+/// radio groups reflect the current state; every click is forwarded to the
+/// event loop as an [`AppEvent`] and takes effect immediately.
+pub struct LampTray {
+    proxy: EventLoopProxy<AppEvent>,
+    pub size_mode: WindowSizeMode,
+    pub themes: Vec<String>,
+    pub theme: Option<String>,
+    pub layout: Layout,
+    pub metric: Metric,
+    /// Loaded metric plugins; each gets its own entry in the metric menu.
+    pub plugins: Vec<String>,
+    pub plugin: Option<String>,
+    pub sampling_interval: Duration,
+    pub profiles: Vec<String>,
+    pub profile: Option<String>,
+    pub paused: bool,
+    pub crt: bool,
+    pub borderless: bool,
+    pub widget: bool,
+    pub click_through: bool,
+    /// A baseline is set.
+    pub baseline: bool,
+    /// The window is hidden to the tray.
+    pub hidden: bool,
+    /// Percentage and color of the last sample, drawn into the icon.
+    pub level: Option<(f64, Tier)>,
+}
+
+impl LampTray {
+    pub fn new(
+        proxy: EventLoopProxy<AppEvent>,
+        config: &Config,
+        themes: Vec<String>,
+        plugins: Vec<String>,
+        profiles: Vec<String>,
+        profile: Option<String>,
+    ) -> Self {
+        LampTray {
+            proxy,
+            size_mode: config.size,
+            themes,
+            theme: config.active_theme().map(String::from),
+            layout: config.layout,
+            metric: config.metric,
+            plugins,
+            plugin: config.metrics.plugin.clone(),
+            sampling_interval: config.sampling_interval(),
+            profiles,
+            profile,
+            paused: false,
+            crt: config.effects.crt,
+            borderless: config.borderless,
+            widget: config.widget,
+            click_through: config.click_through,
+            baseline: false,
+            hidden: false,
+            level: None,
+        }
+    }
+
+    /// Takes over the settings after a profile switch or config change.
+    pub fn sync(&mut self, config: &Config) {
+        self.size_mode = config.size;
+        self.theme = config.active_theme().map(String::from);
+        self.layout = config.layout;
+        self.metric = config.metric;
+        self.plugin = config.metrics.plugin.clone();
+        self.sampling_interval = config.sampling_interval();
+        self.crt = config.effects.crt;
+        self.borderless = config.borderless;
+        self.widget = config.widget;
+        self.click_through = config.click_through;
+    }
+
+    /// Entries of the metric menu: the built-in metrics, then one per plugin.
+    fn metric_options(&self) -> Vec<(Metric, Option<String>)> {
+        Metric::ALL
+            .into_iter()
+            .filter(|m| !m.needs_setup())
+            .map(|m| (m, None))
+            .chain(self.plugins.iter().map(|name| (Metric::Plugin, Some(name.clone()))))
+            .collect()
+    }
+
+    fn send(&self, event: AppEvent) {
+        // Schlägt nur fehl, wenn die Event-Loop schon beendet ist
+        let _ = self.proxy.send_event(event);
+    }
+}
+
+impl ksni::Tray for LampTray {
+    fn id(&self) -> String {
+        env!("CARGO_PKG_NAME").into()
+    }
+
+    fn title(&self) -> String {
+        tr!("tray-title")
+    }
+
+    /// Only until the first sample; most hosts prefer the name over the pixmap.
+    fn icon_name(&self) -> String {
+        match self.level {
+            Some(_) => String::new(),
+            None => "utilities-system-monitor".into(),
+        }
+    }
+
+    fn icon_pixmap(&self) -> Vec<Icon> {
+        let Some((percent, tier)) = self.level else { return Vec::new() };
+        // Hosts pick the size closest to their panel; scaled digits would blur
+        ICON_SIZES.iter().map(|&size| render_icon(size, percent, tier)).collect()
+    }
+
+    /// A click on the icon hides the window to the tray or brings it back.
+    fn activate(&mut self, _x: i32, _y: i32) {
+        self.hidden = !self.hidden;
+        self.send(AppEvent::ToggleWindow);
+    }
+
+    fn tool_tip(&self) -> ToolTip {
+        ToolTip {
+            title: tr!("tray-title"),
+            description: match self.level {
+                Some((percent, _)) => {
+                    tr!("tray-tooltip", metric = self.metric.description(), percent = format!("{:.1}", percent))
+                }
+                None => String::new(),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        let size_group = RadioGroup {
+            selected: WindowSizeMode::ALL
+                .iter()
+                .position(|m| *m == self.size_mode)
+                .unwrap_or(0),
+            select: Box::new(|tray: &mut Self, index| {
+                tray.size_mode = WindowSizeMode::ALL[index];
+                tray.send(AppEvent::SetSizeMode(tray.size_mode));
+            }),
+            options: WindowSizeMode::ALL
+                .iter()
+                .map(|m| RadioItem { label: m.description().into(), ..Default::default() })
+                .collect(),
+        };
+
+        // Index 0 ist das klassische Theme aus assets/, danach die Unterordner von assets/themes/
+        let theme_group = RadioGroup {
+            selected: match &self.theme {
+                Some(theme) => self.themes.iter().position(|t| t == theme).map_or(0, |i| i + 1),
+                None => 0,
+            },
+            select: Box::new(|tray: &mut Self, index| {
+                tray.theme = if index == 0 { None } else { tray.themes.get(index - 1).cloned() };
+                tray.send(AppEvent::SetTheme(tray.theme.clone()));
+            }),
+            options: std::iter::once(tr!("theme-classic"))
+                .chain(self.themes.iter().cloned())
+                .map(|label| RadioItem { label, ..Default::default() })
+                .collect(),
+        };
+
+        let layout_group = RadioGroup {
+            selected: Layout::ALL.iter().position(|l| *l == self.layout).unwrap_or(0),
+            select: Box::new(|tray: &mut Self, index| {
+                tray.layout = Layout::ALL[index];
+                tray.send(AppEvent::SetLayout(tray.layout));
+            }),
+            options: Layout::ALL
+                .iter()
+                .map(|l| RadioItem { label: l.description(), ..Default::default() })
+                .collect(),
+        };
+
+        let metric_options = self.metric_options();
+        let metric_group = RadioGroup {
+            selected: metric_options
+                .iter()
+                .position(|(m, plugin)| *m == self.metric && (*m != Metric::Plugin || *plugin == self.plugin))
+                .unwrap_or(0),
+            select: Box::new(|tray: &mut Self, index| {
+                let Some((metric, plugin)) = tray.metric_options().into_iter().nth(index) else { return };
+                tray.metric = metric;
+                match plugin {
+                    Some(name) => {
+                        tray.plugin = Some(name.clone());
+                        tray.send(AppEvent::SetPluginMetric(name));
+                    }
+                    None => tray.send(AppEvent::SetMetric(metric)),
+                }
+            }),
+            options: metric_options
+                .into_iter()
+                .map(|(m, plugin)| RadioItem {
+                    label: plugin.map_or_else(|| m.description(), |name| format!("{}: {}", m.description(), name)),
+                    ..Default::default()
+                })
+                .collect(),
+        };
+
+        // Ein Intervall aus der Config, das keine der Vorgaben ist, steht als eigener Eintrag am Ende
+        let custom = !SAMPLING_INTERVALS.contains(&self.sampling_interval);
+        let interval_group = RadioGroup {
+            selected: SAMPLING_INTERVALS
+                .iter()
+                .position(|d| *d == self.sampling_interval)
+                .unwrap_or(SAMPLING_INTERVALS.len()),
+            select: Box::new(|tray: &mut Self, index| {
+                let Some(interval) = SAMPLING_INTERVALS.get(index) else { return };
+                tray.sampling_interval = *interval;
+                tray.send(AppEvent::SetSamplingInterval(tray.sampling_interval));
+            }),
+            options: SAMPLING_INTERVALS
+                .iter()
+                .map(|d| format_interval(*d))
+                .chain(custom.then(|| tr!("tray-interval-custom", interval = format_interval(self.sampling_interval))))
+                .map(|label| RadioItem { label, ..Default::default() })
+                .collect(),
+        };
+
+        // Index 0 sind die Grundeinstellungen ohne Profil
+        let profile_group = RadioGroup {
+            selected: match &self.profile {
+                Some(profile) => self.profiles.iter().position(|p| p == profile).map_or(0, |i| i + 1),
+                None => 0,
+            },
+            select: Box::new(|tray: &mut Self, index| {
+                tray.profile = if index == 0 { None } else { tray.profiles.get(index - 1).cloned() };
+                tray.send(AppEvent::SetProfile(tray.profile.clone()));
+            }),
+            options: std::iter::once(tr!("profile-default"))
+                .chain(self.profiles.iter().cloned())
+                .map(|label| RadioItem { label, ..Default::default() })
+                .collect(),
+        };
+
+        vec![
+            StandardItem {
+                label: if self.hidden { tr!("tray-show") } else { tr!("tray-hide") },
+                activate: Box::new(|tray: &mut Self| {
+                    tray.hidden = !tray.hidden;
+                    tray.send(AppEvent::ToggleWindow);
+                }),
+                ..Default::default()
+            }
+            .into(),
+            SubMenu {
+                label: tr!("tray-profile"),
+                submenu: vec![profile_group.into()],
+                visible: !self.profiles.is_empty(),
+                ..Default::default()
+            }
+            .into(),
+            SubMenu { label: tr!("tray-size"), submenu: vec![size_group.into()], ..Default::default() }.into(),
+            SubMenu { label: tr!("tray-theme"), submenu: vec![theme_group.into()], ..Default::default() }.into(),
+            SubMenu { label: tr!("tray-layout"), submenu: vec![layout_group.into()], ..Default::default() }.into(),
+            SubMenu { label: tr!("tray-metric"), submenu: vec![metric_group.into()], ..Default::default() }.into(),
+            SubMenu {
+                label: tr!("tray-sampling-interval"),
+                submenu: vec![interval_group.into()],
+                ..Default::default()
+            }
+            .into(),
+            CheckmarkItem {
+                label: tr!("tray-crt"),
+                checked: self.crt,
+                activate: Box::new(|tray: &mut Self| tray.send(AppEvent::ToggleCrt)),
+                ..Default::default()
+            }
+            .into(),
+            CheckmarkItem {
+                label: tr!("tray-borderless"),
+                checked: self.borderless,
+                activate: Box::new(|tray: &mut Self| tray.send(AppEvent::ToggleBorderless)),
+                ..Default::default()
+            }
+            .into(),
+            CheckmarkItem {
+                label: tr!("tray-widget"),
+                checked: self.widget,
+                activate: Box::new(|tray: &mut Self| tray.send(AppEvent::ToggleWidget)),
+                ..Default::default()
+            }
+            .into(),
+            CheckmarkItem {
+                label: tr!("tray-click-through"),
+                checked: self.click_through,
+                activate: Box::new(|tray: &mut Self| tray.send(AppEvent::ToggleClickThrough)),
+                ..Default::default()
+            }
+            .into(),
+            CheckmarkItem {
+                label: tr!("tray-baseline"),
+                checked: self.baseline,
+                activate: Box::new(|tray: &mut Self| tray.send(AppEvent::ToggleBaseline)),
+                ..Default::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            CheckmarkItem {
+                label: tr!("tray-pause"),
+                checked: self.paused,
+                activate: Box::new(|tray: &mut Self| {
+                    tray.paused = !tray.paused;
+                    tray.send(AppEvent::TogglePause);
+                }),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: tr!("tray-quit"),
+                icon_name: "application-exit".into(),
+                activate: Box::new(|tray: &mut Self| tray.send(AppEvent::Quit)),
+                ..Default::default()
+            }
+            .into(),
+        ]
+    }
+}
+
+/// Sizes of the rendered icon, in pixels.
+const ICON_SIZES: [usize; 2] = [16, 32];
+
+fn render_icon(size: usize, percent: f64, tier: Tier) -> Icon {
+    let data = render_icon_rgba(size, percent, tier).chunks_exact(4).flat_map(|p| [p[3], p[0], p[1], p[2]]).collect();
+    Icon { width: size as i32, height: size as i32, data }
+}
+
+/// A block in the lava color with the percentage on it, capped at two
+/// digits: `size`×`size` RGBA pixels, the corners transparent.
+pub fn render_icon_rgba(size: usize, percent: f64, tier: Tier) -> Vec<u8> {
+    let mut rgba = vec![0u8; size * size * 4];
+    let color = tier.debug_color();
+    let last = size - 1;
+    for y in 0..size {
+        for x in 0..size {
+            // Abgeschnittene Ecken, damit der Block nicht wie ein Fehlerbild aussieht
+            if (x == 0 || x == last) && (y == 0 || y == last) {
+                continue;
+            }
+            rgba[(y * size + x) * 4..][..4].copy_from_slice(&color);
+        }
+    }
+
+    let text = format!("{:.0}", percent.clamp(0.0, 99.0));
+    let scale = (size / 16).max(1);
+    let x = size.saturating_sub(overlay::text_width(&text, scale)) / 2;
+    let y = size.saturating_sub(overlay::GLYPH_HEIGHT * scale) / 2;
+    // Gelb und Grün sind hell, Weiß darauf wäre kaum lesbar
+    let [r, g, b, _] = color;
+    let luminance = 0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64;
+    let ink = if luminance > 140.0 { [0, 0, 0] } else { [255, 255, 255] };
+    overlay::draw_text(&mut rgba, size, x, y, scale, &text, ink);
+    rgba
+}
+
+/// Whole seconds as "2 s", everything else in milliseconds ("1500 ms"), so
+/// an interval from the config is shown exactly.
+fn format_interval(interval: Duration) -> String {
+    if interval < Duration::from_secs(1) || interval.subsec_millis() != 0 {
+        format!("{} ms", interval.as_millis())
+    } else {
+        format!("{} s", interval.as_secs())
+    }
+}
+
+/// Starts the tray service. Returns `None` when the desktop has no
+/// StatusNotifierItem host; see [`crate::xembed`] for the fallback.
+pub fn spawn(tray: LampTray) -> Option<Handle<LampTray>> {
+    match tray.spawn() {
+        Ok(handle) => {
+            log::info!("{}", tr!("tray-started"));
+            Some(handle)
+        }
+        Err(e) => {
+            log::error!("{}", tr!("tray-unavailable", error = e));
+            None
+        }
+    }
+}
+
+/// Keeps the tray's copy of the settings in sync after changes made elsewhere (e.g. hotkeys).
+pub fn update<F: FnOnce(&mut LampTray)>(handle: &Option<Handle<LampTray>>, f: F) {
+    if let Some(handle) = handle {
+        handle.update(f);
+    }
+}
@@ -0,0 +1,69 @@
+//! Reloads the config while running: the file is polled for changes, and on
+//! Unix `SIGHUP` forces a reload (`pkill -HUP ram-lavalampe`). `SIGUSR1`
+//! dumps the current state to the log (`pkill -USR1 ram-lavalampe`).
+
+use std::fs;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use winit::event_loop::EventLoopProxy;
+
+use crate::config::config_path;
+use crate::AppEvent;
+
+/// Polling is cheap (one `stat`) and works the same on every filesystem and editor.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Starts the watcher threads. They end on their own once the event loop is gone.
+pub fn spawn(proxy: EventLoopProxy<AppEvent>) {
+    #[cfg(unix)]
+    spawn_signal_handler(proxy.clone());
+
+    let _ = thread::Builder::new().name("config-watch".into()).spawn(move || {
+        // Größe mitprüfen: manche Editoren speichern zweimal innerhalb derselben Sekunde
+        let stamp = || {
+            let metadata = fs::metadata(config_path()?).ok()?;
+            Some((metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH), metadata.len()))
+        };
+
+        let mut last = stamp();
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = stamp();
+            // A deleted file (or one being replaced) is not a change worth reloading
+            if current.is_some() && current != last && proxy.send_event(AppEvent::ReloadConfig).is_err() {
+                return;
+            }
+            last = current;
+        }
+    });
+}
+
+#[cfg(unix)]
+fn spawn_signal_handler(proxy: EventLoopProxy<AppEvent>) {
+    use crate::i18n::tr;
+    use signal_hook::{
+        consts::{SIGHUP, SIGUSR1},
+        iterator::Signals,
+    };
+
+    let mut signals = match Signals::new([SIGHUP, SIGUSR1]) {
+        Ok(signals) => signals,
+        Err(e) => {
+            log::error!("{}", tr!("sighup-failed", error = e));
+            return;
+        }
+    };
+    let _ = thread::Builder::new().name("sighup".into()).spawn(move || {
+        for signal in signals.forever() {
+            let event = if signal == SIGUSR1 {
+                AppEvent::DumpState
+            } else {
+                log::info!("{}", tr!("sighup-received"));
+                AppEvent::ReloadConfig
+            };
+            if proxy.send_event(event).is_err() {
+                return;
+            }
+        }
+    });
+}
@@ -0,0 +1,96 @@
+//! Render watchdog: when presenting frames keeps failing (a GPU reset loop, a
+//! surface that's gone for good), the renderer is torn down and built again,
+//! after repeated restarts on the software path (WARP, llvmpipe/lavapipe).
+//! Sampling, alerts, the tray and the log don't depend on the renderer and
+//! keep running throughout; if nothing helps, the lamp stops drawing but
+//! keeps monitoring.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Failed frames in a row before the renderer is rebuilt; single hiccups
+/// (a surface timeout while the screen locks) aren't worth it.
+const FAILURES_BEFORE_RESTART: u32 = 3;
+/// Restarts are counted over this long.
+const RESTART_WINDOW: Duration = Duration::from_secs(60);
+/// Restarts within the window before switching to the software path.
+const RESTARTS_BEFORE_SOFTWARE: usize = 2;
+/// Restarts within the window before giving up on drawing.
+const MAX_RESTARTS: usize = 5;
+
+/// The `[renderer]` config section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RendererConfig {
+    /// Rebuild the renderer when frames keep failing.
+    pub watchdog: bool,
+    /// Switch to software rendering after repeated restarts (or when no GPU works at startup).
+    pub software_fallback: bool,
+    /// Render in software from the start; read at startup.
+    pub software: bool,
+}
+
+impl Default for RendererConfig {
+    fn default() -> Self {
+        RendererConfig { watchdog: true, software_fallback: true, software: false }
+    }
+}
+
+/// What to do after a failed frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Try again with the next frame.
+    Retry,
+    /// Build a new renderer, in software or not.
+    Restart { software: bool },
+    /// Stop drawing; monitoring goes on.
+    GiveUp,
+    /// The watchdog is off: quit.
+    Quit,
+}
+
+#[derive(Default)]
+pub struct Watchdog {
+    failures: u32,
+    restarts: Vec<Instant>,
+    software: bool,
+    gave_up: bool,
+}
+
+impl Watchdog {
+    pub fn new(software: bool) -> Self {
+        Watchdog { software, ..Default::default() }
+    }
+
+    pub fn succeeded(&mut self) {
+        self.failures = 0;
+    }
+
+    pub fn failed(&mut self, config: &RendererConfig) -> Verdict {
+        if !config.watchdog {
+            return Verdict::Quit;
+        }
+        self.failures += 1;
+        if self.failures < FAILURES_BEFORE_RESTART {
+            return Verdict::Retry;
+        }
+        self.failures = 0;
+        let now = Instant::now();
+        self.restarts.retain(|at| now.duration_since(*at) < RESTART_WINDOW);
+        self.restarts.push(now);
+        if self.restarts.len() > MAX_RESTARTS {
+            self.gave_up = true;
+            return Verdict::GiveUp;
+        }
+        // Neustarts in Folge: der Treiber kommt nicht wieder auf die Beine
+        if config.software_fallback && self.restarts.len() > RESTARTS_BEFORE_SOFTWARE {
+            self.software = true;
+        }
+        Verdict::Restart { software: self.software }
+    }
+
+    /// Drawing was given up; only sampling goes on.
+    pub fn gave_up(&self) -> bool {
+        self.gave_up
+    }
+}
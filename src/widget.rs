@@ -0,0 +1,149 @@
+//! Window behavior for using the lamp as a desktop widget: always on top,
+//! left out of the taskbar, shown on every workspace, click-through.
+//! `--widget` switches the useful ones on at once (see [`crate::cli`]).
+//!
+//! Staying on top works wherever winit does. The taskbar and workspaces are
+//! platform features: on X11 they're window manager states set with
+//! `wmctrl`, Windows only has the taskbar, macOS the Dock icon and Spaces.
+//! Wayland leaves all of it to the compositor.
+
+use winit::error::ExternalError;
+use winit::window::{Window, WindowLevel};
+
+use crate::config::Config;
+use crate::i18n::tr;
+
+/// Applies every widget setting that is switched on, at startup.
+pub fn apply(window: &Window, config: &Config) {
+    if config.always_on_top {
+        set_always_on_top(window, true);
+    }
+    if config.skip_taskbar {
+        set_skip_taskbar(window, true);
+    }
+    if config.sticky {
+        set_sticky(window, true);
+    }
+    if config.click_through {
+        set_click_through(window, true);
+    }
+}
+
+pub fn set_always_on_top(window: &Window, on: bool) {
+    window.set_window_level(if on { WindowLevel::AlwaysOnTop } else { WindowLevel::Normal });
+}
+
+pub fn set_skip_taskbar(window: &Window, on: bool) {
+    if let Err(error) = platform::set_skip_taskbar(window, on) {
+        log::warn!("{}", tr!("widget-skip-taskbar-failed", error = error));
+    }
+}
+
+pub fn set_sticky(window: &Window, on: bool) {
+    if let Err(error) = platform::set_sticky(window, on) {
+        log::warn!("{}", tr!("widget-sticky-failed", error = error));
+    }
+}
+
+/// Lets clicks fall through to whatever is below. The lamp can't be moved
+/// with the mouse then, it doesn't get the clicks either.
+pub fn set_click_through(window: &Window, on: bool) {
+    match window.set_cursor_hittest(!on) {
+        Ok(()) => {}
+        // Ausschalten auf einer Plattform ohne Unterstützung ist kein Fehler
+        Err(_) if !on => {}
+        // X11 kann es grundsätzlich nicht, das ist keine Warnung wert
+        Err(ExternalError::NotSupported(_)) => log::info!("{}", tr!("widget-click-through-unsupported")),
+        Err(error) => log::warn!("{}", tr!("widget-click-through-failed", error = error)),
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use winit::platform::windows::WindowExtWindows;
+    use winit::window::Window;
+
+    use crate::i18n::tr;
+
+    pub fn set_skip_taskbar(window: &Window, on: bool) -> Result<(), String> {
+        window.set_skip_taskbar(on);
+        Ok(())
+    }
+
+    /// Virtual desktops have no public API for pinning a window.
+    pub fn set_sticky(_window: &Window, on: bool) -> Result<(), String> {
+        if on {
+            return Err(tr!("widget-unsupported"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc::runtime::{Object, BOOL};
+    use objc::{class, msg_send, sel, sel_impl};
+    use winit::platform::macos::WindowExtMacOS;
+    use winit::window::Window;
+
+    /// `NSApplicationActivationPolicyRegular` / `...Accessory`
+    const POLICY_REGULAR: i64 = 0;
+    const POLICY_ACCESSORY: i64 = 1;
+    /// `NSWindowCollectionBehaviorCanJoinAllSpaces`
+    const CAN_JOIN_ALL_SPACES: u64 = 1 << 0;
+
+    /// There's no taskbar; the closest thing is the Dock icon.
+    pub fn set_skip_taskbar(_window: &Window, on: bool) -> Result<(), String> {
+        let policy = if on { POLICY_ACCESSORY } else { POLICY_REGULAR };
+        unsafe {
+            let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+            let _: BOOL = msg_send![app, setActivationPolicy: policy];
+        }
+        Ok(())
+    }
+
+    pub fn set_sticky(window: &Window, on: bool) -> Result<(), String> {
+        let ns_window = window.ns_window() as *mut Object;
+        unsafe {
+            let behavior: u64 = msg_send![ns_window, collectionBehavior];
+            let behavior = if on { behavior | CAN_JOIN_ALL_SPACES } else { behavior & !CAN_JOIN_ALL_SPACES };
+            let _: () = msg_send![ns_window, setCollectionBehavior: behavior];
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod platform {
+    use std::process::Command;
+    use winit::platform::x11::WindowExtX11;
+    use winit::window::Window;
+
+    use crate::i18n::tr;
+
+    pub fn set_skip_taskbar(window: &Window, on: bool) -> Result<(), String> {
+        // Pager gleich mit, sonst taucht die Lampe in der Arbeitsflächen-Vorschau auf
+        wm_state(window, "skip_taskbar,skip_pager", on)
+    }
+
+    pub fn set_sticky(window: &Window, on: bool) -> Result<(), String> {
+        wm_state(window, "sticky", on)
+    }
+
+    /// Adds or removes `_NET_WM_STATE` flags.
+    fn wm_state(window: &Window, states: &str, on: bool) -> Result<(), String> {
+        let Some(id) = window.xlib_window() else {
+            // Wayland: nichts zu tun, wenn es nur ums Ausschalten geht
+            return if on { Err(tr!("widget-unsupported")) } else { Ok(()) };
+        };
+        let action = if on { "add" } else { "remove" };
+        let status = Command::new("wmctrl")
+            .args(["-i", "-r", &format!("0x{:x}", id), "-b", &format!("{},{}", action, states)])
+            .status()
+            .map_err(|e| tr!("widget-wmctrl-missing", error = e))?;
+        if !status.success() {
+            return Err(tr!("widget-wmctrl-failed", status = status.to_string()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+//! Fallback tray icon for X11 panels that only know the old XEmbed system
+//! tray (fluxbox, i3bar without SNI, stalonetray, ...), used when no
+//! StatusNotifierItem host is running.
+//!
+//! The icon is a small window the panel embeds. It shows the same live
+//! percentage as the SNI icon; a left click pauses or resumes, or brings
+//! back a window hidden to the tray. There is no menu, that needs a
+//! StatusNotifierItem host.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use winit::event_loop::EventLoopProxy;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{
+    AtomEnum, BackPixmap, ChangeWindowAttributesAux, ClientMessageEvent, ConnectionExt, CreateGCAux,
+    CreateWindowAux, EventMask, ImageFormat, PropMode, WindowClass,
+};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+use x11rb::CURRENT_TIME;
+
+use crate::i18n::tr;
+use crate::tier::Tier;
+use crate::tray;
+use crate::AppEvent;
+
+/// `SYSTEM_TRAY_REQUEST_DOCK` opcode of `_NET_SYSTEM_TRAY_OPCODE`.
+const REQUEST_DOCK: u32 = 0;
+/// `XEMBED_MAPPED`: the panel should show the icon right away.
+const XEMBED_MAPPED: u32 = 1;
+/// Size until the panel tells us its own.
+const DEFAULT_SIZE: u16 = 22;
+
+/// The embedded icon window. Drawing happens from the event loop (new
+/// sample) and from the icon's own thread (the panel asks for a redraw).
+pub struct XembedTray {
+    icon: Arc<Icon>,
+}
+
+struct Icon {
+    conn: RustConnection,
+    window: u32,
+    gc: u32,
+    depth: u8,
+    state: Mutex<State>,
+}
+
+struct State {
+    size: (u16, u16),
+    level: Option<(f64, Tier)>,
+    /// Rounded percentage and tier last drawn.
+    drawn: Option<(i64, Tier)>,
+}
+
+impl XembedTray {
+    pub fn show(&self, percent: f64, tier: Tier) {
+        let mut state = self.icon.state.lock().unwrap();
+        state.level = Some((percent, tier));
+        // Pro Sample neu zeichnen wäre zu viel Verkehr für eine Zahl, die sich selten ändert
+        if state.drawn != Some((percent.round() as i64, tier)) {
+            self.icon.draw(&mut state);
+        }
+    }
+}
+
+impl Icon {
+    fn draw(&self, state: &mut State) {
+        let Some((percent, tier)) = state.level else { return };
+        let (width, height) = state.size;
+        let size = width.min(height).max(1);
+        let (x, y) = ((width - size) / 2, (height - size) / 2);
+        let rgba = tray::render_icon_rgba(size as usize, percent, tier);
+        // 24-Bit-Visuals liegen als BGRX im Speicher
+        let bgrx: Vec<u8> = rgba.chunks_exact(4).flat_map(|p| [p[2], p[1], p[0], 0]).collect();
+
+        match self.paint(size, x as i16, y as i16, &bgrx) {
+            Ok(()) => state.drawn = Some((percent.round() as i64, tier)),
+            Err(e) => log::debug!("{}", tr!("xembed-failed", error = e)),
+        }
+    }
+
+    /// Puts a `size`×`size` image at (`x`, `y`), with the panel showing
+    /// through the window around it and the image's cut-off corners.
+    fn paint(&self, size: u16, x: i16, y: i16, bgrx: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        // Leeren zeigt den Panel-Hintergrund (ParentRelative)
+        self.conn.clear_area(false, self.window, 0, 0, 0, 0)?;
+        self.conn.put_image(ImageFormat::Z_PIXMAP, self.window, self.gc, size, size, x, y, 0, self.depth, bgrx)?;
+        let last = size as i16 - 1;
+        for (cx, cy) in [(0, 0), (last, 0), (0, last), (last, last)] {
+            self.conn.clear_area(false, self.window, x + cx, y + cy, 1, 1)?;
+        }
+        self.conn.flush()?;
+        Ok(())
+    }
+}
+
+/// Connects to the X server and docks into the system tray, now or as soon
+/// as a panel with one starts. `None` without X11 (Wayland, no `DISPLAY`).
+pub fn spawn(proxy: EventLoopProxy<AppEvent>) -> Option<XembedTray> {
+    match connect() {
+        Ok((icon, atoms)) => {
+            let icon = Arc::new(icon);
+            let thread_icon = icon.clone();
+            let _ = thread::Builder::new().name("xembed".into()).spawn(move || {
+                if let Err(e) = run(&thread_icon, &atoms, &proxy) {
+                    log::error!("{}", tr!("xembed-failed", error = e));
+                }
+            });
+            Some(XembedTray { icon })
+        }
+        Err(e) => {
+            log::error!("{}", tr!("xembed-failed", error = e));
+            None
+        }
+    }
+}
+
+struct Atoms {
+    selection: u32,
+    opcode: u32,
+    manager: u32,
+}
+
+fn connect() -> Result<(Icon, Atoms), Box<dyn std::error::Error>> {
+    let (conn, screen_num) = x11rb::connect(None)?;
+    let screen = &conn.setup().roots[screen_num];
+    let (root, depth, visual) = (screen.root, screen.root_depth, screen.root_visual);
+    let bits_per_pixel = conn.setup().pixmap_formats.iter().find(|f| f.depth == depth).map(|f| f.bits_per_pixel);
+    if depth != 24 || bits_per_pixel != Some(32) {
+        return Err(tr!("xembed-unsupported-visual", depth = depth).into());
+    }
+
+    let intern = |name: &str| -> Result<u32, Box<dyn std::error::Error>> {
+        Ok(conn.intern_atom(false, name.as_bytes())?.reply()?.atom)
+    };
+    let atoms = Atoms {
+        selection: intern(&format!("_NET_SYSTEM_TRAY_S{}", screen_num))?,
+        opcode: intern("_NET_SYSTEM_TRAY_OPCODE")?,
+        manager: intern("MANAGER")?,
+    };
+    let xembed_info = intern("_XEMBED_INFO")?;
+
+    let window = conn.generate_id()?;
+    conn.create_window(
+        depth,
+        window,
+        root,
+        0,
+        0,
+        DEFAULT_SIZE,
+        DEFAULT_SIZE,
+        0,
+        WindowClass::INPUT_OUTPUT,
+        visual,
+        &CreateWindowAux::new()
+            .background_pixmap(BackPixmap::PARENT_RELATIVE)
+            .event_mask(EventMask::EXPOSURE | EventMask::STRUCTURE_NOTIFY | EventMask::BUTTON_PRESS),
+    )?;
+    conn.change_property8(PropMode::REPLACE, window, AtomEnum::WM_NAME, AtomEnum::STRING, tr!("tray-title").as_bytes())?;
+    // Version 0, gleich sichtbar
+    conn.change_property32(PropMode::REPLACE, window, xembed_info, xembed_info, &[0, XEMBED_MAPPED])?;
+    let gc = conn.generate_id()?;
+    conn.create_gc(gc, window, &CreateGCAux::new())?;
+    // Ein Panel, das später startet, kündigt sich mit MANAGER auf dem Root-Fenster an
+    conn.change_window_attributes(root, &ChangeWindowAttributesAux::new().event_mask(EventMask::STRUCTURE_NOTIFY))?;
+    conn.flush()?;
+
+    let state = State { size: (DEFAULT_SIZE, DEFAULT_SIZE), level: None, drawn: None };
+    Ok((Icon { conn, window, gc, depth, state: Mutex::new(state) }, atoms))
+}
+
+/// Asks the current tray owner, if any, to embed the icon. `false` if there is none.
+fn dock(icon: &Icon, atoms: &Atoms) -> Result<bool, Box<dyn std::error::Error>> {
+    let owner = icon.conn.get_selection_owner(atoms.selection)?.reply()?.owner;
+    if owner == x11rb::NONE {
+        return Ok(false);
+    }
+    let message = ClientMessageEvent::new(32, owner, atoms.opcode, [CURRENT_TIME, REQUEST_DOCK, icon.window, 0, 0]);
+    icon.conn.send_event(false, owner, EventMask::NO_EVENT, message)?;
+    icon.conn.flush()?;
+    log::info!("{}", tr!("xembed-docked"));
+    Ok(true)
+}
+
+fn run(icon: &Icon, atoms: &Atoms, proxy: &EventLoopProxy<AppEvent>) -> Result<(), Box<dyn std::error::Error>> {
+    if !dock(icon, atoms)? {
+        log::info!("{}", tr!("xembed-waiting"));
+    }
+    loop {
+        match icon.conn.wait_for_event()? {
+            Event::Expose(event) if event.count == 0 => {
+                let mut state = icon.state.lock().unwrap();
+                icon.draw(&mut state);
+            }
+            Event::ConfigureNotify(event) if event.window == icon.window => {
+                let mut state = icon.state.lock().unwrap();
+                if state.size != (event.width, event.height) {
+                    state.size = (event.width, event.height);
+                    icon.draw(&mut state);
+                }
+            }
+            Event::ButtonPress(event) if event.detail == 1 => {
+                // Schlägt nur fehl, wenn die Event-Loop schon beendet ist
+                let Ok(()) = proxy.send_event(AppEvent::TrayClicked) else { return Ok(()) };
+            }
+            // Neues oder neu gestartetes Panel
+            Event::ClientMessage(event) if event.type_ == atoms.manager && event.data.as_data32()[1] == atoms.selection => {
+                dock(icon, atoms)?;
+            }
+            _ => {}
+        }
+    }
+}